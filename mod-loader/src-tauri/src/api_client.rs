@@ -5,7 +5,11 @@
 // Response format: JSON with statuscode property and mod.releases[0].mainfile containing the download URL
 // API docs: http://mods.vintagestory.at/api
 
+use crate::mod_source::{ModSource, ModSourceSpec};
+use crate::request_policy::{self, FetchOutcome, ResponseMeta};
 use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 // Custom deserializer for statuscode that can handle both string and number
 fn deserialize_statuscode<'de, D>(deserializer: D) -> Result<Option<u16>, D::Error>
@@ -89,204 +93,392 @@ struct ModApiResponse {
     mod_data: Option<ModApiData>, // Optional because API may return error
 }
 
+// The `mod` object returned by /api/mod/<modid>
 #[derive(Debug, Deserialize)]
 struct ModApiData {
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    modid: Option<u64>, // Numeric ModDB id, not the modinfo modid
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    text: Option<String>, // Description, HTML
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    urlalias: Option<String>,
+    #[serde(default)]
+    side: Option<String>,
+    #[serde(default, rename = "type")]
+    mod_type: Option<String>,
+    #[serde(default)]
+    logofile: Option<String>,
+    #[serde(default)]
+    homepageurl: Option<String>,
+    #[serde(default)]
+    sourcecodeurl: Option<String>,
+    #[serde(default)]
+    issuetrackerurl: Option<String>,
+    #[serde(default)]
+    wikiurl: Option<String>,
+    #[serde(default)]
+    trailervideourl: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    downloads: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    follows: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    comments: Option<u64>,
+    #[serde(default)]
+    created: Option<String>,
+    #[serde(default)]
+    lastmodified: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    tags: Vec<String>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    screenshots: Vec<ModScreenshot>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
     releases: Vec<ModRelease>,
 }
 
+// Full details of a ModDB mod, as returned by `get_mod_details`
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub(crate) struct ModRelease {
-    mainfile: String, // Full URI to download file - always respect full URIs returned by API
+pub struct ModDetails {
+    pub id: Option<u64>,       // Numeric ModDB id
+    pub modid: Option<String>, // modinfo modid, taken from the newest release
+    pub name: Option<String>,
+    pub description: Option<String>, // HTML as written on the ModDB
+    pub authors: Vec<String>,
+    pub side: Option<String>, // "client", "server" or "both"
+    pub mod_type: Option<String>,
+    pub tags: Vec<String>,
+    pub downloads: Option<u64>,
+    pub follows: Option<u64>,
+    pub comments: Option<u64>,
+    pub logo_url: Option<String>,
+    pub screenshots: Vec<ModScreenshot>,
+    pub page_url: Option<String>,
+    pub homepage_url: Option<String>,
+    pub source_url: Option<String>,
+    pub issue_tracker_url: Option<String>,
+    pub wiki_url: Option<String>,
+    pub trailer_video_url: Option<String>,
+    pub created: Option<String>,
+    pub last_modified: Option<String>,
+    pub releases: Vec<ModRelease>, // Newest first
+    pub fetch_outcome: FetchOutcome,
+    pub age_secs: u64, // Age of the data; non-zero when served from the response cache
 }
 
-#[tauri::command]
-pub async fn get_mod_download_url(
-    mod_id: String,
-    mod_url: Option<String>,
-) -> Result<String, String> {
-    use regex::Regex;
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModScreenshot {
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    pub fileid: Option<u64>,
+    #[serde(default)]
+    pub mainfile: Option<String>, // Full URI of the image
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub created: Option<String>,
+}
+
+// Empty strings from the API mean "not set"
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.filter(|v| !v.trim().is_empty())
+}
+
+impl ModDetails {
+    fn from_api(data: ModApiData, meta: ResponseMeta) -> Self {
+        let mut releases = data.releases;
+        releases.sort_by(|a, b| b.created.cmp(&a.created));
+
+        let authors = data
+            .author
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|a| !a.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        let page_url = non_empty(data.urlalias)
+            .map(|alias| format!("https://mods.vintagestory.at/{}", alias))
+            .or_else(|| {
+                data.modid
+                    .map(|id| format!("https://mods.vintagestory.at/show/mod/{}", id))
+            });
+
+        ModDetails {
+            id: data.modid,
+            modid: releases.iter().find_map(|r| non_empty(r.modidstr.clone())),
+            name: data.name,
+            description: non_empty(data.text),
+            authors,
+            side: non_empty(data.side),
+            mod_type: non_empty(data.mod_type),
+            tags: data.tags,
+            downloads: data.downloads,
+            follows: data.follows,
+            comments: data.comments,
+            logo_url: non_empty(data.logofile),
+            screenshots: data.screenshots,
+            page_url,
+            homepage_url: non_empty(data.homepageurl),
+            source_url: non_empty(data.sourcecodeurl),
+            issue_tracker_url: non_empty(data.issuetrackerurl),
+            wiki_url: non_empty(data.wikiurl),
+            trailer_video_url: non_empty(data.trailervideourl),
+            created: data.created,
+            last_modified: data.lastmodified,
+            releases,
+            fetch_outcome: meta.outcome,
+            age_secs: meta.age_secs,
+        }
+    }
+}
+
+// Treat an explicit `null` the same as a missing field
+fn deserialize_null_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+// Counters and ids arrive as numbers, numeric strings or null depending on the endpoint
+fn deserialize_lenient_u64<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        match Option::<serde_json::Value>::deserialize(deserializer)? {
+            Some(serde_json::Value::Number(n)) => n.as_u64(),
+            Some(serde_json::Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        },
+    )
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ModRelease {
+    pub mainfile: String, // Full URI to download file - always respect full URIs returned by API
+    #[serde(default)]
+    pub modversion: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_null_default")]
+    pub tags: Vec<String>, // Game versions the release is tagged for, e.g. "v1.19.8"
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    pub fileid: Option<u64>,
+    #[serde(default)]
+    pub created: Option<String>, // "YYYY-MM-DD HH:MM:SS", sorts chronologically as a string
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    pub releaseid: Option<u64>,
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub modidstr: Option<String>, // modinfo modid of this release
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    pub downloads: Option<u64>,
+    #[serde(default)]
+    pub changelog: Option<String>, // HTML
+    // modid -> minimum version, from sources that publish dependencies with
+    // their releases; the ModDB API does not, so there they are in the zip
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<BTreeMap<String, String>>,
+}
+
+impl ModRelease {
+    pub(crate) fn supports_game_version(&self, game_version: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|tag| crate::version::same_version(tag, game_version))
+    }
+
+    pub(crate) fn version_label(&self) -> &str {
+        self.modversion.as_deref().unwrap_or("unknown")
+    }
+}
 
+const MOD_DB_API_URL: &str = "http://mods.vintagestory.at/api";
+
+// `mod_ref` is a modid or a numeric ModDB id; the API accepts both
+fn mod_api_url(mod_ref: &str) -> String {
+    format!("{}/mod/{}", MOD_DB_API_URL, mod_ref)
+}
+
+// Fetch the `mod` object for a mod from /api/mod/<modid>
+async fn fetch_mod_data(mod_ref: &str) -> Result<(ModApiData, ResponseMeta), String> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
-    // Try to construct the API URL
-    // API base URL: http://mods.vintagestory.at/api (per API docs)
-    // Endpoint: /api/mod/<modid> where modid can be numeric ID or modid string
-    let api_url = if let Some(url) = mod_url {
-        // If it's already an API URL, use it (handle both http and https)
-        if url.contains("/api/mod/") {
-            // Ensure we use http:// as per API docs (though https may also work)
-            url.replace(
-                "https://mods.vintagestory.at/api/mod/",
-                "http://mods.vintagestory.at/api/mod/",
-            )
-        } else if url.contains("/api/mods/") {
-            // Old format - convert to new format
-            let mod_id_from_url = url.split("/api/mods/").last().unwrap_or(&mod_id);
-            format!("http://mods.vintagestory.at/api/mod/{}", mod_id_from_url)
-        } else if url.contains("/show/mod/") {
-            // Convert page URL to API URL
-            let mod_id_from_url = url.split("/show/mod/").last().unwrap_or(&mod_id);
-            format!("http://mods.vintagestory.at/api/mod/{}", mod_id_from_url)
-        } else if url.contains("mods.vintagestory.at") {
-            // Try to extract mod ID from URL or use provided mod_id
-            format!("http://mods.vintagestory.at/api/mod/{}", mod_id)
-        } else {
-            format!("http://mods.vintagestory.at/api/mod/{}", mod_id)
-        }
-    } else {
-        format!("http://mods.vintagestory.at/api/mod/{}", mod_id)
-    };
+    let api_url = mod_api_url(mod_ref);
+    eprintln!("[fetch_mod_data] Fetching mod API: {}", api_url);
 
-    eprintln!("[get_mod_download_url] Fetching mod API: {}", api_url);
+    let (text, meta) = request_policy::get_text(&client, &api_url).await?;
 
-    // Try API endpoint first
-    match client
-        .get(&api_url)
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
+    // API returns JSON with statuscode property and mod data
+    let api_response = serde_json::from_str::<ModApiResponse>(&text).map_err(|e| {
+        format!(
+            "Failed to parse API JSON: {} (response: {})",
+            e,
+            text.chars().take(500).collect::<String>()
         )
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.text().await {
-                    Ok(text) => {
-                        // Try to parse as JSON API response
-                        // API returns JSON with statuscode property and mod data
-                        match serde_json::from_str::<ModApiResponse>(&text) {
-                            Ok(api_response) => {
-                                // Check statuscode if present (API uses HTTP error codes)
-                                if let Some(status) = api_response.status_code {
-                                    if status >= 400 {
-                                        eprintln!("[get_mod_download_url] API returned error statuscode: {}", status);
-                                        // Continue to fallback
-                                    }
-                                }
-
-                                // Get the first (latest) release's mainfile URL
-                                // API docs: "Always respect the full uris returned by the api"
-                                if let Some(mod_data) = api_response.mod_data {
-                                    if let Some(release) = mod_data.releases.first() {
-                                        let download_url = release.mainfile.clone();
-                                        eprintln!("[get_mod_download_url] Found download URL from API: {}", download_url);
-                                        return Ok(download_url);
-                                    } else {
-                                        eprintln!("[get_mod_download_url] No releases found in API response");
-                                    }
-                                } else {
-                                    eprintln!("[get_mod_download_url] No mod data in API response (may be error response)");
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!("[get_mod_download_url] Failed to parse API JSON: {}", e);
-                                eprintln!(
-                                    "[get_mod_download_url] Response text: {}",
-                                    &text.chars().take(500).collect::<String>()
-                                );
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("[get_mod_download_url] Failed to read API response: {}", e);
-                    }
-                }
-            } else {
-                eprintln!(
-                    "[get_mod_download_url] API returned status: {}",
-                    response.status()
-                );
-            }
-        }
-        Err(e) => {
-            eprintln!("[get_mod_download_url] Failed to fetch API: {}", e);
+    })?;
+
+    // Check statuscode if present (API uses HTTP error codes)
+    if let Some(status) = api_response.status_code {
+        if status >= 400 {
+            return Err(format!("API returned error statuscode: {}", status));
         }
     }
 
-    // Fallback: Try scraping the mod page HTML
-    let page_url = format!("https://mods.vintagestory.at/show/mod/{}", mod_id);
-    eprintln!(
-        "[get_mod_download_url] Falling back to scraping mod page: {}",
-        page_url
-    );
+    api_response
+        .mod_data
+        .map(|mod_data| (mod_data, meta))
+        .ok_or_else(|| "No mod data in API response".to_string())
+}
 
-    match client
-        .get(&page_url)
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
-        )
-        .send()
-        .await
+// Fetch the release list for a mod from /api/mod/<modid>
+pub(crate) async fn fetch_mod_releases(
+    mod_ref: &str,
+) -> Result<(Vec<ModRelease>, ResponseMeta), String> {
+    let (mod_data, meta) = fetch_mod_data(mod_ref).await?;
+    Ok((mod_data.releases, meta))
+}
+
+#[tauri::command]
+pub async fn get_mod_details(
+    mod_id: String,
+    mod_url: Option<String>,
+) -> Result<ModDetails, String> {
+    let mod_ref = match ModSourceSpec::infer(mod_url.as_deref()) {
+        ModSourceSpec::Moddb { id: Some(id) } => id,
+        _ => mod_id,
+    };
+    let (mod_data, meta) = fetch_mod_data(&mod_ref).await?;
+    Ok(ModDetails::from_api(mod_data, meta))
+}
+
+// Pick the newest release tagged for `game_version`, or simply the newest
+// release when the game version is unknown or no release is tagged at all.
+// When nothing is compatible the error explains which game versions the
+// newest release targets instead.
+pub(crate) fn select_release<'a>(
+    mod_id: &str,
+    releases: &'a [ModRelease],
+    game_version: Option<&str>,
+) -> Result<&'a ModRelease, String> {
+    // The API lists releases newest first; sorting by date as well keeps the
+    // choice right if that ever changes (the sort is stable for equal dates)
+    let mut newest_first: Vec<&ModRelease> = releases.iter().collect();
+    newest_first.sort_by(|a, b| b.created.cmp(&a.created));
+
+    let newest = *newest_first
+        .first()
+        .ok_or_else(|| format!("No releases found for {}", mod_id))?;
+
+    // Releases from hosts without game version tags cannot be judged either way
+    let tagged = newest_first.iter().any(|release| !release.tags.is_empty());
+    let game_version = match game_version {
+        Some(v) if tagged => v,
+        _ => return Ok(newest),
+    };
+
+    if let Some(release) = newest_first
+        .iter()
+        .find(|release| release.supports_game_version(game_version))
     {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.text().await {
-                    Ok(html) => {
-                        // Pattern 1: Direct download link with number
-                        let download_pattern =
-                            Regex::new(r#"/download/(\d+)/[^"'\s<>]+\.(zip|tar|tar\.gz)"#)
-                                .map_err(|e| format!("Failed to create regex: {}", e))?;
-
-                        for cap in download_pattern.captures_iter(&html) {
-                            if let Some(full_match) = cap.get(0) {
-                                let path = full_match.as_str();
-                                let download_url = if path.starts_with("http") {
-                                    path.to_string()
-                                } else if path.starts_with("/") {
-                                    format!("https://mods.vintagestory.at{}", path)
-                                } else {
-                                    format!("https://mods.vintagestory.at/{}", path)
-                                };
-                                eprintln!(
-                                    "[get_mod_download_url] Found download URL from HTML: {}",
-                                    download_url
-                                );
-                                return Ok(download_url);
-                            }
-                        }
-
-                        // Pattern 2: href attribute with download link
-                        let href_pattern =
-                            Regex::new(r#"href=["']([^"']*download[^"']*\.(zip|tar|tar\.gz))"#)
-                                .map_err(|e| format!("Failed to create href regex: {}", e))?;
-
-                        for cap in href_pattern.captures_iter(&html) {
-                            if let Some(url_match) = cap.get(1) {
-                                let mut url = url_match.as_str().to_string();
-                                if url.starts_with("/") {
-                                    url = format!("https://mods.vintagestory.at{}", url);
-                                } else if !url.starts_with("http") {
-                                    url = format!("https://mods.vintagestory.at/{}", url);
-                                }
-                                if url.contains("/download/") {
-                                    eprintln!("[get_mod_download_url] Found download URL from HTML (href): {}", url);
-                                    return Ok(url);
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("[get_mod_download_url] Failed to read HTML: {}", e);
-                    }
-                }
-            } else {
-                eprintln!(
-                    "[get_mod_download_url] Page returned status: {}",
-                    response.status()
-                );
-            }
-        }
-        Err(e) => {
-            eprintln!("[get_mod_download_url] Failed to fetch page: {}", e);
-        }
+        return Ok(release);
     }
 
-    Err(format!("Could not find download URL for {}", mod_id))
+    let newest_tags = if newest.tags.is_empty() {
+        "no game versions".to_string()
+    } else {
+        newest
+            .tags
+            .iter()
+            .map(|t| crate::version::normalize(t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    Err(format!(
+        "No release of {} is compatible with game version {} (newest release {} is tagged for {})",
+        mod_id,
+        crate::version::normalize(game_version),
+        newest.version_label(),
+        newest_tags
+    ))
+}
+
+// Find the release published as `version`. Pinned installs must stay
+// reproducible, so a version that was removed from the ModDB is an error
+// rather than a reason to pick some other release.
+pub(crate) fn find_release_by_version<'a>(
+    mod_id: &str,
+    releases: &'a [ModRelease],
+    version: &str,
+) -> Result<&'a ModRelease, String> {
+    let wanted = crate::version::normalize(version);
+
+    if let Some(release) = releases.iter().find(|r| {
+        r.modversion
+            .as_deref()
+            .is_some_and(|v| crate::version::same_version(v, version))
+    }) {
+        return Ok(release);
+    }
+
+    let available = releases
+        .iter()
+        .filter_map(|r| r.modversion.as_deref())
+        .take(10)
+        .collect::<Vec<_>>();
+
+    Err(format!(
+        "Version {} of {} is no longer available on the ModDB (available: {})",
+        wanted,
+        mod_id,
+        if available.is_empty() {
+            "none".to_string()
+        } else {
+            available.join(", ")
+        }
+    ))
+}
+
+// Empty, wildcard and "unknown" versions mean "no particular version"
+fn pinned_version(version: Option<&str>) -> Option<&str> {
+    version
+        .map(str::trim)
+        .filter(|v| !v.is_empty() && *v != "*" && *v != "unknown")
+}
+
+#[tauri::command]
+pub async fn get_mod_download_url(
+    mod_id: String,
+    mod_url: Option<String>,
+    version: Option<String>,
+    source: Option<ModSourceSpec>,
+) -> Result<String, String> {
+    // Packs without a recorded source fall back to what their URL looks like
+    let source = source.unwrap_or_else(|| ModSourceSpec::infer(mod_url.as_deref()));
+    let pinned = pinned_version(version.as_deref());
+    let game_version = crate::game_detector::current_game_version().await;
+
+    let resolved = source
+        .resolve(&mod_id, pinned, game_version.as_deref())
+        .await?;
+    eprintln!(
+        "[get_mod_download_url] Resolved {}@{} for game version {}: {}",
+        mod_id,
+        resolved.version.as_deref().unwrap_or("unknown"),
+        game_version.as_deref().unwrap_or("unknown"),
+        resolved.location
+    );
+    Ok(resolved.location)
 }
 
 // Structures for mod search API response
@@ -295,6 +487,20 @@ pub struct ModSearchResult {
     #[serde(rename = "statuscode", deserialize_with = "deserialize_statuscode")]
     status_code: Option<u16>,
     pub mods: Vec<ModSearchItem>,
+    // Set by us, not the API: where the result came from and how old it is
+    #[serde(default)]
+    pub fetch_outcome: Option<FetchOutcome>,
+    #[serde(default)]
+    pub age_secs: Option<u64>,
+    // Set by us, not the API: the page of the full result set `mods` holds
+    #[serde(default)]
+    pub total: usize,
+    #[serde(default)]
+    pub page: usize,
+    #[serde(default)]
+    pub per_page: usize,
+    #[serde(default)]
+    pub total_pages: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -308,13 +514,40 @@ pub struct ModSearchItem {
     pub category: Option<String>,
     pub tags: Option<Vec<String>>,
     pub releases: Option<Vec<ModRelease>>,
+    #[serde(default, deserialize_with = "deserialize_lenient_u64")]
+    pub downloads: Option<u64>,
 }
 
-#[tauri::command]
-pub async fn search_mods(
-    query: Option<String>,
-    page: Option<usize>,
-) -> Result<ModSearchResult, String> {
+const DEFAULT_SEARCH_PAGE_SIZE: usize = 20;
+const MAX_SEARCH_PAGE_SIZE: usize = 100;
+// The API has no pagination and its order is not guaranteed, so order the
+// full set ourselves: most downloaded first, then by name and id
+fn sort_search_results(mods: &mut [ModSearchItem]) {
+    mods.sort_by(|a, b| {
+        b.downloads
+            .unwrap_or(0)
+            .cmp(&a.downloads.unwrap_or(0))
+            .then_with(|| {
+                let name = |m: &ModSearchItem| m.name.as_deref().unwrap_or_default().to_lowercase();
+                name(a).cmp(&name(b))
+            })
+            .then_with(|| a.id.cmp(&b.id))
+            .then_with(|| a.modid.cmp(&b.modid))
+    });
+}
+
+// Page `page` (1-based) of `mods`; pages past the end are empty
+fn paginate(mods: &[ModSearchItem], page: usize, per_page: usize) -> Vec<ModSearchItem> {
+    mods.iter()
+        .skip((page - 1).saturating_mul(per_page))
+        .take(per_page)
+        .cloned()
+        .collect()
+}
+
+// The full, sorted result set of a query. Turning pages does not refetch, as
+// the response cache serves the same query again until it goes stale.
+async fn fetch_search_results(query: &str) -> Result<(Vec<ModSearchItem>, ResponseMeta), String> {
     let client = reqwest::Client::builder()
         .timeout(std::time::Duration::from_secs(15))
         .build()
@@ -323,75 +556,384 @@ pub async fn search_mods(
     // API endpoint: /api/mods
     // Parameters: text (search query), orderby, orderdirection
     let mut api_url = "http://mods.vintagestory.at/api/mods".to_string();
-    let mut params = Vec::new();
+    if !query.is_empty() {
+        api_url.push_str(&format!("?text={}", urlencoding::encode(query)));
+    }
 
-    if let Some(q) = query {
-        if !q.trim().is_empty() {
-            params.push(format!("text={}", urlencoding::encode(&q)));
-        }
+    eprintln!("[search_mods] Searching mods: {}", api_url);
+
+    let (text, meta) = request_policy::get_text(&client, &api_url)
+        .await
+        .map_err(|e| format!("Mod search failed: {}", e))?;
+    let result = serde_json::from_str::<ModSearchResult>(&text).map_err(|e| {
+        eprintln!(
+            "[search_mods] Response preview: {}",
+            &text.chars().take(500).collect::<String>()
+        );
+        format!("Failed to parse search result: {}", e)
+    })?;
+    if let Some(code) = result.status_code.filter(|code| *code != 200) {
+        return Err(format!(
+            "Mod search failed: the ModDB returned status {}",
+            code
+        ));
     }
 
-    // Add pagination if needed (API doesn't explicitly support page, but we can try)
-    if let Some(p) = page {
-        if p > 1 {
-            // Note: API docs don't mention pagination, but we'll include it in case
-            params.push(format!("page={}", p));
+    let mut mods = result.mods;
+    sort_search_results(&mut mods);
+    eprintln!("[search_mods] Found {} mods", mods.len());
+
+    Ok((mods, meta))
+}
+
+#[tauri::command]
+pub async fn search_mods(
+    query: Option<String>,
+    page: Option<usize>,
+    per_page: Option<usize>,
+) -> Result<ModSearchResult, String> {
+    let query = query.unwrap_or_default().trim().to_string();
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page
+        .unwrap_or(DEFAULT_SEARCH_PAGE_SIZE)
+        .clamp(1, MAX_SEARCH_PAGE_SIZE);
+
+    let (all_mods, meta) = fetch_search_results(&query).await?;
+
+    Ok(ModSearchResult {
+        status_code: Some(200),
+        mods: paginate(&all_mods, page, per_page),
+        fetch_outcome: Some(meta.outcome),
+        age_secs: Some(meta.age_secs),
+        total: all_mods.len(),
+        page,
+        per_page,
+        total_pages: all_mods.len().div_ceil(per_page),
+    })
+}
+
+// Number of attempts a download gets before giving up; each retry resumes
+// from the bytes already on disk
+const DOWNLOAD_ATTEMPTS: usize = 3;
+
+// How long a download may go without receiving anything before the attempt
+// counts as interrupted. There is no limit on the whole transfer, so large
+// files on slow connections still finish.
+const DOWNLOAD_CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+const DOWNLOAD_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+fn download_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .connect_timeout(DOWNLOAD_CONNECT_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+// Downloads land next to the mods they replace (so the final rename stays on
+// one filesystem) under a hidden name the mod scanner ignores. The URL hash
+// keeps a leftover part of one release from being resumed as another.
+pub(crate) fn partial_download_path(mods_dir: &Path, mod_id: &str, download_url: &str) -> PathBuf {
+    use sha2::{Digest, Sha256};
+
+    let url_hash = hex::encode(Sha256::digest(download_url.as_bytes()));
+    mods_dir.join(format!(".{}-{}.zip.part", mod_id, &url_hash[..12]))
+}
+
+// Stream `download_url` into `part_path`, resuming with a Range request from
+// whatever an interrupted attempt left behind. Returns the total size the
+// server announced, if any.
+async fn download_to_part_file(
+    client: &reqwest::Client,
+    download_url: &str,
+    part_path: &Path,
+) -> Result<Option<u64>, String> {
+    use reqwest::{header, StatusCode};
+    use std::io::Write;
+
+    let mut last_error = String::new();
+
+    for attempt in 1..=DOWNLOAD_ATTEMPTS {
+        let existing = std::fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+
+        let mut request = client.get(download_url);
+        if existing > 0 {
+            eprintln!(
+                "[download_mod] Resuming at byte {} (attempt {})",
+                existing, attempt
+            );
+            request = request.header(header::RANGE, format!("bytes={}-", existing));
+        }
+
+        let mut response = match tokio::time::timeout(DOWNLOAD_IDLE_TIMEOUT, request.send()).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(e)) => {
+                last_error = format!("Failed to download mod: {}", e);
+                eprintln!("[download_mod] {}", last_error);
+                continue;
+            }
+            Err(_) => {
+                last_error = "Download server did not respond".to_string();
+                eprintln!("[download_mod] {}", last_error);
+                continue;
+            }
+        };
+
+        let status = response.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // The partial file does not fit the remote file any more; start over
+            std::fs::remove_file(part_path)
+                .map_err(|e| format!("Failed to remove partial download: {}", e))?;
+            last_error = "Server rejected the resume range".to_string();
+            continue;
+        }
+        if status.is_server_error() {
+            last_error = format!("Download failed: {}", status);
+            eprintln!("[download_mod] {}", last_error);
+            continue;
+        }
+        if !status.is_success() {
+            return Err(format!("Download failed: {}", status));
+        }
+
+        // Servers that ignore Range answer 200 with the whole file
+        let resumed = status == StatusCode::PARTIAL_CONTENT;
+        let expected_size =
+            response
+                .content_length()
+                .map(|len| if resumed { existing + len } else { len });
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(part_path)
+            .map_err(|e| format!("Failed to open partial download: {}", e))?;
+
+        let mut interrupted = false;
+        loop {
+            match tokio::time::timeout(DOWNLOAD_IDLE_TIMEOUT, response.chunk()).await {
+                Ok(Ok(Some(chunk))) => file
+                    .write_all(&chunk)
+                    .map_err(|e| format!("Failed to write partial download: {}", e))?,
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => {
+                    last_error = format!("Download interrupted: {}", e);
+                    eprintln!("[download_mod] {}", last_error);
+                    interrupted = true;
+                    break;
+                }
+                Err(_) => {
+                    last_error = format!(
+                        "Download stalled: nothing received for {} seconds",
+                        DOWNLOAD_IDLE_TIMEOUT.as_secs()
+                    );
+                    eprintln!("[download_mod] {}", last_error);
+                    interrupted = true;
+                    break;
+                }
+            }
+        }
+
+        file.sync_all()
+            .map_err(|e| format!("Failed to flush partial download: {}", e))?;
+
+        let written = std::fs::metadata(part_path).map(|m| m.len()).unwrap_or(0);
+        if let Some(expected) = expected_size {
+            if written < expected {
+                last_error = format!(
+                    "Download interrupted: received {} of {} bytes",
+                    written, expected
+                );
+                interrupted = true;
+            }
+        }
+
+        if !interrupted {
+            return Ok(expected_size);
         }
     }
 
-    if !params.is_empty() {
-        api_url.push('?');
-        api_url.push_str(&params.join("&"));
+    Err(format!(
+        "{} (gave up after {} attempts)",
+        last_error, DOWNLOAD_ATTEMPTS
+    ))
+}
+
+// Check a finished download before it is allowed to replace the installed mod
+fn verify_download(
+    mod_id: &str,
+    path: &Path,
+    expected_size: Option<u64>,
+    expected_hash: Option<&str>,
+) -> Result<(), String> {
+    use zip::ZipArchive;
+
+    let size = std::fs::metadata(path)
+        .map_err(|e| format!("Failed to read downloaded file: {}", e))?
+        .len();
+    if let Some(expected) = expected_size {
+        if size != expected {
+            return Err(format!(
+                "Downloaded file has {} bytes, expected {}",
+                size, expected
+            ));
+        }
     }
 
-    eprintln!("[search_mods] Searching mods: {}", api_url);
+    // Read every entry to the end so the zip crate checks each CRC
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open downloaded file: {}", e))?;
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| format!("Downloaded file is not a valid zip archive: {}", e))?;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Downloaded zip is corrupted: {}", e))?;
+        std::io::copy(&mut entry, &mut std::io::sink())
+            .map_err(|e| format!("Downloaded zip is corrupted ({}): {}", entry.name(), e))?;
+    }
 
-    match client
-        .get(&api_url)
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
-        )
-        .send()
-        .await
-    {
-        Ok(response) => {
-            if response.status().is_success() {
-                match response.text().await {
-                    Ok(text) => {
-                        // Try to parse as ModSearchResult
-                        match serde_json::from_str::<ModSearchResult>(&text) {
-                            Ok(result) => {
-                                eprintln!("[search_mods] Found {} mods", result.mods.len());
-                                return Ok(result);
-                            }
-                            Err(e) => {
-                                eprintln!("[search_mods] Failed to parse search result: {}", e);
-                                eprintln!(
-                                    "[search_mods] Response preview: {}",
-                                    &text.chars().take(500).collect::<String>()
-                                );
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("[search_mods] Failed to read response: {}", e);
-                    }
-                }
-            } else {
-                eprintln!("[search_mods] API returned status: {}", response.status());
+    // Code mods may describe themselves in the DLL instead of a modinfo.json,
+    // and numeric ModDB ids never match a modid, so only compare when both exist
+    match crate::mod_manager::extract_modinfo_from_zip(path) {
+        Ok(info) => {
+            let is_numeric_id = mod_id.chars().all(|c| c.is_ascii_digit());
+            if !is_numeric_id && !info.modid.eq_ignore_ascii_case(mod_id) {
+                return Err(format!(
+                    "Downloaded mod has modid \"{}\", expected \"{}\"",
+                    info.modid, mod_id
+                ));
             }
         }
         Err(e) => {
-            eprintln!("[search_mods] Failed to fetch API: {}", e);
+            eprintln!(
+                "[download_mod] WARNING: {}, skipping modid check for {}",
+                e, mod_id
+            );
         }
     }
 
-    // Return empty result on error
-    Ok(ModSearchResult {
-        status_code: Some(500),
-        mods: vec![],
-    })
+    if let Some(expected) = expected_hash.map(str::trim).filter(|h| !h.is_empty()) {
+        let actual = crate::mod_manager::hash_file(path)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(format!(
+                "Hash mismatch for {}: expected {}, got {}",
+                mod_id, expected, actual
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Verify a finished file and move it over the installed copy of the mod
+pub(crate) fn install_verified_zip(
+    mod_id: &str,
+    part_path: &Path,
+    mods_dir: &Path,
+    expected_size: Option<u64>,
+    expected_hash: Option<&str>,
+) -> Result<PathBuf, String> {
+    let zip_path = mods_dir.join(format!("{}.zip", mod_id));
+
+    if let Err(e) = verify_download(mod_id, part_path, expected_size, expected_hash) {
+        // A corrupt file must not be resumed by the next attempt
+        let _ = std::fs::remove_file(part_path);
+        return Err(format!("Downloaded file failed verification: {}", e));
+    }
+
+    // Atomic on the same filesystem, so a crash leaves either the old or the new zip
+    std::fs::rename(part_path, &zip_path).map_err(|e| format!("Failed to save zip file: {}", e))?;
+    crate::mod_manager::invalidate_scan();
+
+    eprintln!("[download_mod] Saved zip file to: {:?}", zip_path);
+
+    // An unpacked copy of the same mod would be loaded alongside the new zip
+    let dir_path = mods_dir.join(mod_id);
+    if dir_path.is_dir() {
+        std::fs::remove_dir_all(&dir_path)
+            .map_err(|e| format!("Failed to remove existing mod directory: {}", e))?;
+    }
+
+    Ok(zip_path)
+}
+
+// Make sure a release is in the download cache without installing it, e.g. to
+// read its modinfo before deciding to update
+pub(crate) async fn fetch_release_to_cache(
+    mod_id: &str,
+    release: &ModRelease,
+) -> Result<crate::download_cache::CacheEntry, String> {
+    use crate::download_cache;
+
+    if let Some(entry) = download_cache::lookup(
+        mod_id,
+        release.modversion.as_deref(),
+        None,
+        Some(&release.mainfile),
+    ) {
+        return Ok(entry);
+    }
+
+    let client = download_client()?;
+    let staging = download_cache::staging_dir()?;
+    let part_path = partial_download_path(&staging, mod_id, &release.mainfile);
+
+    let expected_size = download_to_part_file(&client, &release.mainfile, &part_path).await?;
+    let stored = verify_download(mod_id, &part_path, expected_size, None).and_then(|_| {
+        download_cache::store(
+            &part_path,
+            mod_id,
+            release.modversion.as_deref(),
+            Some(&release.mainfile),
+        )
+    });
+    let _ = std::fs::remove_file(&part_path);
+    stored
+}
+
+// Install a mod from the download cache or the network. `on_verifying` is
+// called once the file is complete and is being checked.
+pub(crate) async fn fetch_and_install(
+    mod_id: &str,
+    download_url: &str,
+    mods_dir: &Path,
+    expected_hash: Option<&str>,
+    version: Option<&str>,
+    on_verifying: impl FnOnce(),
+) -> Result<PathBuf, String> {
+    use crate::download_cache;
+
+    // A file we fetched before (for any pack or profile) needs no network at all
+    if let Some(entry) = download_cache::lookup(mod_id, version, expected_hash, Some(download_url))
+    {
+        match download_cache::install_from_cache(&entry, mod_id, mods_dir) {
+            Ok(zip_path) => return Ok(zip_path),
+            Err(e) => eprintln!("[download_mod] Cached copy unusable, downloading: {}", e),
+        }
+    }
+
+    let client = download_client()?;
+
+    eprintln!(
+        "[download_mod] Downloading mod {} from {}",
+        mod_id, download_url
+    );
+
+    let part_path = partial_download_path(mods_dir, mod_id, download_url);
+
+    // The installed mod stays untouched until the new file is complete and verified
+    let expected_size = download_to_part_file(&client, download_url, &part_path).await?;
+    on_verifying();
+    let zip_path =
+        install_verified_zip(mod_id, &part_path, mods_dir, expected_size, expected_hash)?;
+
+    if let Err(e) = download_cache::store(&zip_path, mod_id, version, Some(download_url)) {
+        eprintln!("[download_mod] WARNING: Failed to cache {}: {}", mod_id, e);
+    }
+
+    Ok(zip_path)
 }
 
 #[tauri::command]
@@ -399,91 +941,181 @@ pub async fn download_mod(
     mod_id: String,
     download_url: String,
     mods_path: String,
+    expected_hash: Option<String>,
+    version: Option<String>,
+    source: Option<ModSourceSpec>,
+    override_hold: Option<bool>,
 ) -> Result<String, String> {
-    use zip::ZipArchive;
+    use crate::download_queue::{self, DownloadRequest};
 
-    let client = reqwest::Client::new();
+    // Goes through the shared queue so it counts against the parallel download
+    // limit, and so the queue can refuse to replace a held mod
+    let item = download_queue::enqueue(DownloadRequest {
+        mod_id,
+        version,
+        download_url: Some(download_url),
+        mods_path,
+        expected_hash,
+        source,
+        override_hold: override_hold.unwrap_or(false),
+    });
+    download_queue::wait_for(&item.id).await
+}
 
-    eprintln!(
-        "[download_mod] Downloading mod {} from {}",
-        mod_id, download_url
-    );
-    let response = client
-        .get(&download_url)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to download mod: {}", e))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if !response.status().is_success() {
-        return Err(format!("Download failed: {}", response.status()));
+    fn release(version: &str, tags: &[&str], created: &str) -> ModRelease {
+        ModRelease {
+            mainfile: format!("https://mods.vintagestory.at/download/{}.zip", version),
+            modversion: Some(version.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            fileid: None,
+            created: Some(created.to_string()),
+            releaseid: None,
+            filename: None,
+            modidstr: None,
+            downloads: None,
+            changelog: None,
+            dependencies: None,
+        }
     }
 
-    let bytes = response
-        .bytes()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
+    #[test]
+    fn test_select_release_prefers_newest_compatible() {
+        let releases = vec![
+            release("2.0.0", &["v1.20.0"], "2024-06-01 10:00:00"),
+            release("1.5.0", &["v1.19.7", "v1.19.8"], "2024-03-01 10:00:00"),
+            release("1.4.0", &["v1.19.8"], "2024-01-01 10:00:00"),
+        ];
 
-    eprintln!("[download_mod] Downloaded {} bytes", bytes.len());
+        let newest = select_release("testmod", &releases, None).unwrap();
+        assert_eq!(newest.version_label(), "2.0.0");
 
-    // Save zip file directly to mods directory
-    let mods_dir = std::path::Path::new(&mods_path);
-    let zip_path = mods_dir.join(format!("{}.zip", mod_id));
-
-    // Remove existing mod (either zip or directory)
-    if zip_path.exists() {
-        std::fs::remove_file(&zip_path)
-            .map_err(|e| format!("Failed to remove existing zip: {}", e))?;
+        let compatible = select_release("testmod", &releases, Some("1.19.8")).unwrap();
+        assert_eq!(compatible.version_label(), "1.5.0");
     }
-    let dir_path = mods_dir.join(&mod_id);
-    if dir_path.exists() && dir_path.is_dir() {
-        std::fs::remove_dir_all(&dir_path)
-            .map_err(|e| format!("Failed to remove existing mod directory: {}", e))?;
+
+    #[test]
+    fn test_select_release_explains_incompatibility() {
+        let releases = vec![release(
+            "2.0.0",
+            &["v1.20.0", "v1.20.1"],
+            "2024-06-01 10:00:00",
+        )];
+
+        let err = select_release("testmod", &releases, Some("v1.18.0")).unwrap_err();
+        assert!(err.contains("1.18.0"));
+        assert!(err.contains("1.20.0, 1.20.1"));
     }
 
-    // Write zip file directly
-    std::fs::write(&zip_path, bytes).map_err(|e| format!("Failed to save zip file: {}", e))?;
+    #[test]
+    fn test_find_release_by_version() {
+        let releases = vec![
+            release("1.5.0", &["v1.19.8"], "2024-03-01 10:00:00"),
+            release("1.4.0", &["v1.19.8"], "2024-01-01 10:00:00"),
+        ];
 
-    eprintln!("[download_mod] Saved zip file to: {:?}", zip_path);
+        let pinned = find_release_by_version("testmod", &releases, "v1.4.0").unwrap();
+        assert_eq!(pinned.version_label(), "1.4.0");
 
-    // Verify it's a valid zip and contains modinfo.json
-    let file =
-        std::fs::File::open(&zip_path).map_err(|e| format!("Failed to open zip file: {}", e))?;
+        let err = find_release_by_version("testmod", &releases, "1.3.0").unwrap_err();
+        assert!(err.contains("no longer available"));
+        assert!(err.contains("1.5.0, 1.4.0"));
+    }
 
-    let mut archive =
-        ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    fn write_mod_zip(path: &Path, modid: &str) {
+        use std::io::Write;
 
-    // Check if modinfo.json exists in the zip
-    let mut found_modinfo = false;
-    for i in 0..archive.len() {
-        let file = archive
-            .by_index(i)
-            .map_err(|e| format!("Failed to read file from archive: {}", e))?;
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        zip.start_file("modinfo.json", zip::write::FileOptions::default())
+            .unwrap();
+        write!(
+            zip,
+            r#"{{"modid": "{}", "name": "Test", "version": "1.0.0", "authors": []}}"#,
+            modid
+        )
+        .unwrap();
+        zip.finish().unwrap();
+    }
 
-        let name = file.name();
-        if name == "modinfo.json" || name.ends_with("/modinfo.json") {
-            found_modinfo = true;
-            break;
-        }
+    #[test]
+    fn test_verify_download() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let zip_path = temp_dir.path().join("download.part");
+        write_mod_zip(&zip_path, "testmod");
+        let size = std::fs::metadata(&zip_path).unwrap().len();
+        let hash = crate::mod_manager::hash_file(&zip_path).unwrap();
+
+        assert!(verify_download("testmod", &zip_path, Some(size), Some(&hash)).is_ok());
+        assert!(verify_download("testmod", &zip_path, Some(size + 1), None).is_err());
+        assert!(verify_download("othermod", &zip_path, None, None).is_err());
+        assert!(verify_download("testmod", &zip_path, None, Some("deadbeef")).is_err());
+
+        std::fs::write(&zip_path, b"not a zip").unwrap();
+        assert!(verify_download("testmod", &zip_path, None, None).is_err());
     }
 
-    if !found_modinfo {
-        // Try to find modinfo.json in subdirectories
-        for i in 0..archive.len() {
-            let file = archive
-                .by_index(i)
-                .map_err(|e| format!("Failed to read file from archive: {}", e))?;
-
-            let name = file.name().to_lowercase();
-            if name.contains("modinfo.json") {
-                found_modinfo = true;
-                break;
-            }
-        }
+    #[test]
+    fn test_release_parses_null_tags() {
+        let json = r#"{"mainfile": "https://example.com/a.zip", "modversion": "1.0.0", "tags": null, "fileid": 42}"#;
+        let release: ModRelease = serde_json::from_str(json).unwrap();
+        assert!(release.tags.is_empty());
+        assert_eq!(release.fileid, Some(42));
     }
 
-    if !found_modinfo {
-        eprintln!("[download_mod] WARNING: modinfo.json not found in zip, but continuing anyway");
+    #[test]
+    fn test_search_results_sorted_and_paginated() {
+        let json = r#"{"statuscode": "200", "mods": [
+            {"id": 3, "modid": "c", "name": "Gamma", "downloads": 10},
+            {"id": 1, "modid": "a", "name": "alpha", "downloads": "500"},
+            {"id": 2, "modid": "b", "name": "Beta", "downloads": 10},
+            {"id": 4, "modid": "d", "name": "Delta"}
+        ]}"#;
+        let mut mods = serde_json::from_str::<ModSearchResult>(json).unwrap().mods;
+        sort_search_results(&mut mods);
+
+        let ids: Vec<Option<u64>> = mods.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![Some(1), Some(2), Some(3), Some(4)]);
+
+        let page_two = paginate(&mods, 2, 3);
+        assert_eq!(page_two.len(), 1);
+        assert_eq!(page_two[0].id, Some(4));
+        assert!(paginate(&mods, 3, 3).is_empty());
     }
 
-    Ok(zip_path.to_string_lossy().to_string())
+    #[test]
+    fn test_mod_details_from_api() {
+        let json = r#"{"statuscode": "200", "mod": {
+            "modid": 1234, "name": "Example", "text": "<p>Does things</p>",
+            "author": "Alice, Bob", "urlalias": "", "side": "both", "type": "mod",
+            "homepageurl": "", "sourcecodeurl": "https://github.com/alice/example",
+            "downloads": "5012", "follows": 77, "tags": ["Utility"],
+            "screenshots": [{"fileid": 9, "mainfile": "https://example.com/shot.png", "filename": "shot.png", "created": "2024-01-01 00:00:00"}],
+            "releases": [
+                {"releaseid": 1, "mainfile": "https://example.com/1.0.0.zip", "modidstr": "example", "modversion": "1.0.0", "tags": ["v1.19.8"], "created": "2024-01-01 00:00:00", "changelog": "First"},
+                {"releaseid": 2, "mainfile": "https://example.com/1.1.0.zip", "modidstr": "example", "modversion": "1.1.0", "tags": null, "created": "2024-03-01 00:00:00", "changelog": "Second"}
+            ]
+        }}"#;
+        let response: ModApiResponse = serde_json::from_str(json).unwrap();
+        let meta = ResponseMeta {
+            outcome: FetchOutcome::Fresh,
+            age_secs: 0,
+        };
+        let details = ModDetails::from_api(response.mod_data.unwrap(), meta);
+
+        assert_eq!(details.id, Some(1234));
+        assert_eq!(details.modid.as_deref(), Some("example"));
+        assert_eq!(details.authors, vec!["Alice", "Bob"]);
+        assert_eq!(details.downloads, Some(5012));
+        assert_eq!(details.homepage_url, None);
+        assert_eq!(
+            details.page_url.as_deref(),
+            Some("https://mods.vintagestory.at/show/mod/1234")
+        );
+        assert_eq!(details.screenshots.len(), 1);
+        assert_eq!(details.releases[0].modversion.as_deref(), Some("1.1.0"));
+        assert_eq!(details.releases[1].changelog.as_deref(), Some("First"));
+    }
 }
@@ -0,0 +1,261 @@
+// Asset overrides between enabled mods. The game keeps one file per asset
+// location, so when two mods ship the same `assets/<domain>/...` path only one
+// of them is used and the other is silently ignored. One mod overriding a base
+// game asset is usually what it means to do and is not reported, but two mods
+// overriding the same one lose one of the overrides, just like two mods
+// fighting over the same mod asset.
+
+use crate::mod_manager::Mod;
+use crate::mod_status::is_base_game_mod;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tauri::command;
+use zip::ZipArchive;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum OverlapKind {
+    // Both mods replace the same base game asset, so one replacement is lost
+    SharedVanillaOverride,
+    // Both mods ship the same asset of a mod domain
+    ModConflict,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetConflict {
+    pub mod_a: String,
+    pub name_a: String,
+    pub mod_b: String,
+    pub name_b: String,
+    // ModConflict as soon as any shared path is outside the base game domains
+    pub kind: OverlapKind,
+    pub vanilla_overrides: Vec<String>,
+    pub mod_conflicts: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetConflictReport {
+    pub mods_scanned: usize,
+    pub conflicts: Vec<AssetConflict>, // Mod conflicts first, then by number of paths
+    pub errors: Vec<String>,           // Mods whose files could not be listed
+}
+
+// The asset files a mod provides
+struct ModAssets {
+    mod_id: String,
+    name: String,
+    paths: BTreeSet<String>,
+}
+
+// Asset locations are case-insensitive and always use forward slashes
+pub(crate) fn normalize_asset_path(path: &str) -> Option<String> {
+    let path = path.replace('\\', "/").to_lowercase();
+    let path = path.trim_start_matches("./");
+    if !path.starts_with("assets/") || path.ends_with('/') {
+        return None;
+    }
+    // assets/<domain>/<file>, anything shallower is not an asset
+    (path.split('/').filter(|part| !part.is_empty()).count() >= 3).then(|| path.to_string())
+}
+
+fn zip_asset_paths(zip_path: &Path) -> Result<BTreeSet<String>, String> {
+    let file = File::open(zip_path).map_err(|e| format!("Failed to open zip file: {}", e))?;
+    let archive =
+        ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+    Ok(archive
+        .file_names()
+        .filter_map(normalize_asset_path)
+        .collect())
+}
+
+// The normalized asset path and the file on disk of every asset of a folder mod
+pub(crate) fn dir_asset_files(mod_dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![mod_dir.join("assets")];
+    while let Some(dir) = pending.pop() {
+        if !dir.is_dir() {
+            continue;
+        }
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| e.to_string())?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Some(asset_path) = path
+                .strip_prefix(mod_dir)
+                .ok()
+                .and_then(|relative| normalize_asset_path(&relative.to_string_lossy()))
+            {
+                files.push((asset_path, path));
+            }
+        }
+    }
+    Ok(files)
+}
+
+// Every `assets/<domain>/...` file of a zip or folder mod, normalized
+pub(crate) fn asset_paths(mod_item: &Mod) -> Result<BTreeSet<String>, String> {
+    let path = Path::new(&mod_item.path);
+    if mod_item.is_zip {
+        zip_asset_paths(path)
+    } else {
+        Ok(dir_asset_files(path)?
+            .into_iter()
+            .map(|(asset_path, _)| asset_path)
+            .collect())
+    }
+}
+
+fn mod_assets(mod_item: &Mod) -> Result<ModAssets, String> {
+    Ok(ModAssets {
+        mod_id: mod_item.id.clone(),
+        name: mod_item.name.clone(),
+        paths: asset_paths(mod_item)?,
+    })
+}
+
+fn overlap_kind(path: &str) -> OverlapKind {
+    match path.split('/').nth(1) {
+        Some(domain) if is_base_game_mod(domain) => OverlapKind::SharedVanillaOverride,
+        _ => OverlapKind::ModConflict,
+    }
+}
+
+// One entry for every pair of mods that ship at least one identical path
+fn find_conflicts(mods: &[ModAssets]) -> Vec<AssetConflict> {
+    let mut providers: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (index, assets) in mods.iter().enumerate() {
+        for path in &assets.paths {
+            providers.entry(path.as_str()).or_default().push(index);
+        }
+    }
+
+    let mut pairs: BTreeMap<(usize, usize), Vec<&str>> = BTreeMap::new();
+    for (path, indices) in providers.iter().filter(|(_, indices)| indices.len() > 1) {
+        for (i, &a) in indices.iter().enumerate() {
+            for &b in &indices[i + 1..] {
+                pairs.entry((a, b)).or_default().push(path);
+            }
+        }
+    }
+
+    let mut conflicts: Vec<AssetConflict> = pairs
+        .into_iter()
+        .map(|((a, b), paths)| {
+            let (vanilla_overrides, mod_conflicts): (Vec<&str>, Vec<&str>) = paths
+                .into_iter()
+                .partition(|path| overlap_kind(path) == OverlapKind::SharedVanillaOverride);
+            AssetConflict {
+                mod_a: mods[a].mod_id.clone(),
+                name_a: mods[a].name.clone(),
+                mod_b: mods[b].mod_id.clone(),
+                name_b: mods[b].name.clone(),
+                kind: if mod_conflicts.is_empty() {
+                    OverlapKind::SharedVanillaOverride
+                } else {
+                    OverlapKind::ModConflict
+                },
+                vanilla_overrides: vanilla_overrides.into_iter().map(String::from).collect(),
+                mod_conflicts: mod_conflicts.into_iter().map(String::from).collect(),
+            }
+        })
+        .collect();
+
+    conflicts.sort_by_key(|c| {
+        (
+            c.kind == OverlapKind::SharedVanillaOverride,
+            std::cmp::Reverse(c.mod_conflicts.len() + c.vanilla_overrides.len()),
+        )
+    });
+    conflicts
+}
+
+#[command]
+pub async fn analyze_asset_conflicts(mods_path: String) -> Result<AssetConflictReport, String> {
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+
+    let mut report = AssetConflictReport::default();
+    let mut mods = Vec::new();
+    for mod_item in mod_list.iter().filter(|m| m.enabled) {
+        match mod_assets(mod_item) {
+            Ok(assets) => mods.push(assets),
+            Err(e) => report.errors.push(format!("{}: {}", mod_item.id, e)),
+        }
+    }
+
+    report.mods_scanned = mods.len();
+    report.conflicts = find_conflicts(&mods);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assets(mod_id: &str, paths: &[&str]) -> ModAssets {
+        ModAssets {
+            mod_id: mod_id.to_string(),
+            name: mod_id.to_string(),
+            paths: paths
+                .iter()
+                .filter_map(|p| normalize_asset_path(p))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_conflicts_classifies_overlaps() {
+        let mods = vec![
+            assets(
+                "betterpans",
+                &[
+                    "modinfo.json",
+                    "assets/game/textures/block/pan.png",
+                    "assets/game/blocktypes/pan.json",
+                ],
+            ),
+            assets(
+                "panrework",
+                &[
+                    "modinfo.json",
+                    "assets/game/blocktypes/pan.json",
+                    "assets/betterpans/lang/en.json",
+                ],
+            ),
+            assets("panlang", &["Assets\\BetterPans\\Lang\\en.json"]),
+            assets("standalone", &["assets/standalone/lang/en.json"]),
+        ];
+
+        let conflicts = find_conflicts(&mods);
+        let summary: Vec<(&str, &str, OverlapKind)> = conflicts
+            .iter()
+            .map(|c| (c.mod_a.as_str(), c.mod_b.as_str(), c.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("panrework", "panlang", OverlapKind::ModConflict),
+                (
+                    "betterpans",
+                    "panrework",
+                    OverlapKind::SharedVanillaOverride
+                ),
+            ]
+        );
+        assert_eq!(
+            conflicts[0].mod_conflicts,
+            vec!["assets/betterpans/lang/en.json"]
+        );
+        assert_eq!(
+            conflicts[1].vanilla_overrides,
+            vec!["assets/game/blocktypes/pan.json"]
+        );
+    }
+}
@@ -16,6 +16,28 @@ pub struct Settings {
     pub theme: String,
     #[serde(rename = "default_mod_pack_location")]
     pub default_mod_pack_location: Option<String>,
+    // Game version used to pick compatible releases (e.g. "1.19.8"); detected when unset
+    #[serde(rename = "game_version")]
+    pub game_version: Option<String>,
+    // Serve ModDB data only from the response cache, never touching the network
+    #[serde(rename = "offline_mode", default)]
+    pub offline_mode: bool,
+    // How long cached ModDB responses are used before being revalidated
+    #[serde(rename = "api_cache_ttl_minutes")]
+    pub api_cache_ttl_minutes: Option<u64>,
+    // How many mods the download queue fetches at once
+    #[serde(rename = "parallel_downloads")]
+    pub parallel_downloads: Option<usize>,
+    // Fall back to scraping ModDB pages when the API cannot resolve a mod; off
+    // unless turned on, as scraping breaks whenever the pages change
+    #[serde(rename = "enable_html_scraping", default)]
+    pub enable_html_scraping: bool,
+    // Minutes between background checks of the watchlist; 0 turns them off
+    #[serde(rename = "watchlist_interval_minutes")]
+    pub watchlist_interval_minutes: Option<u64>,
+    // Folder the game is installed in, used to read the installed game version
+    #[serde(rename = "game_install_path")]
+    pub game_install_path: Option<String>,
 }
 
 impl Default for Settings {
@@ -27,6 +49,13 @@ impl Default for Settings {
             api_password: None,
             theme: "light".to_string(),
             default_mod_pack_location: None,
+            game_version: None,
+            offline_mode: false,
+            api_cache_ttl_minutes: None,
+            parallel_downloads: None,
+            enable_html_scraping: false,
+            watchlist_interval_minutes: None,
+            game_install_path: None,
         }
     }
 }
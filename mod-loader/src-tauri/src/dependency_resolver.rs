@@ -0,0 +1,893 @@
+// Resolves the full dependency graph of installed mods: what their
+// dependencies need in turn, which release of each missing or outdated mod
+// to install, and in which order. The result is a plan the UI can preview
+// before anything is downloaded: a release's dependencies come from its
+// source's metadata, or from its modinfo when the zip is already cached.
+
+use crate::api_client::ModRelease;
+use crate::mod_status::{is_base_game_mod, parse_dependencies, ModDependency};
+use crate::version;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use tauri::command;
+
+// Guards against constraints that keep re-resolving each other
+const MAX_RESOLUTION_STEPS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum PlanAction {
+    Install,
+    Upgrade,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Requirement {
+    pub mod_id: String,              // The mod that declares the dependency
+    pub min_version: Option<String>, // None accepts any version
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanStep {
+    pub mod_id: String,
+    pub action: PlanAction,
+    pub from_version: Option<String>, // Installed version for upgrades
+    pub to_version: String,
+    pub required_by: Vec<Requirement>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SatisfiedDependency {
+    pub mod_id: String,
+    pub installed_version: String,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum PlanProblem {
+    // No release meets the highest minimum version any dependent asks for
+    #[serde(rename_all = "camelCase")]
+    Unsatisfiable {
+        mod_id: String,
+        min_version: String,
+        newest_available: Option<String>,
+        required_by: Vec<Requirement>,
+    },
+    // The releases of the mod could not be listed
+    #[serde(rename_all = "camelCase")]
+    Unavailable {
+        mod_id: String,
+        error: String,
+        required_by: Vec<Requirement>,
+    },
+    // Mods that depend on each other; each is in the plan, but in no particular order
+    #[serde(rename_all = "camelCase")]
+    Cycle { mod_ids: Vec<String> },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyPlan {
+    pub roots: Vec<String>,
+    pub steps: Vec<PlanStep>, // Dependencies before the mods that need them
+    pub satisfied: Vec<SatisfiedDependency>,
+    pub problems: Vec<PlanProblem>,
+    pub warnings: Vec<String>,
+}
+
+impl DependencyPlan {
+    // Whether every dependency can be installed as planned
+    pub fn is_complete(&self) -> bool {
+        !self
+            .problems
+            .iter()
+            .any(|p| !matches!(p, PlanProblem::Cycle { .. }))
+    }
+
+    // What carrying out the plan would do, one line per step or problem
+    fn summary(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .steps
+            .iter()
+            .map(|step| {
+                format!(
+                    "{:?} {} {} -> {}",
+                    step.action,
+                    key(&step.mod_id),
+                    step.from_version.as_deref().unwrap_or("none"),
+                    step.to_version
+                )
+            })
+            .chain(self.problems.iter().filter_map(|problem| match problem {
+                PlanProblem::Unsatisfiable { mod_id, .. }
+                | PlanProblem::Unavailable { mod_id, .. } => {
+                    Some(format!("cannot install {}", key(mod_id)))
+                }
+                PlanProblem::Cycle { .. } => None,
+            }))
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    // An error when the plan would do something other than `previewed`, e.g.
+    // because a new release came out after the preview
+    pub(crate) fn check_unchanged(&self, previewed: &DependencyPlan) -> Result<(), String> {
+        let (now, before) = (self.summary(), previewed.summary());
+        if now == before {
+            return Ok(());
+        }
+        let added: Vec<&str> = now
+            .iter()
+            .filter(|line| !before.contains(line))
+            .map(String::as_str)
+            .collect();
+        let dropped: Vec<&str> = before
+            .iter()
+            .filter(|line| !now.contains(line))
+            .map(String::as_str)
+            .collect();
+        Err(format!(
+            "The dependencies changed since they were previewed (now: {}; no longer: {}); review them again",
+            if added.is_empty() { "nothing new".to_string() } else { added.join(", ") },
+            if dropped.is_empty() { "nothing".to_string() } else { dropped.join(", ") }
+        ))
+    }
+
+    // An error naming every dependency that cannot be installed
+    pub(crate) fn check_complete(&self) -> Result<(), String> {
+        if self.is_complete() {
+            return Ok(());
+        }
+        let problems: Vec<String> = self
+            .problems
+            .iter()
+            .filter_map(|problem| match problem {
+                PlanProblem::Unavailable { mod_id, error, .. } => {
+                    Some(format!("{}: {}", mod_id, error))
+                }
+                PlanProblem::Unsatisfiable {
+                    mod_id,
+                    min_version,
+                    newest_available,
+                    ..
+                } => Some(format!(
+                    "{}: no release satisfies {} (newest is {})",
+                    mod_id,
+                    min_version,
+                    newest_available.as_deref().unwrap_or("none")
+                )),
+                PlanProblem::Cycle { .. } => None,
+            })
+            .collect();
+        Err(format!(
+            "Some dependencies cannot be installed ({})",
+            problems.join("; ")
+        ))
+    }
+}
+
+// Where releases and their dependencies come from; the network in the app,
+// fixed data in tests
+pub(crate) trait ReleaseProvider {
+    async fn releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String>;
+
+    async fn dependencies(
+        &self,
+        mod_id: &str,
+        release: &ModRelease,
+    ) -> Result<Vec<ModDependency>, String>;
+}
+
+// The dependencies a release declares: from its source's metadata when the
+// source publishes them, otherwise from its modinfo if the zip is in the
+// download cache. Only with `download` is an uncached release fetched for that.
+pub(crate) async fn release_dependencies(
+    mod_id: &str,
+    release: &ModRelease,
+    download: bool,
+) -> Result<Vec<ModDependency>, String> {
+    if let Some(dependencies) = &release.dependencies {
+        return Ok(parse_dependencies(&serde_json::json!(dependencies)));
+    }
+
+    let cached = crate::download_cache::lookup(
+        mod_id,
+        release.modversion.as_deref(),
+        None,
+        Some(&release.mainfile),
+    );
+    let entry = match cached {
+        Some(entry) => entry,
+        None if download => crate::api_client::fetch_release_to_cache(mod_id, release).await?,
+        None => {
+            return Err(
+                "its source does not publish them and it is not downloaded yet; they are checked once it is installed"
+                    .to_string(),
+            )
+        }
+    };
+    let zip_path = crate::download_cache::cached_file(&entry)?;
+    let info = crate::mod_manager::extract_modinfo_from_zip(&zip_path)?;
+    Ok(info
+        .dependencies
+        .as_ref()
+        .map(parse_dependencies)
+        .unwrap_or_default())
+}
+
+// Releases from each mod's recorded source; dependencies without downloading
+pub(crate) struct SourceReleaseProvider;
+
+impl ReleaseProvider for SourceReleaseProvider {
+    async fn releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        crate::mod_source::releases_for_update_check(mod_id)
+            .await
+            .map(|(releases, _)| releases)
+    }
+
+    async fn dependencies(
+        &self,
+        mod_id: &str,
+        release: &ModRelease,
+    ) -> Result<Vec<ModDependency>, String> {
+        release_dependencies(mod_id, release, false).await
+    }
+}
+
+struct InstalledMod {
+    mod_id: String,
+    version: String,
+    enabled: bool,
+    dependencies: Vec<ModDependency>,
+}
+
+enum Resolution {
+    Installed,
+    Release(Box<ModRelease>),
+    Unavailable(String),
+    Unsatisfiable { newest_available: Option<String> },
+}
+
+struct Node {
+    mod_id: String,
+    resolved_for: Option<String>, // The minimum version this resolution was made for
+    resolution: Resolution,
+}
+
+fn key(mod_id: &str) -> String {
+    mod_id.trim().to_lowercase()
+}
+
+fn effective_minimum(requirements: Option<&BTreeMap<String, Option<String>>>) -> Option<String> {
+    requirements?
+        .values()
+        .flatten()
+        .max_by(|a, b| version::compare(a, b))
+        .cloned()
+}
+
+fn satisfies(version_text: &str, minimum: Option<&str>) -> bool {
+    minimum.is_none_or(|minimum| version::satisfies_minimum(version_text, minimum))
+}
+
+// The newest release compatible with the game version that meets `minimum`.
+// When only releases for other game versions meet it, the newest of those is
+// taken with a warning.
+fn choose_release(
+    mod_id: &str,
+    releases: &[ModRelease],
+    minimum: Option<&str>,
+    game_version: Option<&str>,
+) -> Result<(ModRelease, Option<String>), Option<String>> {
+    let candidates: Vec<ModRelease> = releases
+        .iter()
+        .filter(|r| {
+            r.modversion
+                .as_deref()
+                .is_some_and(|v| satisfies(v, minimum))
+        })
+        .cloned()
+        .collect();
+
+    if candidates.is_empty() {
+        let newest_available = releases
+            .iter()
+            .filter_map(|r| r.modversion.as_deref())
+            .max_by(|a, b| version::compare(a, b))
+            .map(|v| v.to_string());
+        return Err(newest_available);
+    }
+
+    match crate::api_client::select_release(mod_id, &candidates, game_version) {
+        Ok(release) => Ok((release.clone(), None)),
+        Err(note) => {
+            let newest = crate::api_client::select_release(mod_id, &candidates, None)
+                .map_err(|_| None)?
+                .clone();
+            Ok((newest, Some(note)))
+        }
+    }
+}
+
+struct Resolver<'a, P: ReleaseProvider> {
+    provider: &'a P,
+    game_version: Option<&'a str>,
+    installed: HashMap<String, InstalledMod>,
+    // dependency -> dependent -> minimum version it asks for
+    requirements: HashMap<String, BTreeMap<String, Option<String>>>,
+    edges: HashMap<String, Vec<String>>, // dependent -> dependencies
+    nodes: HashMap<String, Node>,
+    queue: VecDeque<String>,
+    release_lists: HashMap<String, Result<Vec<ModRelease>, String>>,
+    warnings: Vec<String>,
+}
+
+impl<'a, P: ReleaseProvider> Resolver<'a, P> {
+    fn set_dependencies(&mut self, from: &str, dependencies: &[ModDependency]) {
+        for previous in self.edges.remove(from).unwrap_or_default() {
+            if let Some(requirements) = self.requirements.get_mut(&previous) {
+                requirements.remove(from);
+            }
+        }
+
+        let mut targets = Vec::new();
+        for dep in dependencies {
+            if is_base_game_mod(&dep.modid) {
+                continue;
+            }
+            let target = key(&dep.modid);
+            let minimum = dep
+                .version
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty() && *v != "*")
+                .map(|v| v.to_string());
+            self.requirements
+                .entry(target.clone())
+                .or_default()
+                .insert(from.to_string(), minimum);
+            self.nodes.entry(target.clone()).or_insert_with(|| Node {
+                mod_id: dep.modid.clone(),
+                resolved_for: None,
+                resolution: Resolution::Unavailable("Not resolved yet".to_string()),
+            });
+            self.queue.push_back(target.clone());
+            targets.push(target);
+        }
+        self.edges.insert(from.to_string(), targets);
+    }
+
+    // Whether the node's resolution still holds for the current minimum
+    fn is_settled(&self, node_key: &str, minimum: Option<&str>) -> bool {
+        let Some(node) = self.nodes.get(node_key) else {
+            return false;
+        };
+        match &node.resolution {
+            Resolution::Installed => self
+                .installed
+                .get(node_key)
+                .is_some_and(|m| satisfies(&m.version, minimum)),
+            Resolution::Release(release) => release
+                .modversion
+                .as_deref()
+                .is_some_and(|v| satisfies(v, minimum)),
+            Resolution::Unavailable(_) | Resolution::Unsatisfiable { .. } => {
+                node.resolved_for.is_some() && node.resolved_for.as_deref() == minimum
+            }
+        }
+    }
+
+    async fn resolve_node(&mut self, node_key: String) {
+        let minimum = effective_minimum(self.requirements.get(&node_key));
+        if self.is_settled(&node_key, minimum.as_deref()) {
+            return;
+        }
+        let mod_id = self
+            .nodes
+            .get(&node_key)
+            .map(|n| n.mod_id.clone())
+            .unwrap_or_else(|| node_key.clone());
+
+        if let Some(installed) = self.installed.get(&node_key) {
+            if satisfies(&installed.version, minimum.as_deref()) {
+                let dependencies = installed.dependencies.clone();
+                self.nodes.insert(
+                    node_key.clone(),
+                    Node {
+                        mod_id: installed.mod_id.clone(),
+                        resolved_for: minimum,
+                        resolution: Resolution::Installed,
+                    },
+                );
+                self.set_dependencies(&node_key, &dependencies);
+                return;
+            }
+        }
+
+        if !self.release_lists.contains_key(&node_key) {
+            let releases = self.provider.releases(&mod_id).await;
+            self.release_lists.insert(node_key.clone(), releases);
+        }
+        let resolution = match &self.release_lists[&node_key] {
+            Err(e) => Resolution::Unavailable(e.clone()),
+            Ok(releases) => {
+                match choose_release(&mod_id, releases, minimum.as_deref(), self.game_version) {
+                    Ok((release, warning)) => {
+                        if let Some(warning) = warning {
+                            self.warnings.push(warning);
+                        }
+                        Resolution::Release(Box::new(release))
+                    }
+                    Err(newest_available) => Resolution::Unsatisfiable { newest_available },
+                }
+            }
+        };
+
+        let dependencies = match &resolution {
+            Resolution::Release(release) => {
+                match self.provider.dependencies(&mod_id, release).await {
+                    Ok(dependencies) => dependencies,
+                    Err(e) => {
+                        self.warnings.push(format!(
+                            "Could not read the dependencies of {} {}: {}",
+                            mod_id,
+                            release.version_label(),
+                            e
+                        ));
+                        Vec::new()
+                    }
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        self.nodes.insert(
+            node_key.clone(),
+            Node {
+                mod_id,
+                resolved_for: minimum,
+                resolution,
+            },
+        );
+        self.set_dependencies(&node_key, &dependencies);
+    }
+
+    fn required_by(&self, node_key: &str) -> Vec<Requirement> {
+        self.requirements
+            .get(node_key)
+            .map(|requirements| {
+                requirements
+                    .iter()
+                    .map(|(dependent, minimum)| Requirement {
+                        mod_id: self
+                            .nodes
+                            .get(dependent)
+                            .map(|n| n.mod_id.clone())
+                            .unwrap_or_else(|| dependent.clone()),
+                        min_version: minimum.clone(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    // Depth-first from the roots: post-order puts dependencies first, and a
+    // back edge to a node still on the stack closes a cycle
+    fn order(&self, roots: &[String]) -> (Vec<String>, Vec<Vec<String>>) {
+        fn visit(
+            node: &str,
+            edges: &HashMap<String, Vec<String>>,
+            on_stack: &mut Vec<String>,
+            done: &mut BTreeSet<String>,
+            order: &mut Vec<String>,
+            cycles: &mut Vec<Vec<String>>,
+        ) {
+            if done.contains(node) {
+                return;
+            }
+            if let Some(start) = on_stack.iter().position(|n| n == node) {
+                cycles.push(on_stack[start..].to_vec());
+                return;
+            }
+            on_stack.push(node.to_string());
+            let mut targets = edges.get(node).cloned().unwrap_or_default();
+            targets.sort();
+            for target in targets {
+                visit(&target, edges, on_stack, done, order, cycles);
+            }
+            on_stack.pop();
+            done.insert(node.to_string());
+            order.push(node.to_string());
+        }
+
+        let mut order = Vec::new();
+        let mut cycles = Vec::new();
+        let mut done = BTreeSet::new();
+        for root in roots {
+            visit(
+                root,
+                &self.edges,
+                &mut Vec::new(),
+                &mut done,
+                &mut order,
+                &mut cycles,
+            );
+        }
+        (order, cycles)
+    }
+}
+
+// Plan what the `roots` (modids of installed mods) need, directly or not
+pub(crate) async fn plan_dependencies_for(
+    roots: &[String],
+    mod_list: &[crate::mod_manager::Mod],
+    game_version: Option<&str>,
+    provider: &impl ReleaseProvider,
+) -> DependencyPlan {
+    plan_with_installed(roots, installed_mods(mod_list), game_version, provider).await
+}
+
+// Plan what installed mods need once they are upgraded to the given releases;
+// the dependencies of each upgraded mod come from its new release
+pub(crate) async fn plan_upgrade_dependencies(
+    upgrades: &[(String, ModRelease)],
+    mod_list: &[crate::mod_manager::Mod],
+    game_version: Option<&str>,
+    provider: &impl ReleaseProvider,
+) -> DependencyPlan {
+    let mut installed = installed_mods(mod_list);
+    let mut warnings = Vec::new();
+    for (mod_id, release) in upgrades {
+        let Some(entry) = installed.get_mut(&key(mod_id)) else {
+            continue;
+        };
+        if let Some(version) = &release.modversion {
+            entry.version = version.clone();
+        }
+        match provider.dependencies(mod_id, release).await {
+            Ok(dependencies) => entry.dependencies = dependencies,
+            Err(e) => warnings.push(format!(
+                "Could not read the dependencies of {} {}: {}",
+                mod_id,
+                release.version_label(),
+                e
+            )),
+        }
+    }
+
+    let roots: Vec<String> = upgrades.iter().map(|(mod_id, _)| mod_id.clone()).collect();
+    let mut plan = plan_with_installed(&roots, installed, game_version, provider).await;
+    warnings.append(&mut plan.warnings);
+    plan.warnings = warnings;
+    plan
+}
+
+fn installed_mods(mod_list: &[crate::mod_manager::Mod]) -> HashMap<String, InstalledMod> {
+    mod_list
+        .iter()
+        .map(|m| {
+            let info = m.info.as_ref();
+            (
+                key(&m.id),
+                InstalledMod {
+                    mod_id: m.id.clone(),
+                    version: info
+                        .map(|i| i.version.clone())
+                        .unwrap_or_else(|| m.version.clone()),
+                    enabled: m.enabled,
+                    dependencies: info
+                        .and_then(|i| i.dependencies.as_ref())
+                        .map(parse_dependencies)
+                        .unwrap_or_default(),
+                },
+            )
+        })
+        .collect()
+}
+
+async fn plan_with_installed(
+    roots: &[String],
+    installed: HashMap<String, InstalledMod>,
+    game_version: Option<&str>,
+    provider: &impl ReleaseProvider,
+) -> DependencyPlan {
+    let mut resolver = Resolver {
+        provider,
+        game_version,
+        installed,
+        requirements: HashMap::new(),
+        edges: HashMap::new(),
+        nodes: HashMap::new(),
+        queue: VecDeque::new(),
+        release_lists: HashMap::new(),
+        warnings: Vec::new(),
+    };
+
+    let mut plan = DependencyPlan::default();
+    let mut root_keys = Vec::new();
+    for root in roots {
+        let root_key = key(root);
+        let Some(installed) = resolver.installed.get(&root_key) else {
+            plan.warnings.push(format!("{} is not installed", root));
+            continue;
+        };
+        let (mod_id, dependencies) = (installed.mod_id.clone(), installed.dependencies.clone());
+        plan.roots.push(mod_id.clone());
+        resolver.nodes.insert(
+            root_key.clone(),
+            Node {
+                mod_id,
+                resolved_for: None,
+                resolution: Resolution::Installed,
+            },
+        );
+        resolver.set_dependencies(&root_key, &dependencies);
+        root_keys.push(root_key);
+    }
+
+    let mut steps = 0;
+    while let Some(node_key) = resolver.queue.pop_front() {
+        if root_keys.contains(&node_key) {
+            continue;
+        }
+        steps += 1;
+        if steps > MAX_RESOLUTION_STEPS {
+            plan.warnings.push(
+                "Dependency resolution did not settle; the plan may be incomplete".to_string(),
+            );
+            break;
+        }
+        resolver.resolve_node(node_key).await;
+    }
+
+    // Only what the roots still reach counts; re-resolved releases may have
+    // dropped dependencies
+    let (order, cycles) = resolver.order(&root_keys);
+    for node_key in order.iter().filter(|k| !root_keys.contains(k)) {
+        let node = &resolver.nodes[node_key];
+        let required_by = resolver.required_by(node_key);
+        let minimum = effective_minimum(resolver.requirements.get(node_key));
+
+        match &node.resolution {
+            Resolution::Installed => {
+                let installed = &resolver.installed[node_key];
+                if !installed.enabled {
+                    plan.warnings
+                        .push(format!("{} is installed but disabled", installed.mod_id));
+                }
+                plan.satisfied.push(SatisfiedDependency {
+                    mod_id: installed.mod_id.clone(),
+                    installed_version: installed.version.clone(),
+                    enabled: installed.enabled,
+                });
+            }
+            Resolution::Release(release) => {
+                // A release without a version could not be installed pinned
+                let Some(to_version) = release.modversion.clone() else {
+                    plan.problems.push(PlanProblem::Unavailable {
+                        mod_id: node.mod_id.clone(),
+                        error: "The chosen release does not say which version it is".to_string(),
+                        required_by,
+                    });
+                    continue;
+                };
+                let from_version = resolver.installed.get(node_key).map(|m| m.version.clone());
+                plan.steps.push(PlanStep {
+                    mod_id: node.mod_id.clone(),
+                    action: if from_version.is_some() {
+                        PlanAction::Upgrade
+                    } else {
+                        PlanAction::Install
+                    },
+                    from_version,
+                    to_version,
+                    required_by,
+                });
+            }
+            Resolution::Unavailable(error) => plan.problems.push(PlanProblem::Unavailable {
+                mod_id: node.mod_id.clone(),
+                error: error.clone(),
+                required_by,
+            }),
+            Resolution::Unsatisfiable { newest_available } => {
+                plan.problems.push(PlanProblem::Unsatisfiable {
+                    mod_id: node.mod_id.clone(),
+                    min_version: minimum.unwrap_or_default(),
+                    newest_available: newest_available.clone(),
+                    required_by,
+                })
+            }
+        }
+    }
+
+    for cycle in cycles {
+        plan.problems.push(PlanProblem::Cycle {
+            mod_ids: cycle
+                .iter()
+                .map(|k| {
+                    resolver
+                        .nodes
+                        .get(k)
+                        .map(|n| n.mod_id.clone())
+                        .unwrap_or_else(|| k.clone())
+                })
+                .collect(),
+        });
+    }
+    plan.warnings.extend(resolver.warnings);
+    plan.warnings.dedup();
+    plan
+}
+
+// Preview what installing the dependencies of a mod would do
+#[command]
+pub async fn plan_dependencies(
+    mod_id: String,
+    mods_path: String,
+) -> Result<DependencyPlan, String> {
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let game_version = crate::game_detector::current_game_version().await;
+
+    Ok(plan_dependencies_for(
+        &[mod_id],
+        &mod_list,
+        game_version.as_deref(),
+        &SourceReleaseProvider,
+    )
+    .await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_manager::{test_mod, Mod};
+
+    struct FixedProvider {
+        // modid -> (version, game version tag, dependencies)
+        releases: HashMap<&'static str, Vec<(&'static str, &'static str, serde_json::Value)>>,
+    }
+
+    impl ReleaseProvider for FixedProvider {
+        async fn releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+            let releases = self
+                .releases
+                .get(mod_id)
+                .ok_or_else(|| format!("{} not found", mod_id))?;
+            Ok(releases
+                .iter()
+                .enumerate()
+                .map(|(i, (version, tag, _))| ModRelease {
+                    mainfile: format!("https://example.com/{}-{}.zip", mod_id, version),
+                    modversion: Some(version.to_string()),
+                    tags: vec![tag.to_string()],
+                    fileid: None,
+                    created: Some(format!("2024-01-{:02} 00:00:00", i + 1)),
+                    releaseid: None,
+                    filename: None,
+                    modidstr: Some(mod_id.to_string()),
+                    downloads: None,
+                    changelog: None,
+                    dependencies: None,
+                })
+                .collect())
+        }
+
+        async fn dependencies(
+            &self,
+            mod_id: &str,
+            release: &ModRelease,
+        ) -> Result<Vec<ModDependency>, String> {
+            let (_, _, deps) = self.releases[mod_id]
+                .iter()
+                .find(|(v, _, _)| Some(*v) == release.modversion.as_deref())
+                .unwrap();
+            Ok(parse_dependencies(deps))
+        }
+    }
+
+    fn installed(modid: &str, version: &str, deps: serde_json::Value) -> Mod {
+        test_mod(
+            modid,
+            version,
+            true,
+            serde_json::json!({ "dependencies": deps }),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_transitive_plan_in_install_order() {
+        use serde_json::json;
+
+        let mut provider = FixedProvider {
+            releases: HashMap::from([
+                (
+                    "middle",
+                    vec![
+                        ("1.0.0", "v1.20.0", json!({"base": "1.0.0"})),
+                        ("1.1.0", "v1.20.0", json!({"base": "2.0.0"})),
+                    ],
+                ),
+                (
+                    "base",
+                    vec![
+                        ("1.5.0", "v1.20.0", json!({"game": "1.20.0"})),
+                        ("2.1.0", "v1.20.0", json!({})),
+                    ],
+                ),
+                ("missing", vec![("0.9.0", "v1.20.0", json!({}))]),
+            ]),
+        };
+        let mod_list = vec![
+            installed(
+                "top",
+                "1.0.0",
+                json!({"middle": "1.1.0", "missing": "1.0.0"}),
+            ),
+            installed("base", "1.5.0", json!({})),
+        ];
+
+        let plan =
+            plan_dependencies_for(&["top".to_string()], &mod_list, Some("1.20.0"), &provider).await;
+
+        let steps: Vec<(&str, &str)> = plan
+            .steps
+            .iter()
+            .map(|s| (s.mod_id.as_str(), s.to_version.as_str()))
+            .collect();
+        assert_eq!(steps, vec![("base", "2.1.0"), ("middle", "1.1.0")]);
+        assert_eq!(plan.steps[0].action, PlanAction::Upgrade);
+        assert_eq!(plan.steps[0].from_version.as_deref(), Some("1.5.0"));
+
+        assert!(!plan.is_complete());
+        assert!(plan.problems.iter().any(|p| matches!(
+            p,
+            PlanProblem::Unsatisfiable { mod_id, newest_available, .. }
+                if mod_id == "missing" && newest_available.as_deref() == Some("0.9.0")
+        )));
+        assert!(plan.check_complete().unwrap_err().contains("missing"));
+
+        // The plan comes back from the UI as JSON; a release published since
+        // then changes what would be installed
+        let previewed: DependencyPlan =
+            serde_json::from_value(serde_json::to_value(&plan).unwrap()).unwrap();
+        assert!(plan.check_unchanged(&previewed).is_ok());
+        provider
+            .releases
+            .get_mut("base")
+            .unwrap()
+            .push(("2.2.0", "v1.20.0", json!({})));
+        let replanned =
+            plan_dependencies_for(&["top".to_string()], &mod_list, Some("1.20.0"), &provider).await;
+        let err = replanned.check_unchanged(&previewed).unwrap_err();
+        assert!(err.contains("2.2.0") && err.contains("2.1.0"));
+    }
+
+    #[tokio::test]
+    async fn test_cycle_detected() {
+        use serde_json::json;
+
+        let provider = FixedProvider {
+            releases: HashMap::from([
+                ("a", vec![("1.0.0", "v1.20.0", json!({"b": ""}))]),
+                ("b", vec![("1.0.0", "v1.20.0", json!({"a": ""}))]),
+            ]),
+        };
+        let mod_list = vec![installed("top", "1.0.0", json!({"a": ""}))];
+
+        let plan = plan_dependencies_for(&["top".to_string()], &mod_list, None, &provider).await;
+
+        assert_eq!(plan.steps.len(), 2);
+        assert!(plan.is_complete());
+        assert!(plan
+            .problems
+            .iter()
+            .any(|p| matches!(p, PlanProblem::Cycle { mod_ids } if mod_ids.len() == 2)));
+    }
+}
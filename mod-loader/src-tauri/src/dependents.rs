@@ -0,0 +1,231 @@
+// Reverse dependencies: which enabled mods stop working when others are
+// disabled or deleted. Disabling a library that enabled mods need keeps the
+// game from starting, so those operations check first and only go ahead once
+// the caller has decided what should happen to the dependents.
+
+use crate::mod_manager::Mod;
+use crate::mod_status::parse_dependencies;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+// What to do when enabled mods depend on the mods being removed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum DependentsAction {
+    // Do nothing and report what would break
+    #[default]
+    Ask,
+    // Remove the targets and leave the dependents broken
+    Proceed,
+    // Remove the dependents as well
+    Cascade,
+    // Leave the targets that enabled mods still need and remove the rest
+    Keep,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokenDependent {
+    pub mod_id: String,
+    pub name: String,
+    pub requires: Vec<String>, // The removed mods it depends on
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovalReport {
+    // Enabled mods that depend on the targets, directly or through each other
+    pub would_break: Vec<BrokenDependent>,
+    // Whether anything was done; false when the caller still has to decide
+    pub applied: bool,
+    pub affected: Vec<String>, // Mods that were (or would be) removed
+    pub kept: Vec<String>,     // Targets left in place because enabled mods need them
+}
+
+fn key(mod_id: &str) -> String {
+    mod_id.trim().to_lowercase()
+}
+
+// Lowercase modids each enabled mod depends on, keyed by its own lowercase modid
+fn enabled_dependencies(mod_list: &[Mod]) -> BTreeMap<String, BTreeSet<String>> {
+    mod_list
+        .iter()
+        .filter(|m| m.enabled)
+        .map(|m| {
+            let dependencies = m
+                .info
+                .as_ref()
+                .and_then(|info| info.dependencies.as_ref())
+                .map(parse_dependencies)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|dep| key(&dep.modid))
+                .collect();
+            (key(&m.id), dependencies)
+        })
+        .collect()
+}
+
+// Enabled mods outside `removed` that need something in it, repeated until
+// the dependents of dependents are found too
+fn broken_by(
+    dependencies: &BTreeMap<String, BTreeSet<String>>,
+    removed: &BTreeSet<String>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut broken: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    loop {
+        let mut changed = false;
+        for (mod_key, needs) in dependencies {
+            if removed.contains(mod_key) || broken.contains_key(mod_key) {
+                continue;
+            }
+            let missing: BTreeSet<String> = needs
+                .iter()
+                .filter(|dep| removed.contains(*dep) || broken.contains_key(*dep))
+                .cloned()
+                .collect();
+            if !missing.is_empty() {
+                broken.insert(mod_key.clone(), missing);
+                changed = true;
+            }
+        }
+        if !changed {
+            return broken;
+        }
+    }
+}
+
+// Work out what removing `targets` would break and, for the chosen action,
+// which mods to actually remove
+pub(crate) fn plan_removal(
+    mod_list: &[Mod],
+    targets: &[String],
+    action: DependentsAction,
+) -> RemovalReport {
+    let dependencies = enabled_dependencies(mod_list);
+    let mods_by_key: HashMap<String, &Mod> = mod_list.iter().map(|m| (key(&m.id), m)).collect();
+    let display_id = |mod_key: &str| {
+        mods_by_key
+            .get(mod_key)
+            .map(|m| m.id.clone())
+            .unwrap_or_else(|| mod_key.to_string())
+    };
+
+    // Only removing an enabled mod can break anything that works today
+    let enabled_targets: BTreeSet<String> = targets
+        .iter()
+        .map(|t| key(t))
+        .filter(|t| dependencies.contains_key(t))
+        .collect();
+    let broken = broken_by(&dependencies, &enabled_targets);
+
+    let would_break = broken
+        .iter()
+        .map(|(mod_key, requires)| BrokenDependent {
+            mod_id: display_id(mod_key),
+            name: mods_by_key
+                .get(mod_key.as_str())
+                .map(|m| m.name.clone())
+                .unwrap_or_default(),
+            requires: requires.iter().map(|r| display_id(r)).collect(),
+        })
+        .collect();
+
+    let mut report = RemovalReport {
+        would_break,
+        applied: true,
+        affected: targets.to_vec(),
+        kept: Vec::new(),
+    };
+
+    match action {
+        DependentsAction::Ask if !broken.is_empty() => report.applied = false,
+        DependentsAction::Ask | DependentsAction::Proceed => {}
+        DependentsAction::Cascade => {
+            report
+                .affected
+                .extend(broken.keys().map(|mod_key| display_id(mod_key)));
+        }
+        DependentsAction::Keep => {
+            // Keeping a target keeps what it needs in turn
+            let mut removed = enabled_targets.clone();
+            while !broken_by(&dependencies, &removed).is_empty() {
+                let still_needed: BTreeSet<String> = removed
+                    .iter()
+                    .filter(|t| {
+                        dependencies.iter().any(|(mod_key, needs)| {
+                            !removed.contains(mod_key) && needs.contains(*t)
+                        })
+                    })
+                    .cloned()
+                    .collect();
+                removed.retain(|t| !still_needed.contains(t));
+            }
+            let kept: BTreeSet<&String> = enabled_targets.difference(&removed).collect();
+            report.affected.retain(|t| !kept.contains(&key(t)));
+            report.kept = kept.into_iter().map(|t| display_id(t)).collect();
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_manager::test_mod;
+
+    fn installed(modid: &str, enabled: bool, deps: serde_json::Value) -> Mod {
+        test_mod(
+            modid,
+            "1.0.0",
+            enabled,
+            serde_json::json!({ "dependencies": deps }),
+        )
+    }
+
+    fn mods() -> Vec<Mod> {
+        use serde_json::json;
+        vec![
+            installed("corelib", true, json!({"game": ""})),
+            installed("uilib", true, json!({"CoreLib": "1.0.0"})),
+            installed("bigmod", true, json!({"uilib": ""})),
+            installed("oldmod", false, json!({"corelib": ""})),
+            installed("standalone", true, json!({})),
+        ]
+    }
+
+    #[test]
+    fn test_transitive_dependents_block_until_decided() {
+        let targets = vec!["corelib".to_string(), "standalone".to_string()];
+
+        let report = plan_removal(&mods(), &targets, DependentsAction::Ask);
+        assert!(!report.applied);
+        let broken: Vec<(&str, &[String])> = report
+            .would_break
+            .iter()
+            .map(|b| (b.mod_id.as_str(), b.requires.as_slice()))
+            .collect();
+        assert_eq!(
+            broken,
+            vec![
+                ("bigmod", &["uilib".to_string()][..]),
+                ("uilib", &["corelib".to_string()][..]),
+            ]
+        );
+
+        let report = plan_removal(&mods(), &targets, DependentsAction::Cascade);
+        assert!(report.applied);
+        assert_eq!(
+            report.affected,
+            vec!["corelib", "standalone", "bigmod", "uilib"]
+        );
+
+        let report = plan_removal(&mods(), &targets, DependentsAction::Keep);
+        assert_eq!(report.affected, vec!["standalone"]);
+        assert_eq!(report.kept, vec!["corelib"]);
+
+        let report = plan_removal(&mods(), &["bigmod".to_string()], DependentsAction::Ask);
+        assert!(report.applied && report.would_break.is_empty());
+    }
+}
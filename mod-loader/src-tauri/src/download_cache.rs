@@ -0,0 +1,553 @@
+// Content-addressed cache of downloaded mod zips, shared by every pack and profile.
+// Files are stored once under their SHA-256 hash and can also be found by
+// modid@version or by the URL they were downloaded from, so re-applying a pack
+// after switching works without the network.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CacheEntry {
+    pub hash: String,
+    pub modid: String,
+    pub version: Option<String>,
+    pub size: u64,
+    #[serde(default)]
+    pub source_urls: Vec<String>,
+    pub added_at: u64,  // Unix seconds
+    pub last_used: u64, // Unix seconds, drives age and size pruning
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct CacheIndex {
+    entries: HashMap<String, CacheEntry>, // hash -> entry
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DownloadCacheInfo {
+    pub path: String,
+    pub entry_count: usize,
+    pub total_bytes: u64,
+    pub entries: Vec<CacheEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PruneReport {
+    pub removed: Vec<String>, // modid@version of every removed entry
+    pub freed_bytes: u64,
+}
+
+// Serializes every read-modify-write of index.json; downloads store and
+// install concurrently
+static CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn get_cache_dir() -> Result<PathBuf, String> {
+    let cache_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader")
+        .join("download-cache");
+    std::fs::create_dir_all(cache_dir.join("blobs"))
+        .map_err(|e| format!("Failed to create download cache directory: {}", e))?;
+    Ok(cache_dir)
+}
+
+fn blob_path(cache_dir: &Path, hash: &str) -> PathBuf {
+    cache_dir.join("blobs").join(format!("{}.zip", hash))
+}
+
+// Path of the cached zip for an entry, for reading it in place
+pub(crate) fn cached_file(entry: &CacheEntry) -> Result<PathBuf, String> {
+    Ok(blob_path(&get_cache_dir()?, &entry.hash))
+}
+
+// Where downloads meant only for the cache are assembled before being stored
+pub(crate) fn staging_dir() -> Result<PathBuf, String> {
+    let staging = get_cache_dir()?.join("staging");
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create download staging directory: {}", e))?;
+    Ok(staging)
+}
+
+fn load_cache_index(cache_dir: &Path) -> CacheIndex {
+    let index_path = cache_dir.join("index.json");
+
+    if !index_path.exists() {
+        return CacheIndex::default();
+    }
+
+    match std::fs::read_to_string(&index_path) {
+        Ok(content) => match serde_json::from_str::<CacheIndex>(&content) {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("Failed to parse download cache index: {}", e);
+                CacheIndex::default()
+            }
+        },
+        Err(e) => {
+            eprintln!("Failed to read download cache index: {}", e);
+            CacheIndex::default()
+        }
+    }
+}
+
+fn save_cache_index(cache_dir: &Path, index: &CacheIndex) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize download cache index: {}", e))?;
+    // Written aside and renamed, so a reader never sees a half-written index
+    let tmp = cache_dir.join("index.json.tmp");
+    std::fs::write(&tmp, content)
+        .map_err(|e| format!("Failed to write download cache index: {}", e))?;
+    std::fs::rename(&tmp, cache_dir.join("index.json"))
+        .map_err(|e| format!("Failed to write download cache index: {}", e))?;
+    Ok(())
+}
+
+fn entry_label(entry: &CacheEntry) -> String {
+    format!(
+        "{}@{}",
+        entry.modid,
+        entry.version.as_deref().unwrap_or("unknown")
+    )
+}
+
+// Find a cached file by hash, then by modid@version, then by source URL
+pub(crate) fn lookup(
+    mod_id: &str,
+    version: Option<&str>,
+    hash: Option<&str>,
+    source_url: Option<&str>,
+) -> Option<CacheEntry> {
+    lookup_in(&get_cache_dir().ok()?, mod_id, version, hash, source_url)
+}
+
+fn lookup_in(
+    cache_dir: &Path,
+    mod_id: &str,
+    version: Option<&str>,
+    hash: Option<&str>,
+    source_url: Option<&str>,
+) -> Option<CacheEntry> {
+    let index = {
+        let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load_cache_index(cache_dir)
+    };
+
+    let found = if let Some(hash) = hash.map(str::trim).filter(|h| !h.is_empty()) {
+        index.entries.get(&hash.to_lowercase())
+    } else {
+        index
+            .entries
+            .values()
+            .find(|e| {
+                e.modid.eq_ignore_ascii_case(mod_id)
+                    && matches!(
+                        (e.version.as_deref(), version),
+                        (Some(cached), Some(wanted)) if crate::version::same_version(cached, wanted)
+                    )
+            })
+            .or_else(|| {
+                let url = source_url?;
+                index
+                    .entries
+                    .values()
+                    .find(|e| e.source_urls.iter().any(|u| u == url))
+            })
+    };
+
+    found
+        .filter(|entry| blob_path(cache_dir, &entry.hash).exists())
+        .cloned()
+}
+
+// Add a verified zip to the cache (no-op for the blob if it is already there)
+pub(crate) fn store(
+    zip_path: &Path,
+    mod_id: &str,
+    version: Option<&str>,
+    source_url: Option<&str>,
+) -> Result<CacheEntry, String> {
+    store_in(
+        &get_cache_dir()?,
+        zip_path,
+        mod_id,
+        version,
+        source_url,
+        now_secs(),
+    )
+}
+
+fn store_in(
+    cache_dir: &Path,
+    zip_path: &Path,
+    mod_id: &str,
+    version: Option<&str>,
+    source_url: Option<&str>,
+    now: u64,
+) -> Result<CacheEntry, String> {
+    let hash = crate::mod_manager::hash_file(zip_path)?;
+    let blob = blob_path(cache_dir, &hash);
+    // Prefer what the zip says about itself over what the caller assumed
+    let modinfo = crate::mod_manager::extract_modinfo_from_zip(zip_path).ok();
+
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if !blob.exists() {
+        // Copy under a temporary name first so an interrupted copy is never a valid blob
+        let tmp = blob.with_extension("tmp");
+        std::fs::copy(zip_path, &tmp)
+            .map_err(|e| format!("Failed to copy mod into cache: {}", e))?;
+        std::fs::rename(&tmp, &blob).map_err(|e| format!("Failed to store cached mod: {}", e))?;
+    }
+
+    let size = std::fs::metadata(&blob)
+        .map(|m| m.len())
+        .map_err(|e| format!("Failed to read cached mod: {}", e))?;
+
+    let mut index = load_cache_index(cache_dir);
+    let entry = index
+        .entries
+        .entry(hash.clone())
+        .or_insert_with(|| CacheEntry {
+            hash: hash.clone(),
+            modid: modinfo
+                .as_ref()
+                .map(|i| i.modid.clone())
+                .unwrap_or_else(|| mod_id.to_string()),
+            version: modinfo
+                .as_ref()
+                .map(|i| i.version.clone())
+                .or_else(|| version.map(|v| v.to_string())),
+            size,
+            source_urls: Vec::new(),
+            added_at: now,
+            last_used: now,
+        });
+    entry.last_used = now;
+    if let Some(url) = source_url {
+        if !entry.source_urls.iter().any(|u| u == url) {
+            entry.source_urls.push(url.to_string());
+        }
+    }
+    let entry = entry.clone();
+
+    save_cache_index(cache_dir, &index)?;
+    Ok(entry)
+}
+
+// Copy a cached zip into the mods folder through the same verified, atomic
+// path a fresh download takes
+pub(crate) fn install_from_cache(
+    entry: &CacheEntry,
+    mod_id: &str,
+    mods_dir: &Path,
+) -> Result<PathBuf, String> {
+    let cache_dir = get_cache_dir()?;
+    let blob = blob_path(&cache_dir, &entry.hash);
+    let part_path = crate::api_client::partial_download_path(
+        mods_dir,
+        mod_id,
+        &format!("cache:{}", entry.hash),
+    );
+
+    std::fs::copy(&blob, &part_path).map_err(|e| format!("Failed to copy cached mod: {}", e))?;
+
+    let zip_path = crate::api_client::install_verified_zip(
+        mod_id,
+        &part_path,
+        mods_dir,
+        Some(entry.size),
+        Some(&entry.hash),
+    )?;
+
+    {
+        let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut index = load_cache_index(&cache_dir);
+        if let Some(cached) = index.entries.get_mut(&entry.hash) {
+            cached.last_used = now_secs();
+            save_cache_index(&cache_dir, &index)?;
+        }
+    }
+
+    eprintln!(
+        "[download_cache] Installed {} from cache",
+        entry_label(entry)
+    );
+    Ok(zip_path)
+}
+
+fn remove_entry(cache_dir: &Path, index: &mut CacheIndex, hash: &str, report: &mut PruneReport) {
+    if let Some(entry) = index.entries.remove(hash) {
+        if let Err(e) = std::fs::remove_file(blob_path(cache_dir, hash)) {
+            eprintln!("Failed to remove cached mod {}: {}", entry_label(&entry), e);
+        }
+        report.freed_bytes += entry.size;
+        report.removed.push(entry_label(&entry));
+    }
+}
+
+#[command]
+pub async fn get_download_cache_info() -> Result<DownloadCacheInfo, String> {
+    let cache_dir = get_cache_dir()?;
+    let index = {
+        let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load_cache_index(&cache_dir)
+    };
+
+    let mut entries: Vec<CacheEntry> = index.entries.into_values().collect();
+    entries.sort_by(|a, b| a.modid.cmp(&b.modid).then(a.version.cmp(&b.version)));
+
+    Ok(DownloadCacheInfo {
+        path: cache_dir.to_string_lossy().to_string(),
+        entry_count: entries.len(),
+        total_bytes: entries.iter().map(|e| e.size).sum(),
+        entries,
+    })
+}
+
+#[command]
+pub async fn prune_download_cache(
+    max_age_days: Option<u64>,
+    max_size_mb: Option<u64>,
+) -> Result<PruneReport, String> {
+    prune_in(&get_cache_dir()?, max_age_days, max_size_mb, now_secs())
+}
+
+fn prune_in(
+    cache_dir: &Path,
+    max_age_days: Option<u64>,
+    max_size_mb: Option<u64>,
+    now: u64,
+) -> Result<PruneReport, String> {
+    let _guard = CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut index = load_cache_index(cache_dir);
+    let mut report = PruneReport {
+        removed: Vec::new(),
+        freed_bytes: 0,
+    };
+
+    if let Some(days) = max_age_days {
+        let cutoff = now.saturating_sub(days.saturating_mul(24 * 60 * 60));
+        let expired: Vec<String> = index
+            .entries
+            .values()
+            .filter(|e| e.last_used < cutoff)
+            .map(|e| e.hash.clone())
+            .collect();
+        for hash in expired {
+            remove_entry(cache_dir, &mut index, &hash, &mut report);
+        }
+    }
+
+    if let Some(max_mb) = max_size_mb {
+        let max_bytes = max_mb.saturating_mul(1024 * 1024);
+        let mut by_age: Vec<(u64, String, u64)> = index
+            .entries
+            .values()
+            .map(|e| (e.last_used, e.hash.clone(), e.size))
+            .collect();
+        by_age.sort();
+
+        // Evict least recently used files until the cache fits
+        let mut total: u64 = by_age.iter().map(|(_, _, size)| size).sum();
+        for (_, hash, size) in by_age {
+            if total <= max_bytes {
+                break;
+            }
+            remove_entry(cache_dir, &mut index, &hash, &mut report);
+            total -= size;
+        }
+    }
+
+    save_cache_index(cache_dir, &index)?;
+    Ok(report)
+}
+
+#[command]
+pub async fn seed_download_cache(mods_path: String) -> Result<Vec<String>, String> {
+    let mods_dir = Path::new(&mods_path);
+    let mut candidates = Vec::new();
+
+    for dir in [mods_dir.to_path_buf(), mods_dir.join("disabled")] {
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let ext = path.extension().and_then(|s| s.to_str());
+            if path.is_file() && matches!(ext, Some("zip") | Some("disabled")) {
+                candidates.push(path);
+            }
+        }
+    }
+
+    let mut seeded = Vec::new();
+    for path in candidates {
+        // Only zips that identify themselves can be found again by modid@version
+        let info = match crate::mod_manager::extract_modinfo_from_zip(&path) {
+            Ok(info) => info,
+            Err(e) => {
+                eprintln!("Skipping {} for cache: {}", path.display(), e);
+                continue;
+            }
+        };
+        match store(&path, &info.modid, Some(&info.version), None) {
+            Ok(entry) => seeded.push(entry_label(&entry)),
+            Err(e) => eprintln!("Failed to cache {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(seeded)
+}
+
+#[command]
+pub async fn install_cached_mod(
+    mod_id: String,
+    mods_path: String,
+    version: Option<String>,
+    expected_hash: Option<String>,
+    override_hold: Option<bool>,
+) -> Result<Option<String>, String> {
+    use crate::download_queue::{self, DownloadRequest};
+
+    let entry = match lookup(&mod_id, version.as_deref(), expected_hash.as_deref(), None) {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+
+    // The queue installs the cached copy it finds by hash, checking holds
+    let item = download_queue::enqueue(DownloadRequest {
+        source: crate::mod_source::registered_source(&mod_id),
+        mod_id,
+        version: entry.version.or(version),
+        download_url: None,
+        mods_path,
+        expected_hash: Some(entry.hash),
+        override_hold: override_hold.unwrap_or(false),
+    });
+    download_queue::wait_for(&item.id).await.map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    // A cache dir and a stored file of `size` bytes per (modid, version, url, last used)
+    fn cache_with(files: &[(&str, &str, &str, u64)], size: usize) -> (TempDir, Vec<CacheEntry>) {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::create_dir_all(temp_dir.path().join("blobs")).unwrap();
+        let entries = files
+            .iter()
+            .enumerate()
+            .map(|(i, (mod_id, version, url, used))| {
+                let zip_path = temp_dir.path().join(format!("{}.zip", i));
+                let mut content = vec![0u8; size];
+                content[0] = i as u8;
+                std::fs::write(&zip_path, content).unwrap();
+                store_in(
+                    temp_dir.path(),
+                    &zip_path,
+                    mod_id,
+                    Some(version),
+                    Some(url),
+                    *used,
+                )
+                .unwrap()
+            })
+            .collect();
+        (temp_dir, entries)
+    }
+
+    #[test]
+    fn test_lookup_prefers_hash_then_version_then_url() {
+        let (cache, entries) = cache_with(
+            &[
+                ("examplemod", "1.0.0", "https://example.com/a.zip", 0),
+                ("examplemod", "1.1.0", "https://example.com/b.zip", 0),
+            ],
+            16,
+        );
+        let dir = cache.path();
+        let found = |version: Option<&str>, hash: Option<&str>, url: Option<&str>| {
+            lookup_in(dir, "ExampleMod", version, hash, url).map(|e| e.hash)
+        };
+
+        let (a, b) = (Some(entries[0].hash.clone()), Some(entries[1].hash.clone()));
+        assert_eq!(found(Some("1.0.0"), b.as_deref(), None), b);
+        assert_eq!(
+            found(Some("v1.0"), None, Some("https://example.com/b.zip")),
+            a
+        );
+        assert_eq!(
+            found(Some("2.0.0"), None, Some("https://example.com/b.zip")),
+            b
+        );
+        assert_eq!(found(Some("2.0.0"), None, None), None);
+        assert_eq!(found(Some("1.0.0"), Some("0123abcd"), None), None);
+
+        std::fs::remove_file(blob_path(dir, &entries[0].hash)).unwrap();
+        assert_eq!(found(Some("1.0.0"), None, None), None);
+    }
+
+    #[test]
+    fn test_prune_by_age_then_size() {
+        const DAY: u64 = 24 * 60 * 60;
+        let now = 100 * DAY;
+        // 600 KiB each, so any two are over 1 MiB. Named for their age: a
+        // month, earlier this week, a day, and just now.
+        let (cache, entries) = cache_with(
+            &[
+                (
+                    "stale",
+                    "1.0.0",
+                    "https://example.com/stale.zip",
+                    now - 30 * DAY,
+                ),
+                (
+                    "week",
+                    "1.0.0",
+                    "https://example.com/week.zip",
+                    now - 3 * DAY,
+                ),
+                (
+                    "recent",
+                    "1.0.0",
+                    "https://example.com/recent.zip",
+                    now - DAY,
+                ),
+                ("newest", "1.0.0", "https://example.com/newest.zip", now),
+            ],
+            600 * 1024,
+        );
+        let dir = cache.path();
+
+        let report = prune_in(dir, Some(u64::MAX), Some(u64::MAX), now).unwrap();
+        assert!(report.removed.is_empty());
+
+        let report = prune_in(dir, Some(7), None, now).unwrap();
+        assert_eq!(report.removed, vec!["stale@1.0.0"]);
+        assert!(!blob_path(dir, &entries[0].hash).exists());
+
+        let report = prune_in(dir, None, Some(1), now).unwrap();
+        assert_eq!(report.removed, vec!["week@1.0.0", "recent@1.0.0"]);
+        assert_eq!(report.freed_bytes, 2 * 600 * 1024);
+
+        let remaining: Vec<String> = load_cache_index(dir)
+            .entries
+            .values()
+            .map(|e| e.modid.clone())
+            .collect();
+        assert_eq!(remaining, vec!["newest"]);
+    }
+}
@@ -0,0 +1,655 @@
+// Queue shared by every mod download. Items run with a configurable number in
+// parallel, move through queued -> downloading -> verifying -> done/failed,
+// and the whole queue is saved so it survives an app restart. Every install,
+// from the network or the download cache, goes through here, so this is where
+// mod holds are enforced.
+
+use crate::mod_source::{ModSource, ModSourceSpec, ResolvedMod};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::command;
+use tokio::sync::watch;
+
+const DEFAULT_PARALLEL_DOWNLOADS: usize = 3;
+const MAX_PARALLEL_DOWNLOADS: usize = 8;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DownloadState {
+    Queued,
+    Downloading, // Includes resolving the download URL when none was given
+    Verifying,
+    Done,
+    Failed,
+}
+
+impl DownloadState {
+    fn is_finished(self) -> bool {
+        matches!(self, DownloadState::Done | DownloadState::Failed)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadItem {
+    pub id: String,
+    pub mod_id: String,
+    pub version: Option<String>,
+    pub download_url: Option<String>, // As requested; resolved through the source when not given
+    #[serde(default)]
+    pub resolved_url: Option<String>, // Where the source said to download from
+    pub mods_path: String,
+    pub expected_hash: Option<String>,
+    #[serde(default)]
+    pub source: Option<ModSourceSpec>, // The ModDB when not given
+    #[serde(default)]
+    pub override_hold: bool, // Install even if the mod is held at another version
+    pub state: DownloadState,
+    pub attempts: u32,
+    pub error: Option<String>,
+    pub path: Option<String>, // Installed zip once done
+    pub added_at: u64,        // Unix seconds
+    pub updated_at: u64,      // Unix seconds
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DownloadRequest {
+    pub mod_id: String,
+    pub version: Option<String>,
+    pub download_url: Option<String>,
+    pub mods_path: String,
+    pub expected_hash: Option<String>,
+    #[serde(default)]
+    pub source: Option<ModSourceSpec>,
+    #[serde(default)]
+    pub override_hold: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+struct QueueFile {
+    next_id: u64,
+    items: Vec<DownloadItem>,
+}
+
+struct DownloadQueue {
+    state: Mutex<QueueFile>,
+    changed: watch::Sender<u64>, // Bumped on every change so waiters re-check
+    saved: Mutex<u64>,           // Change number of the copy on disk
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn get_queue_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_dir.join("download-queue.json"))
+}
+
+fn load_queue_file() -> QueueFile {
+    let queue_path = match get_queue_path() {
+        Ok(path) if path.exists() => path,
+        _ => return QueueFile::default(),
+    };
+
+    match std::fs::read_to_string(&queue_path) {
+        Ok(content) => match serde_json::from_str::<QueueFile>(&content) {
+            Ok(file) => file,
+            Err(e) => {
+                eprintln!("Failed to parse download queue: {}", e);
+                QueueFile::default()
+            }
+        },
+        Err(e) => {
+            eprintln!("Failed to read download queue: {}", e);
+            QueueFile::default()
+        }
+    }
+}
+
+fn save_queue_file(file: &QueueFile) {
+    let result = get_queue_path().and_then(|path| {
+        let content = serde_json::to_string_pretty(file)
+            .map_err(|e| format!("Failed to serialize download queue: {}", e))?;
+        let temp_path = path.with_extension("json.tmp");
+        std::fs::write(&temp_path, content)
+            .and_then(|_| std::fs::rename(&temp_path, &path))
+            .map_err(|e| format!("Failed to write download queue: {}", e))
+    });
+    if let Err(e) = result {
+        eprintln!("[download_queue] {}", e);
+    }
+}
+
+// Items cut off by a restart go back to the queue; their partial files are
+// resumed by the download itself
+fn requeue_interrupted(file: &mut QueueFile) {
+    for item in &mut file.items {
+        if matches!(
+            item.state,
+            DownloadState::Downloading | DownloadState::Verifying
+        ) {
+            item.state = DownloadState::Queued;
+        }
+    }
+}
+
+fn queue() -> &'static DownloadQueue {
+    static QUEUE: OnceLock<DownloadQueue> = OnceLock::new();
+    QUEUE.get_or_init(|| {
+        let mut file = load_queue_file();
+        requeue_interrupted(&mut file);
+        DownloadQueue {
+            state: Mutex::new(file),
+            changed: watch::channel(0).0,
+            saved: Mutex::new(0),
+        }
+    })
+}
+
+impl DownloadQueue {
+    // Change the queue under its lock, then save a copy of it in the
+    // background so slow disks do not hold up every other queue operation
+    fn with_state<T>(&self, f: impl FnOnce(&mut QueueFile) -> T) -> T {
+        let (result, snapshot, revision) = {
+            let mut file = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            let result = f(&mut file);
+            let mut revision = 0;
+            self.changed.send_modify(|v| {
+                *v = v.wrapping_add(1);
+                revision = *v;
+            });
+            (result, file.clone(), revision)
+        };
+
+        tauri::async_runtime::spawn_blocking(move || queue().save(&snapshot, revision));
+        result
+    }
+
+    // Saves run in any order; one older than what is on disk is skipped
+    fn save(&self, snapshot: &QueueFile, revision: u64) {
+        let mut saved = self.saved.lock().unwrap_or_else(|e| e.into_inner());
+        if revision > *saved {
+            save_queue_file(snapshot);
+            *saved = revision;
+        }
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut DownloadItem)) {
+        self.with_state(|file| {
+            if let Some(item) = file.items.iter_mut().find(|i| i.id == id) {
+                f(item);
+                item.updated_at = now_secs();
+            }
+        });
+    }
+
+    fn get(&self, id: &str) -> Option<DownloadItem> {
+        let file = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        file.items.iter().find(|i| i.id == id).cloned()
+    }
+}
+
+// Add a download, or return the unfinished item already fetching the same
+// thing: the same mod and version from the same source and requested URL,
+// checked against the same hash, into the same folder, with the same say over
+// holds
+fn add_request(file: &mut QueueFile, request: DownloadRequest, now: u64) -> DownloadItem {
+    let source = request.source.clone().unwrap_or_default();
+    if let Some(existing) = file.items.iter().find(|i| {
+        !i.state.is_finished()
+            && i.mod_id == request.mod_id
+            && i.mods_path == request.mods_path
+            && i.version == request.version
+            && i.source.clone().unwrap_or_default() == source
+            && i.download_url == request.download_url
+            && i.expected_hash == request.expected_hash
+            && i.override_hold == request.override_hold
+    }) {
+        return existing.clone();
+    }
+
+    file.next_id += 1;
+    let item = DownloadItem {
+        id: format!("{}-{}", file.next_id, request.mod_id),
+        mod_id: request.mod_id,
+        version: request.version,
+        download_url: request.download_url,
+        resolved_url: None,
+        mods_path: request.mods_path,
+        expected_hash: request.expected_hash,
+        source: request.source,
+        override_hold: request.override_hold,
+        state: DownloadState::Queued,
+        attempts: 0,
+        error: None,
+        path: None,
+        added_at: now,
+        updated_at: now,
+    };
+    file.items.push(item.clone());
+    item
+}
+
+pub(crate) fn enqueue(request: DownloadRequest) -> DownloadItem {
+    let item = queue().with_state(|file| add_request(file, request, now_secs()));
+    pump();
+    item
+}
+
+// Wait until an item is done (returning the installed zip) or failed
+pub(crate) async fn wait_for(id: &str) -> Result<String, String> {
+    let mut changed = queue().changed.subscribe();
+
+    loop {
+        let item = queue()
+            .get(id)
+            .ok_or_else(|| format!("Download {} is not in the queue", id))?;
+        match item.state {
+            DownloadState::Done => return Ok(item.path.unwrap_or_default()),
+            DownloadState::Failed => {
+                return Err(item
+                    .error
+                    .unwrap_or_else(|| format!("Download of {} failed", item.mod_id)))
+            }
+            _ => {}
+        }
+
+        changed
+            .changed()
+            .await
+            .map_err(|e| format!("Download queue closed: {}", e))?;
+    }
+}
+
+// Start queued items while there is room under the parallel download limit
+fn pump() {
+    tauri::async_runtime::spawn(dispatch());
+}
+
+async fn dispatch() {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+    let parallelism = settings
+        .parallel_downloads
+        .unwrap_or(DEFAULT_PARALLEL_DOWNLOADS)
+        .clamp(1, MAX_PARALLEL_DOWNLOADS);
+
+    let started = queue().with_state(|file| start_queued(file, parallelism, now_secs()));
+    for item in started {
+        tauri::async_runtime::spawn(run_item(item));
+    }
+}
+
+// Move queued items to downloading, oldest first, until `parallelism` run
+fn start_queued(file: &mut QueueFile, parallelism: usize, now: u64) -> Vec<DownloadItem> {
+    let active = file
+        .items
+        .iter()
+        .filter(|i| {
+            matches!(
+                i.state,
+                DownloadState::Downloading | DownloadState::Verifying
+            )
+        })
+        .count();
+
+    let mut started = Vec::new();
+    for item in file.items.iter_mut() {
+        if active + started.len() >= parallelism {
+            break;
+        }
+        if item.state == DownloadState::Queued {
+            item.state = DownloadState::Downloading;
+            item.attempts += 1;
+            item.error = None;
+            item.updated_at = now;
+            started.push(item.clone());
+        }
+    }
+    started
+}
+
+async fn run_item(item: DownloadItem) {
+    let result = process_item(&item).await;
+
+    // Update checks and updates go back to where the mod came from
+    if let (Ok(_), Some(source)) = (&result, &item.source) {
+        crate::mod_source::remember_source(&item.mod_id, source);
+    }
+
+    queue().update(&item.id, |queued| match result {
+        Ok(zip_path) => {
+            queued.state = DownloadState::Done;
+            queued.path = Some(zip_path.to_string_lossy().to_string());
+        }
+        Err(e) => {
+            eprintln!("[download_queue] {} failed: {}", item.mod_id, e);
+            queued.state = DownloadState::Failed;
+            queued.error = Some(e);
+        }
+    });
+
+    pump();
+}
+
+// A held mod is only installed at its held version, unless the item overrides
+// the hold. `version` is what is about to be installed, if known.
+fn check_hold(item: &DownloadItem, version: Option<&str>) -> Result<(), String> {
+    crate::holds::check_hold(
+        &item.mods_path,
+        &item.mod_id,
+        version.or(item.version.as_deref()),
+        item.override_hold,
+    )
+}
+
+async fn process_item(item: &DownloadItem) -> Result<PathBuf, String> {
+    use crate::download_cache;
+
+    // A file fetched before (for any pack or profile) needs no network at all
+    if let Some(entry) = download_cache::lookup(
+        &item.mod_id,
+        item.version.as_deref(),
+        item.expected_hash.as_deref(),
+        item.download_url.as_deref(),
+    ) {
+        check_hold(item, entry.version.as_deref())?;
+        match download_cache::install_from_cache(&entry, &item.mod_id, Path::new(&item.mods_path)) {
+            Ok(zip_path) => return Ok(zip_path),
+            Err(e) => eprintln!("[download_queue] Cached copy unusable, downloading: {}", e),
+        }
+    }
+
+    let source = item.source.clone().unwrap_or_default();
+
+    let resolved = match &item.download_url {
+        Some(url) => ResolvedMod {
+            version: item.version.clone(),
+            location: url.clone(),
+        },
+        None => {
+            let game_version = crate::game_detector::current_game_version().await;
+            let resolved = source
+                .resolve(
+                    &item.mod_id,
+                    item.version.as_deref(),
+                    game_version.as_deref(),
+                )
+                .await?;
+            queue().update(&item.id, |queued| {
+                queued.resolved_url = Some(resolved.location.clone())
+            });
+            resolved
+        }
+    };
+    check_hold(item, resolved.version.as_deref())?;
+
+    source
+        .download(
+            &item.mod_id,
+            &resolved,
+            Path::new(&item.mods_path),
+            item.expected_hash.as_deref(),
+            || queue().update(&item.id, |queued| queued.state = DownloadState::Verifying),
+        )
+        .await
+}
+
+// Continue downloads left in the queue by the previous session
+pub(crate) fn resume_pending() {
+    let pending = {
+        let file = queue().state.lock().unwrap_or_else(|e| e.into_inner());
+        file.items
+            .iter()
+            .filter(|i| i.state == DownloadState::Queued)
+            .count()
+    };
+    if pending > 0 {
+        eprintln!("[download_queue] Resuming {} queued downloads", pending);
+        pump();
+    }
+}
+
+#[command]
+pub async fn get_download_queue() -> Result<Vec<DownloadItem>, String> {
+    let file = queue().state.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(file.items.clone())
+}
+
+#[command]
+pub async fn enqueue_downloads(
+    requests: Vec<DownloadRequest>,
+) -> Result<Vec<DownloadItem>, String> {
+    Ok(requests.into_iter().map(enqueue).collect())
+}
+
+#[command]
+pub async fn wait_for_downloads(ids: Vec<String>) -> Result<Vec<DownloadItem>, String> {
+    let mut items = Vec::new();
+    for id in ids {
+        // Failures are reported through the item state, not as an error
+        let _ = wait_for(&id).await;
+        if let Some(item) = queue().get(&id) {
+            items.push(item);
+        }
+    }
+    Ok(items)
+}
+
+// Put a failed item back in the queue
+fn requeue_failed(file: &mut QueueFile, id: &str, now: u64) -> Result<DownloadItem, String> {
+    let item = file
+        .items
+        .iter_mut()
+        .find(|i| i.id == id)
+        .ok_or_else(|| format!("Download {} is not in the queue", id))?;
+    if item.state != DownloadState::Failed {
+        return Err(format!("Download of {} has not failed", item.mod_id));
+    }
+
+    item.state = DownloadState::Queued;
+    item.error = None;
+    item.updated_at = now;
+    Ok(item.clone())
+}
+
+#[command]
+pub async fn retry_download(id: String) -> Result<DownloadItem, String> {
+    let item = queue().with_state(|file| requeue_failed(file, &id, now_secs()))?;
+    pump();
+    Ok(item)
+}
+
+#[command]
+pub async fn clear_finished_downloads() -> Result<usize, String> {
+    Ok(queue().with_state(|file| {
+        let before = file.items.len();
+        file.items.retain(|i| !i.state.is_finished());
+        before - file.items.len()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, state: DownloadState) -> DownloadItem {
+        DownloadItem {
+            id: id.to_string(),
+            mod_id: id.to_string(),
+            version: None,
+            download_url: None,
+            resolved_url: None,
+            mods_path: "/mods".to_string(),
+            expected_hash: None,
+            source: None,
+            override_hold: false,
+            state,
+            attempts: 1,
+            error: None,
+            path: None,
+            added_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_requeue_interrupted() {
+        let mut file = QueueFile {
+            next_id: 4,
+            items: vec![
+                item("a", DownloadState::Downloading),
+                item("b", DownloadState::Verifying),
+                item("c", DownloadState::Done),
+                item("d", DownloadState::Failed),
+            ],
+        };
+
+        requeue_interrupted(&mut file);
+
+        let states: Vec<DownloadState> = file.items.iter().map(|i| i.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                DownloadState::Queued,
+                DownloadState::Queued,
+                DownloadState::Done,
+                DownloadState::Failed
+            ]
+        );
+    }
+
+    fn request(mod_id: &str, url: Option<&str>, hash: Option<&str>) -> DownloadRequest {
+        DownloadRequest {
+            mod_id: mod_id.to_string(),
+            version: Some("1.0.0".to_string()),
+            download_url: url.map(|u| u.to_string()),
+            mods_path: "/mods".to_string(),
+            expected_hash: hash.map(|h| h.to_string()),
+            source: None,
+            override_hold: false,
+        }
+    }
+
+    #[test]
+    fn test_add_request_dedups_unfinished() {
+        let mut file = QueueFile::default();
+        let first = add_request(&mut file, request("a", Some("https://x/a.zip"), None), 1);
+
+        let same = add_request(&mut file, request("a", Some("https://x/a.zip"), None), 2);
+        assert_eq!(same.id, first.id);
+        assert_eq!(file.items.len(), 1);
+
+        // Another URL or hash is another download
+        let other_url = add_request(&mut file, request("a", Some("https://y/a.zip"), None), 3);
+        let other_hash = add_request(
+            &mut file,
+            request("a", Some("https://x/a.zip"), Some("ab")),
+            4,
+        );
+        assert_ne!(other_url.id, first.id);
+        assert_ne!(other_hash.id, first.id);
+        assert_ne!(other_hash.id, other_url.id);
+        assert_eq!(file.items.len(), 3);
+
+        // Overriding a hold is not the same request as respecting it
+        let mut overriding = request("a", Some("https://x/a.zip"), None);
+        overriding.override_hold = true;
+        let overriding = add_request(&mut file, overriding, 5);
+        assert_ne!(overriding.id, first.id);
+        assert!(overriding.override_hold);
+        file.items.pop();
+
+        // A finished item is fetched again
+        file.items[0].state = DownloadState::Done;
+        let again = add_request(&mut file, request("a", Some("https://x/a.zip"), None), 5);
+        assert_ne!(again.id, first.id);
+        assert_eq!(file.items.len(), 4);
+    }
+
+    #[test]
+    fn test_add_request_dedups_by_source() {
+        let mut file = QueueFile::default();
+        let moddb = add_request(&mut file, request("a", None, None), 1);
+
+        // No source means the ModDB
+        let mut explicit = request("a", None, None);
+        explicit.source = Some(ModSourceSpec::Moddb { id: None });
+        assert_eq!(add_request(&mut file, explicit, 2).id, moddb.id);
+
+        let mut github = request("a", None, None);
+        github.source = Some(ModSourceSpec::Github {
+            repo: "someone/a".to_string(),
+            base_url: None,
+            prereleases: false,
+        });
+        let github = add_request(&mut file, github, 3);
+        assert_ne!(github.id, moddb.id);
+
+        // Resolving the URL does not change what the item was asked for
+        file.items[0].resolved_url = Some("https://x/a.zip".to_string());
+        assert_eq!(
+            add_request(&mut file, request("a", None, None), 4).id,
+            moddb.id
+        );
+        assert_eq!(file.items.len(), 2);
+    }
+
+    #[test]
+    fn test_start_queued_respects_parallelism() {
+        let mut file = QueueFile {
+            next_id: 5,
+            items: vec![
+                item("a", DownloadState::Verifying),
+                item("b", DownloadState::Done),
+                item("c", DownloadState::Queued),
+                item("d", DownloadState::Queued),
+                item("e", DownloadState::Queued),
+            ],
+        };
+
+        let started = start_queued(&mut file, 3, 7);
+        let ids: Vec<&str> = started.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["c", "d"]);
+        assert_eq!(file.items[2].state, DownloadState::Downloading);
+        assert_eq!(file.items[2].attempts, 2);
+        assert_eq!(file.items[4].state, DownloadState::Queued);
+
+        assert!(start_queued(&mut file, 3, 8).is_empty());
+        file.items[0].state = DownloadState::Done;
+        let started = start_queued(&mut file, 3, 9);
+        assert_eq!(started.len(), 1);
+        assert_eq!(started[0].id, "e");
+    }
+
+    #[test]
+    fn test_requeue_failed() {
+        let mut file = QueueFile {
+            next_id: 2,
+            items: vec![
+                item("a", DownloadState::Failed),
+                item("b", DownloadState::Downloading),
+            ],
+        };
+        file.items[0].error = Some("timed out".to_string());
+
+        let retried = requeue_failed(&mut file, "a", 3).unwrap();
+        assert_eq!(retried.state, DownloadState::Queued);
+        assert_eq!(retried.error, None);
+        assert!(requeue_failed(&mut file, "a", 4).is_err());
+        assert!(requeue_failed(&mut file, "b", 4).is_err());
+        assert!(requeue_failed(&mut file, "z", 4).is_err());
+
+        let started = start_queued(&mut file, 2, 5);
+        assert_eq!(started[0].id, "a");
+        assert_eq!(started[0].attempts, 2);
+    }
+}
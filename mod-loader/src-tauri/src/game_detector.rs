@@ -1,4 +1,7 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 #[tauri::command]
 pub async fn detect_vintage_story_path() -> Result<Option<String>, String> {
@@ -22,6 +25,199 @@ pub async fn get_vintage_story_path() -> Result<String, String> {
     Ok(mods_path.to_string_lossy().to_string())
 }
 
+// Game version used when picking releases: the configured setting wins, then
+// the version of the installed game, then the version the game reported in
+// its last log
+pub(crate) async fn current_game_version() -> Option<String> {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+
+    if let Some(version) = settings.game_version.as_deref().map(str::trim) {
+        if !version.is_empty() {
+            return Some(crate::version::normalize(version).to_string());
+        }
+    }
+
+    if let Some(version) = installed_game_version_blocking(settings.game_install_path).await {
+        return Some(version);
+    }
+
+    let data_paths = match settings.vintage_story_path {
+        Some(path) => vec![PathBuf::from(path)],
+        None => get_default_paths(),
+    };
+
+    data_paths
+        .iter()
+        .find_map(|path| detect_game_version_from_logs(path))
+}
+
+// Version of the game installed at the configured install directory, or at
+// one of the usual install locations
+#[tauri::command]
+pub async fn detect_installed_game_version() -> Result<Option<String>, String> {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+    Ok(installed_game_version_blocking(settings.game_install_path).await)
+}
+
+// Reading the assemblies is file IO, so it stays off the async runtime
+async fn installed_game_version_blocking(install_path: Option<String>) -> Option<String> {
+    tokio::task::spawn_blocking(move || installed_game_version(install_path.as_deref()))
+        .await
+        .ok()
+        .flatten()
+}
+
+fn installed_game_version(install_path: Option<&str>) -> Option<String> {
+    let install_paths = match install_path.map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => vec![PathBuf::from(path)],
+        None => get_default_install_paths(),
+    };
+
+    install_paths
+        .iter()
+        .find_map(|path| detect_game_version_from_install(path))
+}
+
+// The base game's `assets` folder, in the configured install directory or
+// one of the usual install locations
+pub(crate) async fn game_assets_dir() -> Option<PathBuf> {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+    let install_paths = match settings
+        .game_install_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(path) => vec![PathBuf::from(path)],
+        None => get_default_install_paths(),
+    };
+
+    install_paths
+        .into_iter()
+        .map(|path| path.join("assets"))
+        .find(|assets| assets.is_dir())
+}
+
+// Version read from each assembly, with the modification time and size it
+// had then. The game is only updated now and then, while the version is
+// asked for by every status check and download.
+type DllVersions = HashMap<PathBuf, (SystemTime, u64, Option<String>)>;
+
+static DLL_VERSIONS: Mutex<Option<DllVersions>> = Mutex::new(None);
+
+// The game's assemblies carry its version in their version resource, so
+// read the ProductVersion of VintagestoryAPI.dll
+fn detect_game_version_from_install(install_path: &Path) -> Option<String> {
+    ["VintagestoryAPI.dll", "VintagestoryLib.dll"]
+        .iter()
+        .flat_map(|name| [install_path.join(name), install_path.join("Lib").join(name)])
+        .find_map(|dll| dll_version(&dll))
+}
+
+fn dll_version(dll: &Path) -> Option<String> {
+    let metadata = std::fs::metadata(dll).ok()?;
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let size = metadata.len();
+
+    if let Some((cached_modified, cached_size, version)) = DLL_VERSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .get(dll)
+    {
+        if *cached_modified == modified && *cached_size == size {
+            return version.clone();
+        }
+    }
+
+    let version = std::fs::read(dll).ok().and_then(|bytes| {
+        read_version_resource(&bytes, "ProductVersion")
+            .or_else(|| read_version_resource(&bytes, "FileVersion"))
+    });
+    DLL_VERSIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_or_insert_with(HashMap::new)
+        .insert(dll.to_path_buf(), (modified, size, version.clone()));
+    version
+}
+
+// Find a string in a PE version resource: the UTF-16 key, zero padding up to
+// the value, then the UTF-16 value. Build metadata ("+abc123") is dropped and
+// only values that parse as a version are accepted.
+fn read_version_resource(bytes: &[u8], key: &str) -> Option<String> {
+    let needle: Vec<u8> = key
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect();
+    let mut pos = bytes
+        .windows(needle.len())
+        .position(|window| window == needle.as_slice())?
+        + needle.len();
+
+    while bytes.get(pos..pos + 2) == Some(&[0, 0]) {
+        pos += 2;
+    }
+
+    let units: Vec<u16> = bytes
+        .get(pos..)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|unit| *unit != 0)
+        .take(64)
+        .collect();
+    let value = String::from_utf16(&units).ok()?;
+    let version = crate::version::normalize(value.split('+').next()?);
+
+    crate::version::Version::parse(version)
+        .ok()
+        .map(|_| version.to_string())
+}
+
+// Vintage Story logs a line like "Game Version: v1.19.8 (Stable)" on startup
+fn detect_game_version_from_logs(data_path: &Path) -> Option<String> {
+    let logs_dir = data_path.join("Logs");
+    let entries = std::fs::read_dir(&logs_dir).ok()?;
+
+    let mut log_files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with("client-main") || n.starts_with("server-main"))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    // Most recently written log first
+    log_files.sort_by_key(|p| {
+        std::cmp::Reverse(
+            std::fs::metadata(p)
+                .and_then(|m| m.modified())
+                .unwrap_or(std::time::SystemTime::UNIX_EPOCH),
+        )
+    });
+
+    log_files.iter().find_map(|path| {
+        let content = std::fs::read_to_string(path).ok()?;
+        parse_game_version_line(&content)
+    })
+}
+
+fn parse_game_version_line(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let rest = &line[line.find("Game Version:")? + "Game Version:".len()..];
+        let version = crate::version::normalize(rest.split_whitespace().next()?);
+        if version.is_empty() {
+            None
+        } else {
+            Some(version.to_string())
+        }
+    })
+}
+
 fn get_default_paths() -> Vec<PathBuf> {
     let mut paths = Vec::new();
 
@@ -54,6 +250,48 @@ fn get_default_paths() -> Vec<PathBuf> {
     paths
 }
 
+// Where the installers put the game itself (not its data folder)
+fn get_default_install_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+
+    #[cfg(target_os = "windows")]
+    {
+        if let Some(appdata) = std::env::var_os("APPDATA") {
+            paths.push(PathBuf::from(appdata).join("Vintagestory"));
+        }
+        if let Some(program_files) = std::env::var_os("ProgramFiles") {
+            paths.push(PathBuf::from(program_files).join("Vintagestory"));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        if let Some(home) = std::env::var_os("HOME") {
+            paths.push(
+                PathBuf::from(&home)
+                    .join(".local")
+                    .join("share")
+                    .join("vintagestory"),
+            );
+            paths.push(PathBuf::from(&home).join("vintagestory"));
+        }
+        paths.push(PathBuf::from("/opt/vintagestory"));
+        paths.push(PathBuf::from("/usr/share/vintagestory"));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        paths.push(
+            PathBuf::from("/Applications")
+                .join("Vintage Story.app")
+                .join("Contents")
+                .join("MacOS"),
+        );
+    }
+
+    paths
+}
+
 fn get_mods_directory_path() -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
     {
@@ -104,4 +342,74 @@ mod tests {
             assert!(p.to_string_lossy().contains("Mods"));
         }
     }
+
+    #[test]
+    fn test_parse_game_version_line() {
+        let log = "12.1.2025 10:00:00 [Notification] Client logger started.\n\
+                   12.1.2025 10:00:00 [Notification] Game Version: v1.19.8 (Stable)\n";
+        assert_eq!(parse_game_version_line(log), Some("1.19.8".to_string()));
+        assert_eq!(parse_game_version_line("no version here"), None);
+    }
+
+    #[test]
+    fn test_read_version_resource() {
+        let utf16 = |text: &str| -> Vec<u8> {
+            text.encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect()
+        };
+        let mut dll = b"MZ\x90\x00 not really a dll".to_vec();
+        dll.extend(utf16("FileVersion\0"));
+        dll.extend([0, 0]);
+        dll.extend(utf16("1.20.3.0\0"));
+        dll.extend(utf16("ProductVersion\0"));
+        dll.extend([0, 0]);
+        dll.extend(utf16("1.20.3+5e1c2b7\0"));
+
+        assert_eq!(
+            read_version_resource(&dll, "ProductVersion").as_deref(),
+            Some("1.20.3")
+        );
+        assert_eq!(
+            read_version_resource(&dll, "FileVersion").as_deref(),
+            Some("1.20.3.0")
+        );
+        assert_eq!(read_version_resource(&dll, "Comments"), None);
+    }
+
+    #[test]
+    fn test_installed_version_follows_dll_changes() {
+        let utf16 = |text: &str| -> Vec<u8> {
+            text.encode_utf16()
+                .flat_map(|unit| unit.to_le_bytes())
+                .collect()
+        };
+        let fake_dll = |version: &str| {
+            let mut dll = b"MZ".to_vec();
+            dll.extend(utf16("ProductVersion\0"));
+            dll.extend(utf16(version));
+            dll.extend([0, 0]);
+            dll
+        };
+
+        let install = tempfile::TempDir::new().unwrap();
+        let dll = install.path().join("VintagestoryAPI.dll");
+        std::fs::write(&dll, fake_dll("1.20.3")).unwrap();
+        assert_eq!(
+            detect_game_version_from_install(install.path()).as_deref(),
+            Some("1.20.3")
+        );
+        assert!(DLL_VERSIONS
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|versions| versions.contains_key(&dll)));
+
+        // A game update changes the file, so it is read again
+        std::fs::write(&dll, fake_dll("1.21.0-rc.1")).unwrap();
+        assert_eq!(
+            detect_game_version_from_install(install.path()).as_deref(),
+            Some("1.21.0-rc.1")
+        );
+    }
 }
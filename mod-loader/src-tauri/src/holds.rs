@@ -0,0 +1,200 @@
+// Mods held at a version, e.g. because a newer one breaks a world or does not
+// match a server. Holds belong to a mods folder, so each game profile keeps
+// its own. Anything that would install another version of a held mod refuses
+// unless the caller explicitly overrides the hold.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HeldMod {
+    pub mod_id: String,
+    pub version: String,
+    pub reason: Option<String>,
+    pub held_at: u64, // Unix seconds
+}
+
+// Holds per mods folder, then per lowercase modid
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct HoldsFile {
+    folders: HashMap<String, HashMap<String, HeldMod>>,
+}
+
+static HOLDS_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_holds_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_dir.join("mod-holds.json"))
+}
+
+fn load_holds_file() -> HoldsFile {
+    let path = match get_holds_path() {
+        Ok(path) if path.exists() => path,
+        _ => return HoldsFile::default(),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse mod holds: {}", e);
+            HoldsFile::default()
+        }),
+        Err(e) => {
+            eprintln!("Failed to read mod holds: {}", e);
+            HoldsFile::default()
+        }
+    }
+}
+
+fn update_holds<T>(
+    mods_path: &str,
+    f: impl FnOnce(&mut HashMap<String, HeldMod>) -> T,
+) -> Result<T, String> {
+    let _guard = HOLDS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = load_holds_file();
+    let result = f(file.folders.entry(mods_path.to_string()).or_default());
+    file.folders.retain(|_, holds| !holds.is_empty());
+
+    let content = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize mod holds: {}", e))?;
+    std::fs::write(get_holds_path()?, content)
+        .map_err(|e| format!("Failed to write mod holds: {}", e))?;
+    Ok(result)
+}
+
+// Holds of a mods folder, keyed by lowercase modid
+pub(crate) fn holds_for(mods_path: &str) -> HashMap<String, HeldMod> {
+    let _guard = HOLDS_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load_holds_file()
+        .folders
+        .remove(mods_path)
+        .unwrap_or_default()
+}
+
+pub(crate) fn held_mod(mods_path: &str, mod_id: &str) -> Option<HeldMod> {
+    holds_for(mods_path).remove(&mod_id.to_lowercase())
+}
+
+// Whether installing `version` of a mod (None: whatever is newest) is allowed
+fn check_against(
+    hold: Option<&HeldMod>,
+    mod_id: &str,
+    version: Option<&str>,
+    override_hold: bool,
+) -> Result<(), String> {
+    let Some(hold) = hold else {
+        return Ok(());
+    };
+    if override_hold || version.is_some_and(|v| crate::version::same_version(v, &hold.version)) {
+        return Ok(());
+    }
+    Err(format!(
+        "{} is held at version {}{}; release the hold or override it to change it",
+        mod_id,
+        hold.version,
+        hold.reason
+            .as_deref()
+            .map(|reason| format!(" ({})", reason))
+            .unwrap_or_default()
+    ))
+}
+
+pub(crate) fn check_hold(
+    mods_path: &str,
+    mod_id: &str,
+    version: Option<&str>,
+    override_hold: bool,
+) -> Result<(), String> {
+    check_against(
+        held_mod(mods_path, mod_id).as_ref(),
+        mod_id,
+        version,
+        override_hold,
+    )
+}
+
+#[command]
+pub async fn get_mod_holds(mods_path: String) -> Result<Vec<HeldMod>, String> {
+    let mut holds: Vec<HeldMod> = holds_for(&mods_path).into_values().collect();
+    holds.sort_by_key(|h| h.mod_id.to_lowercase());
+    Ok(holds)
+}
+
+// Hold a mod at `version`, or at the installed version when not given
+#[command]
+pub async fn hold_mod(
+    mods_path: String,
+    mod_id: String,
+    version: Option<String>,
+    reason: Option<String>,
+) -> Result<HeldMod, String> {
+    let version = match version
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+    {
+        Some(version) => version,
+        None => {
+            let mod_list = crate::mod_manager::scanned_mod_list(&mods_path).await?;
+            mod_list
+                .iter()
+                .find(|m| m.id.eq_ignore_ascii_case(&mod_id))
+                .map(|m| {
+                    m.info
+                        .as_ref()
+                        .map(|i| i.version.clone())
+                        .unwrap_or_else(|| m.version.clone())
+                })
+                .ok_or_else(|| format!("Mod {} is not installed", mod_id))?
+        }
+    };
+
+    let held = HeldMod {
+        mod_id: mod_id.clone(),
+        version,
+        reason: reason.filter(|r| !r.trim().is_empty()),
+        held_at: crate::response_cache::now_secs(),
+    };
+    update_holds(&mods_path, |holds| {
+        holds.insert(mod_id.to_lowercase(), held.clone());
+    })?;
+    Ok(held)
+}
+
+// Returns whether the mod was held
+#[command]
+pub async fn release_mod_hold(mods_path: String, mod_id: String) -> Result<bool, String> {
+    update_holds(&mods_path, |holds| {
+        holds.remove(&mod_id.to_lowercase()).is_some()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hold_blocks_other_versions() {
+        let hold = HeldMod {
+            mod_id: "examplemod".to_string(),
+            version: "1.2.0".to_string(),
+            reason: Some("breaks our world".to_string()),
+            held_at: 0,
+        };
+
+        assert!(check_against(None, "examplemod", None, false).is_ok());
+        assert!(check_against(Some(&hold), "examplemod", Some("v1.2"), false).is_ok());
+
+        let err = check_against(Some(&hold), "examplemod", Some("1.3.0"), false).unwrap_err();
+        assert!(err.contains("1.2.0") && err.contains("breaks our world"));
+        assert!(check_against(Some(&hold), "examplemod", None, false).is_err());
+
+        assert!(check_against(Some(&hold), "examplemod", Some("1.3.0"), true).is_ok());
+    }
+}
@@ -0,0 +1,359 @@
+// Known incompatibilities and issues between mods, kept as rules such as
+// "mod A 1.0.0-1.2.5 breaks with mod B 2.x". The rules live in a local file
+// that uses the same format as the files teams share, so a shared list can be
+// imported as is and the local one exported for others.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::command;
+
+// Newest version of the rules file format this build understands
+const RULES_FORMAT: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleSeverity {
+    Info,
+    #[default]
+    Warning,
+    Error,
+}
+
+// A mod, optionally limited to an inclusive version range
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModMatcher {
+    pub mod_id: String,
+    pub min_version: Option<String>,
+    pub max_version: Option<String>,
+}
+
+impl ModMatcher {
+    fn matches(&self, mod_id: &str, version: &str) -> bool {
+        use crate::version::compare;
+        use std::cmp::Ordering;
+
+        self.mod_id.trim().eq_ignore_ascii_case(mod_id)
+            && self
+                .min_version
+                .as_deref()
+                .is_none_or(|min| compare(version, min) != Ordering::Less)
+            && self
+                .max_version
+                .as_deref()
+                .is_none_or(|max| compare(version, max) != Ordering::Greater)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IncompatibilityRule {
+    pub id: String,
+    #[serde(rename = "mod")]
+    pub mod_a: ModMatcher,
+    // Without it the rule is a known issue of the first mod on its own
+    pub conflicts_with: Option<ModMatcher>,
+    #[serde(default)]
+    pub severity: RuleSeverity,
+    pub note: String,
+    pub link: Option<String>,
+    // Name of the shared file the rule was imported from; None for local rules
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+// The rules file, both the local one and the ones teams share
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesFile {
+    #[serde(default = "default_format")]
+    pub format: u32,
+    pub name: Option<String>,
+    #[serde(default)]
+    pub rules: Vec<IncompatibilityRule>,
+}
+
+fn default_format() -> u32 {
+    RULES_FORMAT
+}
+
+impl Default for RulesFile {
+    fn default() -> Self {
+        Self {
+            format: RULES_FORMAT,
+            name: None,
+            rules: Vec::new(),
+        }
+    }
+}
+
+// A rule that applies to the enabled mods
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleMatch {
+    pub rule: IncompatibilityRule,
+    pub mod_version: String,
+    pub conflicting_version: Option<String>,
+}
+
+// A rule as seen from one of the mods it names
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownIssue {
+    pub rule_id: String,
+    pub severity: RuleSeverity,
+    pub note: String,
+    pub link: Option<String>,
+    pub other_mod: Option<String>, // The mod it clashes with, if any
+}
+
+static RULES_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_rules_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_dir.join("incompatibility-rules.json"))
+}
+
+fn read_rules_file(path: &Path) -> Result<RulesFile, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read incompatibility rules: {}", e))?;
+    let file: RulesFile = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse incompatibility rules: {}", e))?;
+    if file.format > RULES_FORMAT {
+        return Err(format!(
+            "Incompatibility rules use format {}, but only up to {} is supported",
+            file.format, RULES_FORMAT
+        ));
+    }
+    Ok(file)
+}
+
+fn write_rules_file(path: &Path, file: &RulesFile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(file)
+        .map_err(|e| format!("Failed to serialize incompatibility rules: {}", e))?;
+    std::fs::write(path, content)
+        .map_err(|e| format!("Failed to write incompatibility rules: {}", e))
+}
+
+pub(crate) fn load_rules() -> Vec<IncompatibilityRule> {
+    let _guard = RULES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = match get_rules_path() {
+        Ok(path) if path.exists() => path,
+        _ => return Vec::new(),
+    };
+    read_rules_file(&path)
+        .map(|file| file.rules)
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            Vec::new()
+        })
+}
+
+// Change the local rules in one read/modify/write under the lock
+fn update_rules<T>(f: impl FnOnce(&mut Vec<IncompatibilityRule>) -> T) -> Result<T, String> {
+    let _guard = RULES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = get_rules_path()?;
+    let mut file = if path.exists() {
+        read_rules_file(&path)?
+    } else {
+        RulesFile::default()
+    };
+    let result = f(&mut file.rules);
+    write_rules_file(&path, &file)?;
+    Ok(result)
+}
+
+// Rules that apply to a set of enabled mods, given as (modid, version)
+pub(crate) fn matching_rules(
+    rules: &[IncompatibilityRule],
+    enabled: &[(String, String)],
+) -> Vec<RuleMatch> {
+    let find = |matcher: &ModMatcher| {
+        enabled
+            .iter()
+            .find(|(mod_id, version)| matcher.matches(mod_id, version))
+            .map(|(_, version)| version.clone())
+    };
+
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let mod_version = find(&rule.mod_a)?;
+            let conflicting_version = match &rule.conflicts_with {
+                Some(other) => Some(find(other)?),
+                None => None,
+            };
+            Some(RuleMatch {
+                rule: rule.clone(),
+                mod_version,
+                conflicting_version,
+            })
+        })
+        .collect()
+}
+
+// Matches keyed by the lowercase modid of every mod they name
+pub(crate) fn known_issues_by_mod(matches: &[RuleMatch]) -> HashMap<String, Vec<KnownIssue>> {
+    let mut issues: HashMap<String, Vec<KnownIssue>> = HashMap::new();
+    for RuleMatch { rule, .. } in matches {
+        let other = rule.conflicts_with.as_ref().map(|m| m.mod_id.trim());
+        let sides = [(rule.mod_a.mod_id.trim(), other)]
+            .into_iter()
+            .chain(other.map(|other| (other, Some(rule.mod_a.mod_id.trim()))));
+        for (mod_id, other_mod) in sides {
+            issues
+                .entry(mod_id.to_lowercase())
+                .or_default()
+                .push(KnownIssue {
+                    rule_id: rule.id.clone(),
+                    severity: rule.severity,
+                    note: rule.note.clone(),
+                    link: rule.link.clone(),
+                    other_mod: other_mod.map(|m| m.to_string()),
+                });
+        }
+    }
+    issues
+}
+
+// Rules matching the enabled mods of a scanned mods folder
+pub(crate) fn matching_enabled(mod_list: &[crate::mod_manager::Mod]) -> Vec<RuleMatch> {
+    let enabled: Vec<(String, String)> = mod_list
+        .iter()
+        .filter(|m| m.enabled)
+        .map(|m| {
+            let version = m
+                .info
+                .as_ref()
+                .map(|i| i.version.clone())
+                .unwrap_or_else(|| m.version.clone());
+            (m.id.clone(), version)
+        })
+        .collect();
+    matching_rules(&load_rules(), &enabled)
+}
+
+#[command]
+pub async fn get_incompatibility_rules() -> Result<Vec<IncompatibilityRule>, String> {
+    Ok(load_rules())
+}
+
+#[command]
+pub async fn save_incompatibility_rules(rules: Vec<IncompatibilityRule>) -> Result<(), String> {
+    update_rules(|existing| *existing = rules)
+}
+
+// Merge a shared rules file into the local rules. Rules with the same id are
+// replaced, so importing a newer copy of the same file updates it in place.
+// Returns how many rules were imported.
+#[command]
+pub async fn import_incompatibility_rules(file_path: String) -> Result<usize, String> {
+    let path = Path::new(&file_path);
+    let shared = read_rules_file(path)?;
+    let source = shared.name.clone().unwrap_or_else(|| {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.clone())
+    });
+
+    let imported = shared.rules.len();
+    update_rules(|rules| {
+        for mut rule in shared.rules {
+            rule.source.get_or_insert_with(|| source.clone());
+            match rules.iter_mut().find(|r| r.id == rule.id) {
+                Some(existing) => *existing = rule,
+                None => rules.push(rule),
+            }
+        }
+    })?;
+    Ok(imported)
+}
+
+#[command]
+pub async fn export_incompatibility_rules(
+    file_path: String,
+    name: Option<String>,
+) -> Result<(), String> {
+    let file = RulesFile {
+        format: RULES_FORMAT,
+        name,
+        rules: load_rules(),
+    };
+    write_rules_file(Path::new(&file_path), &file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_rules() {
+        let file: RulesFile = serde_json::from_str(
+            r#"{
+                "format": 1,
+                "name": "Team rules",
+                "rules": [
+                    {
+                        "id": "pans-carts",
+                        "mod": {"modId": "betterpans", "minVersion": "1.0.0", "maxVersion": "1.2.5"},
+                        "conflictsWith": {"modId": "carts"},
+                        "severity": "error",
+                        "note": "Carts lose their contents"
+                    },
+                    {
+                        "id": "lanterns-flicker",
+                        "mod": {"modId": "lanterns", "maxVersion": "2.0.0"},
+                        "note": "Flickers at night"
+                    },
+                    {
+                        "id": "pans-wagons",
+                        "mod": {"modId": "betterpans"},
+                        "conflictsWith": {"modId": "wagons"},
+                        "note": "Not installed"
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(file.rules[1].severity, RuleSeverity::Warning);
+
+        let enabled = |mods: &[(&str, &str)]| -> Vec<(String, String)> {
+            mods.iter()
+                .map(|(m, v)| (m.to_string(), v.to_string()))
+                .collect()
+        };
+
+        let matches = matching_rules(
+            &file.rules,
+            &enabled(&[
+                ("BetterPans", "1.2.0"),
+                ("carts", "3.1.0"),
+                ("lanterns", "2.1.0"),
+            ]),
+        );
+        let ids: Vec<&str> = matches.iter().map(|m| m.rule.id.as_str()).collect();
+        assert_eq!(ids, vec!["pans-carts"]);
+        assert_eq!(matches[0].conflicting_version.as_deref(), Some("3.1.0"));
+
+        let issues = known_issues_by_mod(&matches);
+        assert_eq!(issues["carts"][0].other_mod.as_deref(), Some("betterpans"));
+        assert_eq!(issues["betterpans"][0].other_mod.as_deref(), Some("carts"));
+
+        let matches = matching_rules(
+            &file.rules,
+            &enabled(&[
+                ("betterpans", "1.3.0"),
+                ("carts", "3.1.0"),
+                ("lanterns", "v1.9"),
+            ]),
+        );
+        let ids: Vec<&str> = matches.iter().map(|m| m.rule.id.as_str()).collect();
+        assert_eq!(ids, vec!["lanterns-flicker"]);
+    }
+}
@@ -2,19 +2,42 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod api_client;
+mod asset_conflicts;
 mod config;
+mod dependency_resolver;
+mod dependents;
+mod download_cache;
+mod download_queue;
 mod game_detector;
+mod holds;
+mod incompatibilities;
 mod mod_manager;
 mod mod_pack;
 mod mod_pack_manager;
+mod mod_repository;
+mod mod_side;
+mod mod_source;
 mod mod_status;
 mod mod_submission;
+mod patch_analyzer;
+mod release_host;
+mod request_policy;
+mod response_cache;
+mod status_cache;
+mod update_batch;
+mod version;
+mod watchlist;
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_shell::init())
+        .setup(|app| {
+            download_queue::resume_pending();
+            watchlist::start_background_check(app.handle().clone());
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             mod_manager::get_mod_list,
             mod_manager::enable_mods,
@@ -25,21 +48,60 @@ fn main() {
             mod_manager::read_config,
             mod_manager::write_config,
             mod_manager::reindex_mod,
+            asset_conflicts::analyze_asset_conflicts,
+            patch_analyzer::analyze_mod_patches,
             game_detector::detect_vintage_story_path,
             game_detector::get_vintage_story_path,
+            game_detector::detect_installed_game_version,
             config::get_settings,
             config::save_settings,
             api_client::get_mod_download_url,
             api_client::search_mods,
             api_client::download_mod,
+            api_client::get_mod_details,
+            download_cache::get_download_cache_info,
+            download_cache::prune_download_cache,
+            download_cache::seed_download_cache,
+            download_cache::install_cached_mod,
+            download_queue::get_download_queue,
+            download_queue::enqueue_downloads,
+            download_queue::wait_for_downloads,
+            download_queue::retry_download,
+            download_queue::clear_finished_downloads,
+            response_cache::get_response_cache_info,
+            response_cache::clear_response_cache,
             mod_pack::create_mod_pack,
             mod_pack::export_mod_pack,
             mod_pack::import_mod_pack,
             mod_submission::submit_mod_pack,
             mod_status::check_mod_status,
             mod_status::check_all_mods_status,
+            mod_status::get_cached_mod_statuses,
+            mod_status::get_update_digest,
             mod_status::install_dependencies,
+            dependency_resolver::plan_dependencies,
             mod_status::update_mod,
+            incompatibilities::get_incompatibility_rules,
+            incompatibilities::save_incompatibility_rules,
+            incompatibilities::import_incompatibility_rules,
+            incompatibilities::export_incompatibility_rules,
+            holds::get_mod_holds,
+            holds::hold_mod,
+            holds::release_mod_hold,
+            update_batch::update_all,
+            update_batch::get_update_batches,
+            update_batch::rollback_update,
+            mod_source::get_mod_sources,
+            mod_source::set_mod_source,
+            mod_repository::generate_repository_index,
+            mod_repository::search_repository,
+            watchlist::get_watchlist,
+            watchlist::add_to_watchlist,
+            watchlist::remove_from_watchlist,
+            watchlist::check_watchlist_now,
+            mod_side::filter_mods_by_side,
+            mod_side::validate_pack_side,
+            mod_side::client_mods_for_server,
             mod_pack_manager::list_mod_packs,
             mod_pack_manager::enable_mod_pack,
             mod_pack_manager::disable_mod_pack,
@@ -1,3 +1,4 @@
+use crate::dependents::{plan_removal, DependentsAction, RemovalReport};
 use dirs;
 use hex;
 use serde::{Deserialize, Serialize};
@@ -6,6 +7,8 @@ use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tauri::command;
 use zip::ZipArchive;
 
@@ -18,7 +21,11 @@ pub struct ModInfo {
     pub authors: Vec<String>,
     pub website: Option<String>,
     pub side: Option<String>,
+    // modinfo.json usually spells these requiredOnClient and requiredOnServer,
+    // and the game reads its keys in any case
+    #[serde(alias = "requiredOnClient", alias = "RequiredOnClient")]
     pub requiredonclient: Option<bool>,
+    #[serde(alias = "requiredOnServer", alias = "RequiredOnServer")]
     pub requiredonserver: Option<bool>,
     pub dependencies: Option<serde_json::Value>,
 }
@@ -37,8 +44,14 @@ pub struct Mod {
     pub status: Option<crate::mod_status::ModStatus>,
 }
 
+// Bumped when index entries gain fields read from modinfo.json; older
+// entries are dropped on load so their mods are indexed again
+const MOD_INDEX_FORMAT: u32 = 1; // 1: requiredonclient and requiredonserver
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModIndexEntry {
+    #[serde(default)]
+    pub format: u32,
     pub hash: String,
     pub modid: String,
     pub name: String,
@@ -47,6 +60,8 @@ pub struct ModIndexEntry {
     pub authors: Vec<String>,
     pub website: Option<String>,
     pub side: Option<String>,
+    pub requiredonclient: Option<bool>,
+    pub requiredonserver: Option<bool>,
     pub download_url: Option<String>,
     pub thumbnail_url: Option<String>,
     pub category: Option<String>,
@@ -57,6 +72,23 @@ pub struct ModIndexEntry {
     pub dependencies: Option<serde_json::Value>,
 }
 
+impl ModIndexEntry {
+    fn mod_info(&self) -> ModInfo {
+        ModInfo {
+            modid: self.modid.clone(),
+            name: self.name.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+            authors: self.authors.clone(),
+            website: self.website.clone(),
+            side: self.side.clone(),
+            requiredonclient: self.requiredonclient,
+            requiredonserver: self.requiredonserver,
+            dependencies: self.dependencies.clone(),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ModIndex {
     mods: HashMap<String, ModIndexEntry>, // hash -> entry
@@ -214,18 +246,7 @@ pub async fn get_mod_list(
                 .to_string();
             let enabled = !file_name.ends_with(".disabled");
 
-            let modinfo = ModInfo {
-                modid: entry.modid.clone(),
-                name: entry.name.clone(),
-                version: entry.version.clone(),
-                description: entry.description.clone(),
-                authors: entry.authors.clone(),
-                website: entry.website.clone(),
-                side: entry.side.clone(),
-                requiredonclient: None,
-                requiredonserver: None,
-                dependencies: entry.dependencies.clone(),
-            };
+            let modinfo = entry.mod_info();
 
             eprintln!("Found zip mod: {} (enabled: {})", entry.modid, enabled);
 
@@ -300,18 +321,7 @@ pub async fn get_mod_list(
                         };
 
                         if let Some(entry) = index.mods.get(&hash) {
-                            let modinfo = ModInfo {
-                                modid: entry.modid.clone(),
-                                name: entry.name.clone(),
-                                version: entry.version.clone(),
-                                description: entry.description.clone(),
-                                authors: entry.authors.clone(),
-                                website: entry.website.clone(),
-                                side: entry.side.clone(),
-                                requiredonclient: None,
-                                requiredonserver: None,
-                                dependencies: entry.dependencies.clone(),
-                            };
+                            let modinfo = entry.mod_info();
 
                             eprintln!("Found disabled zip mod: {}", entry.modid);
 
@@ -334,18 +344,7 @@ pub async fn get_mod_list(
                                         let entry_clone = new_entry.clone();
                                         index.mods.insert(hash_clone, entry_clone.clone());
 
-                                        let modinfo = ModInfo {
-                                            modid: new_entry.modid.clone(),
-                                            name: new_entry.name.clone(),
-                                            version: new_entry.version.clone(),
-                                            description: new_entry.description.clone(),
-                                            authors: new_entry.authors.clone(),
-                                            website: new_entry.website.clone(),
-                                            side: new_entry.side.clone(),
-                                            requiredonclient: None,
-                                            requiredonserver: None,
-                                            dependencies: new_entry.dependencies.clone(),
-                                        };
+                                        let modinfo = new_entry.mod_info();
 
                                         mods.push(Mod {
                                             id: new_entry.modid.clone(),
@@ -383,6 +382,75 @@ pub async fn get_mod_list(
     Ok(mods)
 }
 
+// The last folder scan, reused by status checks until the folder changes
+struct ScanSnapshot {
+    mods_path: String,
+    fingerprint: Vec<ScanEntry>,
+    mods: Arc<Vec<Mod>>,
+}
+
+// Path, modification time and size of something a scan reads
+type ScanEntry = (PathBuf, Option<SystemTime>, u64);
+
+static LAST_SCAN: Mutex<Option<ScanSnapshot>> = Mutex::new(None);
+
+// Every entry of the mods folder and of disabled/, plus the modinfo.json of
+// unpacked mods. Replacing a zip in place keeps the folder time on some
+// filesystems, but not the zip's own time and size.
+fn scan_fingerprint(mods_dir: &Path) -> Vec<ScanEntry> {
+    let entry = |path: PathBuf| -> ScanEntry {
+        let metadata = std::fs::metadata(&path).ok();
+        let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+        let size = metadata.map(|m| m.len()).unwrap_or(0);
+        (path, modified, size)
+    };
+
+    let mut fingerprint = Vec::new();
+    for dir in [mods_dir.to_path_buf(), mods_dir.join("disabled")] {
+        let Ok(read_dir) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in read_dir.filter_map(|e| e.ok()).map(|e| e.path()) {
+            if path.is_dir() {
+                fingerprint.push(entry(path.join("modinfo.json")));
+            }
+            fingerprint.push(entry(path));
+        }
+    }
+    fingerprint.sort_by(|a, b| a.0.cmp(&b.0));
+    fingerprint
+}
+
+// The mod list from the last scan of `mods_path` if the folder has not changed
+// since, otherwise a fresh scan
+pub(crate) async fn scanned_mod_list(mods_path: &str) -> Result<Arc<Vec<Mod>>, String> {
+    let fingerprint = scan_fingerprint(Path::new(mods_path));
+    {
+        let last = LAST_SCAN.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(snapshot) = last
+            .as_ref()
+            .filter(|s| s.mods_path == mods_path && s.fingerprint == fingerprint)
+        {
+            return Ok(Arc::clone(&snapshot.mods));
+        }
+    }
+
+    let mods = Arc::new(get_mod_list(mods_path.to_string(), Some(false)).await?);
+    *LAST_SCAN.lock().unwrap_or_else(|e| e.into_inner()) = Some(ScanSnapshot {
+        mods_path: mods_path.to_string(),
+        fingerprint,
+        mods: Arc::clone(&mods),
+    });
+    Ok(mods)
+}
+
+// Forget the last scan; called after anything installs, enables, disables or
+// deletes mods, so the next status check sees the change even within the
+// resolution of file times
+pub(crate) fn invalidate_scan() {
+    *LAST_SCAN.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
 #[command]
 pub async fn enable_mods(mods_path: String, mod_ids: Vec<String>) -> Result<(), String> {
     let mods_dir = Path::new(&mods_path);
@@ -490,11 +558,46 @@ pub async fn enable_mods(mods_path: String, mod_ids: Vec<String>) -> Result<(),
     }
 
     save_mod_index(&index).map_err(|e| format!("Failed to save mod index: {}", e))?;
+    invalidate_scan();
     Ok(())
 }
 
+async fn check_dependents(
+    mods_path: &str,
+    mod_ids: &[String],
+    on_dependents: Option<DependentsAction>,
+) -> Result<RemovalReport, String> {
+    let mod_list = get_mod_list(mods_path.to_string(), Some(false))
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let report = plan_removal(&mod_list, mod_ids, on_dependents.unwrap_or_default());
+    if !report.would_break.is_empty() {
+        eprintln!(
+            "Removing {:?} affects {} dependent mod(s): {:?}",
+            mod_ids,
+            report.would_break.len(),
+            on_dependents.unwrap_or_default()
+        );
+    }
+    Ok(report)
+}
+
+// Enabled mods that depend on the targets are reported and left alone until
+// the caller says what should happen to them
 #[command]
-pub async fn disable_mods(mods_path: String, mod_ids: Vec<String>) -> Result<(), String> {
+pub async fn disable_mods(
+    mods_path: String,
+    mod_ids: Vec<String>,
+    on_dependents: Option<DependentsAction>,
+) -> Result<RemovalReport, String> {
+    let report = check_dependents(&mods_path, &mod_ids, on_dependents).await?;
+    if report.applied {
+        disable_mod_files(&mods_path, report.affected.clone())?;
+    }
+    Ok(report)
+}
+
+fn disable_mod_files(mods_path: &str, mod_ids: Vec<String>) -> Result<(), String> {
     let mods_dir = Path::new(&mods_path);
     let disabled_dir = mods_dir.join("disabled");
     let mut index = load_mod_index();
@@ -583,6 +686,7 @@ pub async fn disable_mods(mods_path: String, mod_ids: Vec<String>) -> Result<(),
     }
 
     save_mod_index(&index).map_err(|e| format!("Failed to save mod index: {}", e))?;
+    invalidate_scan();
     Ok(())
 }
 
@@ -606,6 +710,14 @@ fn get_index_path() -> Result<PathBuf, String> {
     Ok(config_dir.join("mod-index.json"))
 }
 
+fn parse_mod_index(content: &str) -> Result<ModIndex, serde_json::Error> {
+    let mut index: ModIndex = serde_json::from_str(content)?;
+    index
+        .mods
+        .retain(|_, entry| entry.format >= MOD_INDEX_FORMAT);
+    Ok(index)
+}
+
 fn load_mod_index() -> ModIndex {
     let index_path = match get_index_path() {
         Ok(p) => p,
@@ -623,7 +735,7 @@ fn load_mod_index() -> ModIndex {
     }
 
     match std::fs::read_to_string(&index_path) {
-        Ok(content) => match serde_json::from_str::<ModIndex>(&content) {
+        Ok(content) => match parse_mod_index(&content) {
             Ok(index) => index,
             Err(e) => {
                 eprintln!("Failed to parse mod index: {}", e);
@@ -650,7 +762,7 @@ fn save_mod_index(index: &ModIndex) -> Result<(), String> {
     Ok(())
 }
 
-fn hash_file(path: &Path) -> Result<String, String> {
+pub(crate) fn hash_file(path: &Path) -> Result<String, String> {
     let mut file =
         File::open(path).map_err(|e| format!("Failed to open file for hashing: {}", e))?;
     let mut hasher = Sha256::new();
@@ -669,7 +781,7 @@ fn hash_file(path: &Path) -> Result<String, String> {
     Ok(hex::encode(hasher.finalize()))
 }
 
-fn extract_modinfo_from_zip(zip_path: &Path) -> Result<ModInfo, String> {
+pub(crate) fn extract_modinfo_from_zip(zip_path: &Path) -> Result<ModInfo, String> {
     let file = File::open(zip_path).map_err(|e| format!("Failed to open zip file: {}", e))?;
     let mut archive =
         ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
@@ -703,6 +815,7 @@ fn index_zip_mod(zip_path: &Path) -> Result<ModIndexEntry, String> {
         .to_string();
 
     Ok(ModIndexEntry {
+        format: MOD_INDEX_FORMAT,
         hash: hash.clone(),
         modid: modinfo.modid.clone(),
         name: modinfo.name.clone(),
@@ -711,6 +824,8 @@ fn index_zip_mod(zip_path: &Path) -> Result<ModIndexEntry, String> {
         authors: modinfo.authors.clone(),
         website: modinfo.website.clone(),
         side: modinfo.side.clone(),
+        requiredonclient: modinfo.requiredonclient,
+        requiredonserver: modinfo.requiredonserver,
         download_url: None,
         thumbnail_url: None,
         category: None,
@@ -782,14 +897,28 @@ pub async fn reindex_mod(mods_path: String, mod_id: String) -> Result<(), String
                 hash_file(&mod_path).map_err(|e| format!("Failed to hash mod file: {}", e))?;
             index.mods.insert(hash, new_entry);
             save_mod_index(&index).map_err(|e| format!("Failed to save mod index: {}", e))?;
+            invalidate_scan();
             Ok(())
         }
         Err(e) => Err(format!("Failed to index mod: {}", e)),
     }
 }
 
+// Same protection as disable_mods; a cascade deletes the dependents too
 #[command]
-pub async fn delete_mods(mods_path: String, mod_ids: Vec<String>) -> Result<(), String> {
+pub async fn delete_mods(
+    mods_path: String,
+    mod_ids: Vec<String>,
+    on_dependents: Option<DependentsAction>,
+) -> Result<RemovalReport, String> {
+    let report = check_dependents(&mods_path, &mod_ids, on_dependents).await?;
+    if report.applied {
+        delete_mod_files(&mods_path, report.affected.clone())?;
+    }
+    Ok(report)
+}
+
+fn delete_mod_files(mods_path: &str, mod_ids: Vec<String>) -> Result<(), String> {
     let mods_dir = Path::new(&mods_path);
     let disabled_dir = mods_dir.join("disabled");
     let mut index = load_mod_index();
@@ -868,9 +997,36 @@ pub async fn delete_mods(mods_path: String, mod_ids: Vec<String>) -> Result<(),
     }
 
     save_mod_index(&index).map_err(|e| format!("Failed to save mod index: {}", e))?;
+    invalidate_scan();
     Ok(())
 }
 
+// A scanned, enabled or disabled zip mod for tests. `modinfo` holds any
+// modinfo.json fields besides the modid, name and version, e.g. dependencies.
+#[cfg(test)]
+pub(crate) fn test_mod(
+    modid: &str,
+    version: &str,
+    enabled: bool,
+    modinfo: serde_json::Value,
+) -> Mod {
+    let mut modinfo = modinfo;
+    modinfo["modid"] = modid.into();
+    modinfo["name"] = modid.into();
+    modinfo["version"] = version.into();
+    modinfo["authors"] = serde_json::json!([]);
+    Mod {
+        id: modid.to_string(),
+        name: modid.to_string(),
+        version: version.to_string(),
+        path: String::new(),
+        enabled,
+        info: Some(serde_json::from_value(modinfo).expect("valid test modinfo")),
+        is_zip: true,
+        status: None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -931,4 +1087,59 @@ mod tests {
         assert!(json_files.contains(&"config.json".to_string()));
         assert!(json_files.contains(&"another.json".to_string()));
     }
+
+    #[test]
+    fn test_side_fields_of_modinfo_and_index() {
+        let info: ModInfo = serde_json::from_str(
+            r#"{"modid": "a", "name": "A", "version": "1.0.0", "authors": [],
+                "RequiredOnClient": false, "requiredOnServer": false}"#,
+        )
+        .unwrap();
+        assert_eq!(info.requiredonclient, Some(false));
+        assert_eq!(info.requiredonserver, Some(false));
+
+        // Entries from before the side fields were indexed are indexed again
+        let entry = |format: &str| {
+            format!(
+                r#"{{{}"hash": "h", "modid": "a", "name": "A", "version": "1.0.0", "description": null,
+                    "authors": [], "website": null, "side": null, "download_url": null,
+                    "thumbnail_url": null, "category": null, "tags": [], "file_name": "a.zip",
+                    "file_path": "/mods/a.zip"}}"#,
+                format
+            )
+        };
+        let old = parse_mod_index(&format!(r#"{{"mods": {{"h": {}}}}}"#, entry(""))).unwrap();
+        assert!(old.mods.is_empty());
+        let current = parse_mod_index(&format!(
+            r#"{{"mods": {{"h": {}}}}}"#,
+            entry(r#""format": 1, "#)
+        ))
+        .unwrap();
+        assert_eq!(current.mods["h"].requiredonclient, None);
+    }
+
+    #[test]
+    fn test_scan_fingerprint_sees_entry_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mods_dir = temp_dir.path();
+        fs::create_dir(mods_dir.join("disabled")).unwrap();
+        fs::write(mods_dir.join("a.zip"), "one").unwrap();
+        fs::create_dir(mods_dir.join("unpacked")).unwrap();
+        fs::write(mods_dir.join("unpacked/modinfo.json"), "{}").unwrap();
+        let before = scan_fingerprint(mods_dir);
+
+        // Same name, different contents
+        fs::write(mods_dir.join("a.zip"), "three").unwrap();
+        let replaced = scan_fingerprint(mods_dir);
+        assert_ne!(replaced, before);
+
+        // Disabling moves the zip without touching it
+        fs::rename(mods_dir.join("a.zip"), mods_dir.join("disabled/a.zip")).unwrap();
+        let disabled = scan_fingerprint(mods_dir);
+        assert_ne!(disabled, replaced);
+
+        // An unpacked mod's modinfo is edited in place
+        fs::write(mods_dir.join("unpacked/modinfo.json"), r#"{"modid": "x"}"#).unwrap();
+        assert_ne!(scan_fingerprint(mods_dir), disabled);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::mod_source::ModSourceSpec;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
@@ -18,6 +19,8 @@ pub struct ModPackMod {
     pub url: Option<String>, // Optional URL to download the mod directly
     #[serde(skip_serializing_if = "Option::is_none")]
     pub hash: Option<String>, // SHA256 hash of the mod file for verification
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<ModSourceSpec>, // Where to get the mod; inferred from `url` when absent
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
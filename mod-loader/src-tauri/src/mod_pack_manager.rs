@@ -4,6 +4,8 @@ use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use tauri::command;
 
+use crate::dependents::{DependentsAction, RemovalReport};
+use crate::incompatibilities::RuleMatch;
 use crate::mod_pack::ModPack;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -17,6 +19,15 @@ pub struct ModPackInfo {
     pub mod_ids: Vec<String>,
 }
 
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackEnableReport {
+    // Whether the pack was enabled; false when incompatibilities need confirming
+    pub applied: bool,
+    // Incompatibility rules matching the mods that would be enabled
+    pub incompatibilities: Vec<RuleMatch>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct ModPackState {
     enabled_packs: HashSet<String>, // Set of mod pack file paths
@@ -127,7 +138,11 @@ pub async fn list_mod_packs() -> Result<Vec<ModPackInfo>, String> {
 }
 
 #[command]
-pub async fn enable_mod_pack(pack_path: String, mods_path: String) -> Result<(), String> {
+pub async fn enable_mod_pack(
+    pack_path: String,
+    mods_path: String,
+    ignore_incompatibilities: Option<bool>,
+) -> Result<PackEnableReport, String> {
     use crate::mod_manager;
 
     // Load the mod pack
@@ -152,6 +167,39 @@ pub async fn enable_mod_pack(pack_path: String, mods_path: String) -> Result<(),
         all_enabled_mod_ids.insert(mod_item.id.clone());
     }
 
+    // Check the mods that would be enabled afterwards against the known
+    // incompatibilities before changing anything
+    let mod_list = mod_manager::scanned_mod_list(&mods_path).await?;
+    let mut would_be_enabled: Vec<(String, String)> = mod_list
+        .iter()
+        .filter(|m| {
+            m.enabled
+                || all_enabled_mod_ids
+                    .iter()
+                    .any(|id| id.eq_ignore_ascii_case(&m.id))
+        })
+        .map(|m| {
+            let version = m.info.as_ref().map(|i| &i.version).unwrap_or(&m.version);
+            (m.id.clone(), version.clone())
+        })
+        .collect();
+    // Pack mods that are not installed yet are checked at the pack's version
+    for mod_item in &pack.mods {
+        if !mod_list.iter().any(|m| m.id.eq_ignore_ascii_case(&mod_item.id)) {
+            would_be_enabled.push((mod_item.id.clone(), mod_item.version.clone()));
+        }
+    }
+    let incompatibilities = crate::incompatibilities::matching_rules(
+        &crate::incompatibilities::load_rules(),
+        &would_be_enabled,
+    );
+    if !incompatibilities.is_empty() && !ignore_incompatibilities.unwrap_or(false) {
+        return Ok(PackEnableReport {
+            applied: false,
+            incompatibilities,
+        });
+    }
+
     // Enable all mods in the union
     let mod_ids_to_enable: Vec<String> = all_enabled_mod_ids.into_iter().collect();
     mod_manager::enable_mods(mods_path, mod_ids_to_enable)
@@ -164,11 +212,18 @@ pub async fn enable_mod_pack(pack_path: String, mods_path: String) -> Result<(),
     save_mod_pack_state(&new_state)
         .map_err(|e| format!("Failed to save mod pack state: {}", e))?;
 
-    Ok(())
+    Ok(PackEnableReport {
+        applied: true,
+        incompatibilities,
+    })
 }
 
 #[command]
-pub async fn disable_mod_pack(pack_path: String, mods_path: String) -> Result<(), String> {
+pub async fn disable_mod_pack(
+    pack_path: String,
+    mods_path: String,
+    on_dependents: Option<DependentsAction>,
+) -> Result<RemovalReport, String> {
     use crate::mod_manager;
 
     // Load the mod pack we're disabling
@@ -197,18 +252,20 @@ pub async fn disable_mod_pack(pack_path: String, mods_path: String) -> Result<()
         }
     }
 
-    // Disable mods that are no longer needed
-    if !mod_ids_to_disable.is_empty() {
-        mod_manager::disable_mods(mods_path, mod_ids_to_disable)
-            .await
-            .map_err(|e| format!("Failed to disable mods: {}", e))?;
+    // Disable mods that are no longer needed; the pack stays enabled while
+    // the caller decides about mods outside it that depend on them
+    let report = mod_manager::disable_mods(mods_path, mod_ids_to_disable, on_dependents)
+        .await
+        .map_err(|e| format!("Failed to disable mods: {}", e))?;
+    if !report.applied {
+        return Ok(report);
     }
 
     // Update state
     save_mod_pack_state(&state)
         .map_err(|e| format!("Failed to save mod pack state: {}", e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 #[command]
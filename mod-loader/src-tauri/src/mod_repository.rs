@@ -0,0 +1,553 @@
+// Static mod repositories: a folder of mod zips with an index.json next to
+// them, served from a file share or any plain HTTP server. The index lists
+// every mod with its releases, each with the zip's path relative to the
+// index, its SHA-256 hash and the dependencies from its modinfo.
+
+use crate::api_client::{self, ModRelease};
+use crate::mod_source::{ModSource, ResolvedMod};
+use crate::request_policy;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tauri::command;
+
+pub const INDEX_FILE_NAME: &str = "index.json";
+const INDEX_FORMAT: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepositoryIndex {
+    pub format: u32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub generated_at: Option<u64>, // Unix seconds
+    pub mods: Vec<RepositoryMod>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepositoryMod {
+    pub modid: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    #[serde(default)]
+    pub side: Option<String>,
+    pub releases: Vec<RepositoryRelease>, // Newest first
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RepositoryRelease {
+    pub version: String,
+    pub file: String,   // Relative to the index, always with '/' separators
+    pub sha256: String, // Lowercase hex
+    pub size: u64,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, String>, // modid -> minimum version ("" for any), as in modinfo
+}
+
+// Where a repository lives: the index and the base its file paths are relative to
+#[derive(Debug, Clone, PartialEq)]
+enum RepositoryLocation {
+    Http { index_url: String },
+    File { index_path: PathBuf },
+}
+
+impl RepositoryLocation {
+    fn parse(url: &str) -> Self {
+        let url = url.trim();
+        let is_index = url.ends_with(".json");
+
+        if url.starts_with("http://") || url.starts_with("https://") {
+            let index_url = if is_index {
+                url.to_string()
+            } else {
+                format!("{}/{}", url.trim_end_matches('/'), INDEX_FILE_NAME)
+            };
+            return RepositoryLocation::Http { index_url };
+        }
+
+        let path = PathBuf::from(url.strip_prefix("file://").unwrap_or(url));
+        let index_path = if is_index {
+            path
+        } else {
+            path.join(INDEX_FILE_NAME)
+        };
+        RepositoryLocation::File { index_path }
+    }
+
+    fn file_location(&self, file: &str) -> String {
+        match self {
+            RepositoryLocation::Http { index_url } => {
+                let base = index_url
+                    .rsplit_once('/')
+                    .map(|(base, _)| base)
+                    .unwrap_or(index_url);
+                let encoded: Vec<String> = file
+                    .split('/')
+                    .map(|segment| urlencoding::encode(segment).into_owned())
+                    .collect();
+                format!("{}/{}", base, encoded.join("/"))
+            }
+            RepositoryLocation::File { index_path } => index_path
+                .parent()
+                .unwrap_or(Path::new(""))
+                .join(file)
+                .to_string_lossy()
+                .to_string(),
+        }
+    }
+}
+
+pub(crate) async fn load_index(url: &str) -> Result<RepositoryIndex, String> {
+    let content = match RepositoryLocation::parse(url) {
+        RepositoryLocation::Http { index_url } => {
+            let client = reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(15))
+                .build()
+                .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+            request_policy::get_text(&client, &index_url).await?.0
+        }
+        RepositoryLocation::File { index_path } => std::fs::read_to_string(&index_path)
+            .map_err(|e| format!("Failed to read {}: {}", index_path.display(), e))?,
+    };
+
+    let index: RepositoryIndex = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse repository index at {}: {}", url, e))?;
+    if index.format > INDEX_FORMAT {
+        return Err(format!(
+            "Repository index at {} uses format {}, this version understands up to {}",
+            url, index.format, INDEX_FORMAT
+        ));
+    }
+    for repo_mod in &index.mods {
+        for release in &repo_mod.releases {
+            check_release_file(&release.file)
+                .map_err(|e| format!("Repository index at {} is not valid: {}", url, e))?;
+        }
+    }
+    Ok(index)
+}
+
+// Release files must stay inside the repository: relative, '/'-separated and
+// without `..`, so an index cannot point the File location at any path on disk
+fn check_release_file(file: &str) -> Result<(), String> {
+    let escapes = file.is_empty()
+        || file.starts_with('/')
+        || file.contains('\\')
+        || file.contains(':')
+        || file
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..");
+    if escapes {
+        return Err(format!(
+            "release file {:?} is not a path inside the repository",
+            file
+        ));
+    }
+    Ok(())
+}
+
+fn dependencies_from_modinfo(deps: Option<&serde_json::Value>) -> BTreeMap<String, String> {
+    let mut result = BTreeMap::new();
+    match deps {
+        Some(serde_json::Value::Object(obj)) => {
+            for (modid, version) in obj {
+                result.insert(
+                    modid.clone(),
+                    version.as_str().unwrap_or_default().to_string(),
+                );
+            }
+        }
+        Some(serde_json::Value::Array(arr)) => {
+            for item in arr {
+                let (modid, version) = match item {
+                    serde_json::Value::String(modid) => (Some(modid.as_str()), None),
+                    serde_json::Value::Object(obj) => (
+                        obj.get("modid").and_then(|v| v.as_str()),
+                        obj.get("version").and_then(|v| v.as_str()),
+                    ),
+                    _ => (None, None),
+                };
+                if let Some(modid) = modid {
+                    result.insert(modid.to_string(), version.unwrap_or_default().to_string());
+                }
+            }
+        }
+        _ => {}
+    }
+    result
+}
+
+fn collect_zips(dir: &Path, zips: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries =
+        std::fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_zips(&path, zips)?;
+        } else if path.extension().and_then(|s| s.to_str()) == Some("zip") {
+            zips.push(path);
+        }
+    }
+    Ok(())
+}
+
+// Build the index for every zip under `folder`. Zips without a readable
+// modinfo are skipped with a log line, as the mod list does.
+pub(crate) fn build_index(folder: &Path, name: Option<String>) -> Result<RepositoryIndex, String> {
+    let mut zips = Vec::new();
+    collect_zips(folder, &mut zips)?;
+    zips.sort();
+
+    let mut mods: BTreeMap<String, RepositoryMod> = BTreeMap::new();
+    for zip_path in zips {
+        let info = match crate::mod_manager::extract_modinfo_from_zip(&zip_path) {
+            Ok(info) => info,
+            Err(e) => {
+                eprintln!("[mod_repository] Skipping {}: {}", zip_path.display(), e);
+                continue;
+            }
+        };
+
+        let relative = zip_path
+            .strip_prefix(folder)
+            .map_err(|e| format!("Failed to make {} relative: {}", zip_path.display(), e))?;
+        let file = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let size = std::fs::metadata(&zip_path)
+            .map_err(|e| format!("Failed to read {}: {}", zip_path.display(), e))?
+            .len();
+
+        let release = RepositoryRelease {
+            version: info.version.clone(),
+            file,
+            sha256: crate::mod_manager::hash_file(&zip_path)?,
+            size,
+            dependencies: dependencies_from_modinfo(info.dependencies.as_ref()),
+        };
+
+        let entry = mods
+            .entry(info.modid.to_lowercase())
+            .or_insert_with(|| RepositoryMod {
+                modid: info.modid.clone(),
+                name: info.name.clone(),
+                description: info.description.clone(),
+                authors: info.authors.clone(),
+                side: info.side.clone(),
+                releases: Vec::new(),
+            });
+        if entry
+            .releases
+            .iter()
+            .any(|r| crate::version::same_version(&r.version, &release.version))
+        {
+            eprintln!(
+                "[mod_repository] Skipping {}: version {} of {} is already indexed",
+                zip_path.display(),
+                release.version,
+                info.modid
+            );
+            continue;
+        }
+        entry.releases.push(release);
+    }
+
+    let mut mods: Vec<RepositoryMod> = mods.into_values().collect();
+    for repo_mod in &mut mods {
+        repo_mod
+            .releases
+            .sort_by(|a, b| crate::version::compare(&b.version, &a.version));
+        // Describe the mod by its newest release
+        if let Some(newest) = repo_mod.releases.first() {
+            if let Ok(info) =
+                crate::mod_manager::extract_modinfo_from_zip(&folder.join(&newest.file))
+            {
+                repo_mod.name = info.name;
+                repo_mod.description = info.description;
+                repo_mod.authors = info.authors;
+                repo_mod.side = info.side;
+            }
+        }
+    }
+
+    Ok(RepositoryIndex {
+        format: INDEX_FORMAT,
+        name,
+        generated_at: Some(crate::response_cache::now_secs()),
+        mods,
+    })
+}
+
+// Write index.json into `folder`, describing every mod zip below it
+#[command]
+pub async fn generate_repository_index(
+    folder: String,
+    name: Option<String>,
+) -> Result<RepositoryIndex, String> {
+    let folder = PathBuf::from(folder);
+    let index = build_index(&folder, name)?;
+
+    let content = serde_json::to_string_pretty(&index)
+        .map_err(|e| format!("Failed to serialize repository index: {}", e))?;
+    std::fs::write(folder.join(INDEX_FILE_NAME), content)
+        .map_err(|e| format!("Failed to write repository index: {}", e))?;
+
+    eprintln!(
+        "[mod_repository] Indexed {} mods in {}",
+        index.mods.len(),
+        folder.display()
+    );
+    Ok(index)
+}
+
+// Mods in a repository whose modid, name, description or authors contain the query
+#[command]
+pub async fn search_repository(
+    repository_url: String,
+    query: Option<String>,
+) -> Result<Vec<RepositoryMod>, String> {
+    let index = load_index(&repository_url).await?;
+    let query = query.unwrap_or_default().trim().to_lowercase();
+
+    Ok(index
+        .mods
+        .into_iter()
+        .filter(|m| {
+            query.is_empty()
+                || m.modid.to_lowercase().contains(&query)
+                || m.name.to_lowercase().contains(&query)
+                || m.description
+                    .as_deref()
+                    .is_some_and(|d| d.to_lowercase().contains(&query))
+                || m.authors.iter().any(|a| a.to_lowercase().contains(&query))
+        })
+        .collect())
+}
+
+pub(crate) struct RepositorySource {
+    pub url: String, // The repository folder or its index.json, as a path, file:// or http(s):// URL
+}
+
+impl RepositorySource {
+    async fn find_mod(&self, mod_id: &str) -> Result<RepositoryMod, String> {
+        load_index(&self.url)
+            .await?
+            .mods
+            .into_iter()
+            .find(|m| m.modid.eq_ignore_ascii_case(mod_id))
+            .ok_or_else(|| format!("{} is not in the repository at {}", mod_id, self.url))
+    }
+}
+
+impl ModSource for RepositorySource {
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        let releases = self.list_releases(mod_id).await?;
+        let release = match version {
+            Some(version) => api_client::find_release_by_version(mod_id, &releases, version)
+                .map_err(|_| {
+                    format!(
+                        "Version {} of {} not found in the repository at {}",
+                        version, mod_id, self.url
+                    )
+                })?,
+            None => api_client::select_release(mod_id, &releases, game_version)?,
+        };
+
+        Ok(ResolvedMod {
+            version: release.modversion.clone(),
+            location: release.mainfile.clone(),
+        })
+    }
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        let location = RepositoryLocation::parse(&self.url);
+        let repo_mod = self.find_mod(mod_id).await?;
+
+        Ok(repo_mod
+            .releases
+            .into_iter()
+            .map(|release| ModRelease {
+                mainfile: location.file_location(&release.file),
+                modversion: Some(release.version),
+                tags: Vec::new(),
+                fileid: None,
+                created: None, // The index is ordered newest first instead
+                releaseid: None,
+                filename: release.file.rsplit('/').next().map(|s| s.to_string()),
+                modidstr: Some(repo_mod.modid.clone()),
+                downloads: None,
+                changelog: None,
+                dependencies: Some(release.dependencies),
+            })
+            .collect())
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        // The index knows the hash of every file, so use it unless one was given
+        let location = RepositoryLocation::parse(&self.url);
+        let index_hash = self.find_mod(mod_id).await.ok().and_then(|repo_mod| {
+            repo_mod
+                .releases
+                .into_iter()
+                .find(|r| location.file_location(&r.file) == resolved.location)
+                .map(|r| r.sha256)
+        });
+        let expected_hash = expected_hash.map(|h| h.to_string()).or(index_hash);
+
+        match location {
+            RepositoryLocation::Http { .. } => {
+                api_client::fetch_and_install(
+                    mod_id,
+                    &resolved.location,
+                    mods_dir,
+                    expected_hash.as_deref(),
+                    resolved.version.as_deref(),
+                    on_verifying,
+                )
+                .await
+            }
+            RepositoryLocation::File { .. } => {
+                let part_path =
+                    api_client::partial_download_path(mods_dir, mod_id, &resolved.location);
+                std::fs::copy(&resolved.location, &part_path)
+                    .map_err(|e| format!("Failed to copy {}: {}", resolved.location, e))?;
+                on_verifying();
+                api_client::install_verified_zip(
+                    mod_id,
+                    &part_path,
+                    mods_dir,
+                    None,
+                    expected_hash.as_deref(),
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_mod_zip(path: &Path, modid: &str, version: &str) {
+        let file = std::fs::File::create(path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        zip.start_file("modinfo.json", zip::write::FileOptions::default())
+            .unwrap();
+        write!(
+            zip,
+            r#"{{"modid": "{}", "name": "Example", "version": "{}", "authors": ["Team"], "dependencies": {{"game": "1.19.0", "othermod": ""}}}}"#,
+            modid, version
+        )
+        .unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_build_index_from_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("old")).unwrap();
+        write_mod_zip(
+            &dir.path().join("old/examplemod-1.9.0.zip"),
+            "examplemod",
+            "1.9.0",
+        );
+        write_mod_zip(
+            &dir.path().join("examplemod-1.10.0.zip"),
+            "examplemod",
+            "1.10.0",
+        );
+        std::fs::write(dir.path().join("notamod.zip"), b"not a zip").unwrap();
+
+        let index = build_index(dir.path(), Some("Team".to_string())).unwrap();
+        assert_eq!(index.mods.len(), 1);
+
+        let releases = &index.mods[0].releases;
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].version, "1.10.0");
+        assert_eq!(releases[1].file, "old/examplemod-1.9.0.zip");
+        assert_eq!(releases[1].sha256.len(), 64);
+        assert_eq!(
+            releases[0].dependencies.get("othermod").map(String::as_str),
+            Some("")
+        );
+        assert_eq!(
+            releases[0].dependencies.get("game").map(String::as_str),
+            Some("1.19.0")
+        );
+    }
+
+    #[test]
+    fn test_repository_locations() {
+        let http = RepositoryLocation::parse("http://mods.example.lan/team/");
+        assert_eq!(
+            http,
+            RepositoryLocation::Http {
+                index_url: "http://mods.example.lan/team/index.json".to_string()
+            }
+        );
+        assert_eq!(
+            http.file_location("old/example mod.zip"),
+            "http://mods.example.lan/team/old/example%20mod.zip"
+        );
+
+        let file = RepositoryLocation::parse("file:///srv/mods/index.json");
+        assert_eq!(
+            file,
+            RepositoryLocation::File {
+                index_path: PathBuf::from("/srv/mods/index.json")
+            }
+        );
+        assert_eq!(
+            PathBuf::from(file.file_location("old/examplemod.zip")),
+            PathBuf::from("/srv/mods/old/examplemod.zip")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_index_files_stay_inside_repository() {
+        for file in ["examplemod.zip", "old/examplemod-1.9.0.zip"] {
+            assert!(check_release_file(file).is_ok(), "{}", file);
+        }
+        for file in [
+            "",
+            "../secret.zip",
+            "old/../../secret.zip",
+            "/etc/passwd",
+            "C:/Windows/evil.zip",
+            "old\\..\\..\\evil.zip",
+            "old//examplemod.zip",
+        ] {
+            assert!(check_release_file(file).is_err(), "{}", file);
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let index_path = dir.path().join(INDEX_FILE_NAME);
+        std::fs::write(
+            &index_path,
+            r#"{"format": 1, "mods": [{"modid": "examplemod", "name": "Example", "releases": [
+                {"version": "1.0.0", "file": "../outside.zip", "sha256": "00", "size": 1}
+            ]}]}"#,
+        )
+        .unwrap();
+        let err = load_index(&index_path.to_string_lossy()).await.unwrap_err();
+        assert!(err.contains("../outside.zip"), "{}", err);
+    }
+}
@@ -0,0 +1,222 @@
+// Which side of the game a mod runs on. modinfo.json declares a `side`
+// (universal, client or server; universal when absent) and, for universal
+// mods, whether the other side must have it too (`requiredOnClient` and
+// `requiredOnServer`, both true when absent). Servers and clients only load
+// what is meant for them, so a server pack should hold no client-only mods
+// and a client joining a server needs exactly the universal mods it requires.
+
+use crate::mod_manager::{Mod, ModInfo};
+use crate::mod_pack::ModPack;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ModSide {
+    #[default]
+    Universal,
+    Client,
+    Server,
+}
+
+impl ModSide {
+    fn parse(side: Option<&str>) -> Self {
+        match side.map(|s| s.trim().to_lowercase()).as_deref() {
+            Some("client") => ModSide::Client,
+            Some("server") => ModSide::Server,
+            _ => ModSide::Universal,
+        }
+    }
+
+    // Whether a mod declared for `self` is loaded on `side`
+    fn runs_on(self, side: ModSide) -> bool {
+        self == ModSide::Universal || side == ModSide::Universal || self == side
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModSideInfo {
+    pub mod_id: String,
+    pub name: String,
+    pub version: String,
+    pub side: ModSide,
+    pub required_on_client: bool,
+    pub required_on_server: bool,
+}
+
+impl ModSideInfo {
+    fn from_mod(mod_item: &Mod) -> Self {
+        let info: Option<&ModInfo> = mod_item.info.as_ref();
+        ModSideInfo {
+            mod_id: mod_item.id.clone(),
+            name: mod_item.name.clone(),
+            version: mod_item.version.clone(),
+            side: ModSide::parse(info.and_then(|i| i.side.as_deref())),
+            required_on_client: info.and_then(|i| i.requiredonclient).unwrap_or(true),
+            required_on_server: info.and_then(|i| i.requiredonserver).unwrap_or(true),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackSideReport {
+    pub side: ModSide,
+    // Pack mods that do not run on that side
+    pub wrong_side: Vec<ModSideInfo>,
+    // Pack mods that are not installed, so their side is unknown
+    pub unknown: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientRequirements {
+    // Universal mods the server makes clients have
+    pub required: Vec<ModSideInfo>,
+    // Universal mods clients may skip, and client-only mods that come with the set
+    pub optional: Vec<ModSideInfo>,
+    // Server-only mods, which clients never load
+    pub server_only: Vec<ModSideInfo>,
+    pub unknown: Vec<String>, // Mods of the set that are not installed
+}
+
+fn find_installed<'a>(mod_list: &'a [Mod], mod_id: &str) -> Option<&'a Mod> {
+    mod_list.iter().find(|m| m.id.eq_ignore_ascii_case(mod_id))
+}
+
+fn client_requirements(mods: &[ModSideInfo]) -> ClientRequirements {
+    let mut requirements = ClientRequirements::default();
+    for info in mods {
+        let list = match info.side {
+            ModSide::Server => &mut requirements.server_only,
+            ModSide::Client => &mut requirements.optional,
+            ModSide::Universal if info.required_on_client => &mut requirements.required,
+            ModSide::Universal => &mut requirements.optional,
+        };
+        list.push(info.clone());
+    }
+    requirements
+}
+
+// Installed mods that run on `side`; universal lists them all
+#[command]
+pub async fn filter_mods_by_side(
+    mods_path: String,
+    side: ModSide,
+    enabled_only: Option<bool>,
+) -> Result<Vec<ModSideInfo>, String> {
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path).await?;
+    Ok(mod_list
+        .iter()
+        .filter(|m| m.enabled || !enabled_only.unwrap_or(false))
+        .map(ModSideInfo::from_mod)
+        .filter(|info| info.side.runs_on(side))
+        .collect())
+}
+
+// Check that every mod of a pack runs on the side it is meant for, e.g. that
+// a server pack has no client-only mods
+#[command]
+pub async fn validate_pack_side(
+    pack_path: String,
+    mods_path: String,
+    side: ModSide,
+) -> Result<PackSideReport, String> {
+    let pack = ModPack::from_file(Path::new(&pack_path))
+        .map_err(|e| format!("Failed to load mod pack: {}", e))?;
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path).await?;
+
+    let mut report = PackSideReport {
+        side,
+        ..Default::default()
+    };
+    for pack_mod in &pack.mods {
+        match find_installed(&mod_list, &pack_mod.id) {
+            Some(installed) => {
+                let info = ModSideInfo::from_mod(installed);
+                if !info.side.runs_on(side) {
+                    report.wrong_side.push(info);
+                }
+            }
+            None => report.unknown.push(pack_mod.id.clone()),
+        }
+    }
+    Ok(report)
+}
+
+// What a client needs to join a server running `mod_ids`, or the mods of
+// `pack_path` when given. Sides are read from the installed copies.
+#[command]
+pub async fn client_mods_for_server(
+    mods_path: String,
+    mod_ids: Option<Vec<String>>,
+    pack_path: Option<String>,
+) -> Result<ClientRequirements, String> {
+    let server_mods: Vec<String> = match (pack_path, mod_ids) {
+        (Some(pack_path), _) => ModPack::from_file(Path::new(&pack_path))
+            .map_err(|e| format!("Failed to load mod pack: {}", e))?
+            .mods
+            .into_iter()
+            .map(|m| m.id)
+            .collect(),
+        (None, Some(mod_ids)) => mod_ids,
+        (None, None) => return Err("Give the server's mods or a mod pack".to_string()),
+    };
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path).await?;
+
+    let mut infos = Vec::new();
+    let mut unknown = Vec::new();
+    for mod_id in server_mods {
+        match find_installed(&mod_list, &mod_id) {
+            Some(installed) => infos.push(ModSideInfo::from_mod(installed)),
+            None => unknown.push(mod_id),
+        }
+    }
+
+    let mut requirements = client_requirements(&infos);
+    requirements.unknown = unknown;
+    Ok(requirements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(modid: &str, modinfo: serde_json::Value) -> Mod {
+        crate::mod_manager::test_mod(modid, "1.0.0", true, modinfo)
+    }
+
+    #[test]
+    fn test_client_requirements() {
+        use serde_json::json;
+        let mods: Vec<ModSideInfo> = [
+            installed("worldgen", json!({})),
+            installed("minimap", json!({"side": "Client"})),
+            installed("backups", json!({"side": "server"})),
+            installed(
+                "chatformat",
+                json!({"side": "Universal", "requiredOnClient": false}),
+            ),
+        ]
+        .iter()
+        .map(ModSideInfo::from_mod)
+        .collect();
+
+        let ids = |list: &[ModSideInfo]| -> Vec<String> {
+            list.iter().map(|m| m.mod_id.clone()).collect()
+        };
+        let requirements = client_requirements(&mods);
+        assert_eq!(ids(&requirements.required), vec!["worldgen"]);
+        assert_eq!(ids(&requirements.optional), vec!["minimap", "chatformat"]);
+        assert_eq!(ids(&requirements.server_only), vec!["backups"]);
+
+        let on_server: Vec<&str> = mods
+            .iter()
+            .filter(|m| m.side.runs_on(ModSide::Server))
+            .map(|m| m.mod_id.as_str())
+            .collect();
+        assert_eq!(on_server, vec!["worldgen", "backups", "chatformat"]);
+    }
+}
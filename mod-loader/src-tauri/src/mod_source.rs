@@ -0,0 +1,877 @@
+// Where mods come from. Every source can resolve a mod (optionally pinned to a
+// version) to a file, list the releases it knows about and install one; mod
+// packs record the source of each mod so a pack can mix them explicitly.
+
+use crate::api_client::{self, ModRelease};
+use crate::mod_repository::RepositorySource;
+use crate::release_host::{ReleaseHostKind, ReleaseHostSource};
+use crate::request_policy::{FetchOutcome, ResponseMeta};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::command;
+
+const MOD_DB_SITE_URL: &str = "https://mods.vintagestory.at";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ModSourceSpec {
+    // The ModDB API; `id` is the numeric ModDB id when it should be used instead of the modid
+    Moddb {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+    },
+    // A fixed download URL
+    Url {
+        url: String,
+    },
+    // A folder of mod zips, or a single zip, on this machine
+    Local {
+        path: String,
+    },
+    // The download link scraped from a ModDB mod page (latest file only)
+    Scrape {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        page_url: Option<String>,
+    },
+    // Zip assets of GitHub releases; `base_url` points at another API host, e.g. a local stand-in
+    Github {
+        repo: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_url: Option<String>,
+        #[serde(default)]
+        prereleases: bool,
+    },
+    // Zip assets of releases on a Gitea or Forgejo instance at `base_url`
+    Gitea {
+        repo: String,
+        base_url: String,
+        #[serde(default)]
+        prereleases: bool,
+    },
+    // A static repository with an index.json, as a folder path, file:// or http(s):// URL
+    Repository {
+        url: String,
+    },
+}
+
+impl Default for ModSourceSpec {
+    fn default() -> Self {
+        ModSourceSpec::Moddb { id: None }
+    }
+}
+
+impl ModSourceSpec {
+    // Map the free-form `url` older packs carry onto a source
+    pub(crate) fn infer(url: Option<&str>) -> Self {
+        let url = match url.map(str::trim).filter(|u| !u.is_empty()) {
+            Some(url) => url,
+            None => return ModSourceSpec::default(),
+        };
+
+        for marker in ["/api/mod/", "/api/mods/", "/show/mod/"] {
+            if let Some((_, rest)) = url.split_once(marker) {
+                let id = rest.trim_end_matches('/');
+                return ModSourceSpec::Moddb {
+                    id: Some(id.to_string()).filter(|id| !id.is_empty()),
+                };
+            }
+        }
+
+        if url.ends_with(crate::mod_repository::INDEX_FILE_NAME) {
+            return ModSourceSpec::Repository {
+                url: url.to_string(),
+            };
+        }
+
+        if let Some(repo) = github_repo(url) {
+            return ModSourceSpec::Github {
+                repo,
+                base_url: None,
+                prereleases: false,
+            };
+        }
+
+        if let Some(path) = url.strip_prefix("file://") {
+            return ModSourceSpec::Local {
+                path: path.to_string(),
+            };
+        }
+
+        let is_http = url.starts_with("http://") || url.starts_with("https://");
+        let is_file = url.ends_with(".zip") || url.contains("/download/");
+        if is_http && is_file {
+            return ModSourceSpec::Url {
+                url: url.to_string(),
+            };
+        }
+        if !is_http && Path::new(url).exists() {
+            return ModSourceSpec::Local {
+                path: url.to_string(),
+            };
+        }
+
+        // Homepages and other pages say nothing about the file; use the ModDB
+        ModSourceSpec::default()
+    }
+
+    fn release_host(&self) -> Option<ReleaseHostSource> {
+        match self {
+            ModSourceSpec::Github {
+                repo,
+                base_url,
+                prereleases,
+            } => Some(ReleaseHostSource {
+                kind: ReleaseHostKind::Github,
+                repo: repo.clone(),
+                base_url: base_url.clone(),
+                prereleases: *prereleases,
+            }),
+            ModSourceSpec::Gitea {
+                repo,
+                base_url,
+                prereleases,
+            } => Some(ReleaseHostSource {
+                kind: ReleaseHostKind::Gitea,
+                repo: repo.clone(),
+                base_url: Some(base_url.clone()),
+                prereleases: *prereleases,
+            }),
+            _ => None,
+        }
+    }
+}
+
+// "owner/name" of a github.com repository page or its releases page. Asset
+// links (releases/download/...) are single files and stay URL sources.
+fn github_repo(url: &str) -> Option<String> {
+    let path = url
+        .strip_prefix("https://github.com/")
+        .or_else(|| url.strip_prefix("http://github.com/"))?;
+    let parts: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+    match parts.as_slice() {
+        [owner, name] | [owner, name, "releases"] if !owner.is_empty() && !name.is_empty() => {
+            Some(format!("{}/{}", owner, name.trim_end_matches(".git")))
+        }
+        _ => None,
+    }
+}
+
+// A mod resolved to one concrete file
+#[derive(Debug, Clone)]
+pub(crate) struct ResolvedMod {
+    pub version: Option<String>, // None when the source cannot tell
+    pub location: String,        // Download URL or file path, depending on the source
+}
+
+pub(crate) trait ModSource {
+    // The file for `version` of `mod_id`, or the newest release compatible
+    // with `game_version` when no version is pinned
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String>;
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String>;
+
+    // Install a resolved file into `mods_dir`. `on_verifying` is called once the
+    // file is complete and is being checked.
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String>;
+}
+
+fn release(mainfile: String, version: Option<String>, created: Option<String>) -> ModRelease {
+    ModRelease {
+        mainfile,
+        modversion: version,
+        tags: Vec::new(),
+        fileid: None,
+        created,
+        releaseid: None,
+        filename: None,
+        modidstr: None,
+        downloads: None,
+        changelog: None,
+        dependencies: None,
+    }
+}
+
+pub(crate) struct ModDbSource {
+    pub id: Option<String>,
+}
+
+impl ModDbSource {
+    fn mod_ref<'a>(&'a self, mod_id: &'a str) -> &'a str {
+        self.id.as_deref().unwrap_or(mod_id)
+    }
+
+    // API docs: "Always respect the full uris returned by the api"
+    fn pick(
+        mod_id: &str,
+        releases: &[ModRelease],
+        version: Option<&str>,
+        game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        let release = match version {
+            Some(version) => api_client::find_release_by_version(mod_id, releases, version)?,
+            None if releases.is_empty() => {
+                return Err(format!("No releases of {} on the ModDB", mod_id))
+            }
+            None => api_client::select_release(mod_id, releases, game_version)?,
+        };
+
+        Ok(ResolvedMod {
+            version: release.modversion.clone(),
+            location: release.mainfile.clone(),
+        })
+    }
+}
+
+impl ModSource for ModDbSource {
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        let (releases, _) = api_client::fetch_mod_releases(self.mod_ref(mod_id)).await?;
+        Self::pick(mod_id, &releases, version, game_version)
+    }
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        api_client::fetch_mod_releases(self.mod_ref(mod_id))
+            .await
+            .map(|(releases, _)| releases)
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        api_client::fetch_and_install(
+            mod_id,
+            &resolved.location,
+            mods_dir,
+            expected_hash,
+            resolved.version.as_deref(),
+            on_verifying,
+        )
+        .await
+    }
+}
+
+pub(crate) struct DirectUrlSource {
+    pub url: String,
+}
+
+impl ModSource for DirectUrlSource {
+    async fn resolve(
+        &self,
+        _mod_id: &str,
+        version: Option<&str>,
+        _game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        // The URL is the release, so a pinned version is taken on trust
+        Ok(ResolvedMod {
+            version: version.map(|v| v.to_string()),
+            location: self.url.clone(),
+        })
+    }
+
+    async fn list_releases(&self, _mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        Ok(vec![release(self.url.clone(), None, None)])
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        api_client::fetch_and_install(
+            mod_id,
+            &resolved.location,
+            mods_dir,
+            expected_hash,
+            resolved.version.as_deref(),
+            on_verifying,
+        )
+        .await
+    }
+}
+
+pub(crate) struct LocalFolderSource {
+    pub path: PathBuf,
+}
+
+impl LocalFolderSource {
+    // Every zip in the folder (or the single zip) that identifies itself as `mod_id`
+    fn matching_zips(&self, mod_id: &str) -> Result<Vec<(PathBuf, String)>, String> {
+        let candidates: Vec<PathBuf> = if self.path.is_file() {
+            vec![self.path.clone()]
+        } else {
+            std::fs::read_dir(&self.path)
+                .map_err(|e| format!("Failed to read {}: {}", self.path.display(), e))?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("zip"))
+                .collect()
+        };
+
+        Ok(candidates
+            .into_iter()
+            .filter_map(|path| {
+                let info = crate::mod_manager::extract_modinfo_from_zip(&path).ok()?;
+                info.modid
+                    .eq_ignore_ascii_case(mod_id)
+                    .then_some((path, info.version))
+            })
+            .collect())
+    }
+}
+
+impl ModSource for LocalFolderSource {
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        _game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        let zips = self.matching_zips(mod_id)?;
+
+        let found = match version {
+            Some(wanted) => zips
+                .into_iter()
+                .find(|(_, v)| crate::version::same_version(v, wanted)),
+            None => zips
+                .into_iter()
+                .max_by(|(_, a), (_, b)| crate::version::compare(a, b)),
+        };
+
+        found
+            .map(|(path, version)| ResolvedMod {
+                version: Some(version),
+                location: path.to_string_lossy().to_string(),
+            })
+            .ok_or_else(|| match version {
+                Some(v) => format!(
+                    "Version {} of {} not found in {}",
+                    v,
+                    mod_id,
+                    self.path.display()
+                ),
+                None => format!("{} not found in {}", mod_id, self.path.display()),
+            })
+    }
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        Ok(self
+            .matching_zips(mod_id)?
+            .into_iter()
+            .map(|(path, version)| release(path.to_string_lossy().to_string(), Some(version), None))
+            .collect())
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        // Same verified, atomic path a download takes
+        let part_path = api_client::partial_download_path(mods_dir, mod_id, &resolved.location);
+        std::fs::copy(&resolved.location, &part_path)
+            .map_err(|e| format!("Failed to copy {}: {}", resolved.location, e))?;
+        on_verifying();
+        api_client::install_verified_zip(mod_id, &part_path, mods_dir, None, expected_hash)
+    }
+}
+
+pub(crate) struct ScrapeSource {
+    pub page_url: Option<String>,
+}
+
+impl ScrapeSource {
+    async fn scrape_download_url(&self, mod_id: &str) -> Result<String, String> {
+        use regex::Regex;
+
+        let page_url = self
+            .page_url
+            .clone()
+            .unwrap_or_else(|| format!("{}/show/mod/{}", MOD_DB_SITE_URL, mod_id));
+        eprintln!("[ScrapeSource] Scraping mod page: {}", page_url);
+
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+        let response = client
+            .get(&page_url)
+            .header(
+                "User-Agent",
+                "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
+            )
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch page: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Page returned status: {}", response.status()));
+        }
+        let html = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read HTML: {}", e))?;
+
+        // Pattern 1: Direct download link with number
+        let download_pattern = Regex::new(r#"/download/(\d+)/[^"'\s<>]+\.(zip|tar|tar\.gz)"#)
+            .map_err(|e| format!("Failed to create regex: {}", e))?;
+        if let Some(full_match) = download_pattern.captures(&html).and_then(|cap| cap.get(0)) {
+            let path = full_match.as_str();
+            let download_url = if path.starts_with("http") {
+                path.to_string()
+            } else if path.starts_with('/') {
+                format!("{}{}", MOD_DB_SITE_URL, path)
+            } else {
+                format!("{}/{}", MOD_DB_SITE_URL, path)
+            };
+            eprintln!("[ScrapeSource] Found download URL: {}", download_url);
+            return Ok(download_url);
+        }
+
+        // Pattern 2: href attribute with download link
+        let href_pattern = Regex::new(r#"href=["']([^"']*download[^"']*\.(zip|tar|tar\.gz))"#)
+            .map_err(|e| format!("Failed to create href regex: {}", e))?;
+        for cap in href_pattern.captures_iter(&html) {
+            if let Some(url_match) = cap.get(1) {
+                let mut url = url_match.as_str().to_string();
+                if url.starts_with('/') {
+                    url = format!("{}{}", MOD_DB_SITE_URL, url);
+                } else if !url.starts_with("http") {
+                    url = format!("{}/{}", MOD_DB_SITE_URL, url);
+                }
+                if url.contains("/download/") {
+                    eprintln!("[ScrapeSource] Found download URL (href): {}", url);
+                    return Ok(url);
+                }
+            }
+        }
+
+        Err(format!("Could not find download URL for {}", mod_id))
+    }
+}
+
+impl ModSource for ScrapeSource {
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        _game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        // The mod page only links the latest file, which would not honor a pinned version
+        if let Some(version) = version {
+            return Err(format!(
+                "Could not resolve version {} of {}: a mod page only links the latest file",
+                version, mod_id
+            ));
+        }
+
+        Ok(ResolvedMod {
+            version: None,
+            location: self.scrape_download_url(mod_id).await?,
+        })
+    }
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        Ok(vec![release(
+            self.scrape_download_url(mod_id).await?,
+            None,
+            None,
+        )])
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        api_client::fetch_and_install(
+            mod_id,
+            &resolved.location,
+            mods_dir,
+            expected_hash,
+            None,
+            on_verifying,
+        )
+        .await
+    }
+}
+
+// A spec is a source too, dispatching to the implementation it names
+impl ModSource for ModSourceSpec {
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        match self {
+            ModSourceSpec::Moddb { id } => {
+                let moddb = ModDbSource { id: id.clone() };
+                match moddb.list_releases(mod_id).await {
+                    // An incompatible mod is not worth a scraping fallback, so report why instead
+                    Ok(releases) if !releases.is_empty() => {
+                        ModDbSource::pick(mod_id, &releases, version, game_version)
+                    }
+                    // Only an unreachable or empty ModDB is worth scraping, and only for the latest file
+                    result if version.is_none() && scraping_enabled().await => {
+                        if let Err(e) = result {
+                            eprintln!("[ModDbSource] {}, falling back to the mod page", e);
+                        }
+                        ScrapeSource { page_url: None }
+                            .resolve(mod_id, None, game_version)
+                            .await
+                    }
+                    Ok(_) => Err(format!("No releases of {} on the ModDB", mod_id)),
+                    Err(e) => Err(match version {
+                        Some(v) => format!("Could not resolve version {} of {}: {}", v, mod_id, e),
+                        None => e,
+                    }),
+                }
+            }
+            ModSourceSpec::Url { url } => {
+                DirectUrlSource { url: url.clone() }
+                    .resolve(mod_id, version, game_version)
+                    .await
+            }
+            ModSourceSpec::Local { path } => {
+                LocalFolderSource {
+                    path: PathBuf::from(path),
+                }
+                .resolve(mod_id, version, game_version)
+                .await
+            }
+            ModSourceSpec::Scrape { page_url } => {
+                ScrapeSource {
+                    page_url: page_url.clone(),
+                }
+                .resolve(mod_id, version, game_version)
+                .await
+            }
+            ModSourceSpec::Github { .. } | ModSourceSpec::Gitea { .. } => {
+                self.release_host()
+                    .ok_or("Not a release host")?
+                    .resolve(mod_id, version, game_version)
+                    .await
+            }
+            ModSourceSpec::Repository { url } => {
+                RepositorySource { url: url.clone() }
+                    .resolve(mod_id, version, game_version)
+                    .await
+            }
+        }
+    }
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        match self {
+            ModSourceSpec::Moddb { id } => {
+                ModDbSource { id: id.clone() }.list_releases(mod_id).await
+            }
+            ModSourceSpec::Url { url } => {
+                DirectUrlSource { url: url.clone() }
+                    .list_releases(mod_id)
+                    .await
+            }
+            ModSourceSpec::Local { path } => {
+                LocalFolderSource {
+                    path: PathBuf::from(path),
+                }
+                .list_releases(mod_id)
+                .await
+            }
+            ModSourceSpec::Scrape { page_url } => {
+                ScrapeSource {
+                    page_url: page_url.clone(),
+                }
+                .list_releases(mod_id)
+                .await
+            }
+            ModSourceSpec::Github { .. } | ModSourceSpec::Gitea { .. } => {
+                self.release_host()
+                    .ok_or("Not a release host")?
+                    .list_releases(mod_id)
+                    .await
+            }
+            ModSourceSpec::Repository { url } => {
+                RepositorySource { url: url.clone() }
+                    .list_releases(mod_id)
+                    .await
+            }
+        }
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        match self {
+            ModSourceSpec::Moddb { id } => {
+                ModDbSource { id: id.clone() }
+                    .download(mod_id, resolved, mods_dir, expected_hash, on_verifying)
+                    .await
+            }
+            ModSourceSpec::Url { url } => {
+                DirectUrlSource { url: url.clone() }
+                    .download(mod_id, resolved, mods_dir, expected_hash, on_verifying)
+                    .await
+            }
+            ModSourceSpec::Local { path } => {
+                LocalFolderSource {
+                    path: PathBuf::from(path),
+                }
+                .download(mod_id, resolved, mods_dir, expected_hash, on_verifying)
+                .await
+            }
+            ModSourceSpec::Scrape { page_url } => {
+                ScrapeSource {
+                    page_url: page_url.clone(),
+                }
+                .download(mod_id, resolved, mods_dir, expected_hash, on_verifying)
+                .await
+            }
+            ModSourceSpec::Github { .. } | ModSourceSpec::Gitea { .. } => {
+                self.release_host()
+                    .ok_or("Not a release host")?
+                    .download(mod_id, resolved, mods_dir, expected_hash, on_verifying)
+                    .await
+            }
+            ModSourceSpec::Repository { url } => {
+                RepositorySource { url: url.clone() }
+                    .download(mod_id, resolved, mods_dir, expected_hash, on_verifying)
+                    .await
+            }
+        }
+    }
+}
+
+async fn scraping_enabled() -> bool {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+    settings.enable_html_scraping
+}
+
+// The source each installed mod came from, so update checks and updates ask
+// the same place. Mods without an entry come from the ModDB.
+static SOURCES_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_sources_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_dir.join("mod-sources.json"))
+}
+
+fn load_sources() -> HashMap<String, ModSourceSpec> {
+    let path = match get_sources_path() {
+        Ok(path) if path.exists() => path,
+        _ => return HashMap::new(),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse mod sources: {}", e);
+            HashMap::new()
+        }),
+        Err(e) => {
+            eprintln!("Failed to read mod sources: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+fn update_sources(f: impl FnOnce(&mut HashMap<String, ModSourceSpec>)) -> Result<(), String> {
+    let _guard = SOURCES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut sources = load_sources();
+    f(&mut sources);
+    let content = serde_json::to_string_pretty(&sources)
+        .map_err(|e| format!("Failed to serialize mod sources: {}", e))?;
+    std::fs::write(get_sources_path()?, content)
+        .map_err(|e| format!("Failed to write mod sources: {}", e))
+}
+
+pub(crate) fn registered_source(mod_id: &str) -> Option<ModSourceSpec> {
+    let _guard = SOURCES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load_sources().remove(&mod_id.to_lowercase())
+}
+
+// Remember where a mod was installed from. The default ModDB source needs no entry.
+pub(crate) fn remember_source(mod_id: &str, source: &ModSourceSpec) {
+    let key = mod_id.to_lowercase();
+    let result = update_sources(|sources| {
+        if *source == ModSourceSpec::default() {
+            sources.remove(&key);
+        } else {
+            sources.insert(key, source.clone());
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("[mod_source] {}", e);
+    }
+}
+
+// Releases for an update check of an installed mod, from the source it was installed from
+pub(crate) async fn releases_for_update_check(
+    mod_id: &str,
+) -> Result<(Vec<ModRelease>, ResponseMeta), String> {
+    let source = registered_source(mod_id).unwrap_or_default();
+    if let Some(host) = source.release_host() {
+        return host.fetch_releases(mod_id).await;
+    }
+
+    match &source {
+        ModSourceSpec::Moddb { id } => {
+            api_client::fetch_mod_releases(id.as_deref().unwrap_or(mod_id)).await
+        }
+        other => other.list_releases(mod_id).await.map(|releases| {
+            let meta = ResponseMeta {
+                outcome: FetchOutcome::Fresh,
+                age_secs: 0,
+            };
+            (releases, meta)
+        }),
+    }
+}
+
+#[command]
+pub async fn get_mod_sources() -> Result<HashMap<String, ModSourceSpec>, String> {
+    let _guard = SOURCES_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_sources())
+}
+
+// Record where a mod comes from; None goes back to the ModDB
+#[command]
+pub async fn set_mod_source(mod_id: String, source: Option<ModSourceSpec>) -> Result<(), String> {
+    let key = mod_id.to_lowercase();
+    update_sources(|sources| match source {
+        Some(source) if source != ModSourceSpec::default() => {
+            sources.insert(key, source);
+        }
+        _ => {
+            sources.remove(&key);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_source_from_legacy_urls() {
+        assert_eq!(ModSourceSpec::infer(None), ModSourceSpec::default());
+        assert_eq!(
+            ModSourceSpec::infer(Some("https://mods.vintagestory.at/api/mod/1234")),
+            ModSourceSpec::Moddb {
+                id: Some("1234".to_string())
+            }
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some("https://mods.vintagestory.at/show/mod/42/")),
+            ModSourceSpec::Moddb {
+                id: Some("42".to_string())
+            }
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some("https://mods.vintagestory.at/download/99/example.zip")),
+            ModSourceSpec::Url {
+                url: "https://mods.vintagestory.at/download/99/example.zip".to_string()
+            }
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some("https://mods.vintagestory.at/example")),
+            ModSourceSpec::default()
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some("https://github.com/owner/examplemod/releases")),
+            ModSourceSpec::Github {
+                repo: "owner/examplemod".to_string(),
+                base_url: None,
+                prereleases: false,
+            }
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some(
+                "https://github.com/owner/examplemod/releases/download/v1.0.0/examplemod.zip"
+            )),
+            ModSourceSpec::Url {
+                url: "https://github.com/owner/examplemod/releases/download/v1.0.0/examplemod.zip"
+                    .to_string()
+            }
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some("http://mods.example.lan/team/index.json")),
+            ModSourceSpec::Repository {
+                url: "http://mods.example.lan/team/index.json".to_string()
+            }
+        );
+        assert_eq!(
+            ModSourceSpec::infer(Some("file:///srv/mods")),
+            ModSourceSpec::Local {
+                path: "/srv/mods".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_source_spec_serialization() {
+        let json = serde_json::to_string(&ModSourceSpec::default()).unwrap();
+        assert_eq!(json, r#"{"type":"moddb"}"#);
+
+        let spec: ModSourceSpec =
+            serde_json::from_str(r#"{"type":"local","path":"/srv/mods"}"#).unwrap();
+        assert_eq!(
+            spec,
+            ModSourceSpec::Local {
+                path: "/srv/mods".to_string()
+            }
+        );
+
+        let spec: ModSourceSpec = serde_json::from_str(
+            r#"{"type":"gitea","repo":"team/examplemod","base_url":"http://127.0.0.1:3000"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            spec,
+            ModSourceSpec::Gitea {
+                repo: "team/examplemod".to_string(),
+                base_url: "http://127.0.0.1:3000".to_string(),
+                prereleases: false,
+            }
+        );
+    }
+}
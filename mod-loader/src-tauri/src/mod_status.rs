@@ -1,8 +1,11 @@
+use crate::request_policy::FetchOutcome;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use tauri::command;
 
-const MOD_DB_BASE_URL: &str = "http://mods.vintagestory.at/api";
+// How many mods a status check of the whole folder works on at once
+const MAX_CONCURRENT_STATUS_CHECKS: usize = 8;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ModDependency {
@@ -20,34 +23,102 @@ pub struct ModStatus {
     pub missing_dependencies: Vec<ModDependency>,
     #[serde(rename = "outdatedDependencies")]
     pub outdated_dependencies: Vec<OutdatedDependency>,
+    // Why no update could be offered, e.g. no release supports the current game version
+    #[serde(rename = "compatibilityNote")]
+    pub compatibility_note: Option<String>,
+    // Whether the ModDB answer was fresh, needed retries or could not be fetched
+    #[serde(rename = "fetchOutcome")]
+    pub fetch_outcome: FetchOutcome,
+    #[serde(rename = "fetchError")]
+    pub fetch_error: Option<String>,
+    // Age of the release data in seconds; non-zero when served from the response cache
+    #[serde(rename = "responseAgeSecs")]
+    pub response_age_secs: Option<u64>,
+    // Every release newer than the installed one, newest first
+    pub changelogs: Vec<ReleaseChangelog>,
+    // Game version the installed mod was checked against, if known
+    #[serde(rename = "gameVersion")]
+    pub game_version: Option<String>,
+    // Highest base game version the modinfo declares as a dependency
+    #[serde(rename = "requiredGameVersion")]
+    pub required_game_version: Option<String>,
+    // Whether the game meets that requirement; None when either is unknown
+    #[serde(rename = "meetsGameRequirement")]
+    pub meets_game_requirement: Option<bool>,
+    // Game versions the ModDB release of the installed version is tagged for
+    #[serde(rename = "installedReleaseTags")]
+    pub installed_release_tags: Vec<String>,
+    // Whether those tags include the game version; None when unknown or untagged
+    #[serde(rename = "taggedCompatible")]
+    pub tagged_compatible: Option<bool>,
+    // Unix seconds of the check, so cached statuses can show their age
+    #[serde(rename = "checkedAt", default)]
+    pub checked_at: Option<u64>,
+    // The hold keeping the mod at its version, if any; never cached, always current
+    #[serde(default)]
+    pub held: Option<crate::holds::HeldMod>,
+    // Incompatibility rules that match the enabled mods and name this one
+    #[serde(rename = "knownIssues", default)]
+    pub known_issues: Vec<crate::incompatibilities::KnownIssue>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct OutdatedDependency {
+pub struct ReleaseChangelog {
+    pub version: String,
+    pub created: Option<String>,
+    pub changelog: Option<String>, // HTML, as written on the ModDB
+    #[serde(rename = "gameVersions")]
+    pub game_versions: Vec<String>,
+    // None when the game version is unknown
+    pub compatible: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewDependency {
     pub modid: String,
-    pub required: String,
-    pub installed: String,
+    pub version: Option<String>,
+    pub installed: bool,
+    // The oldest release of the update known to declare it
+    pub since: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct ModApiResponse {
-    #[serde(rename = "mod")]
-    mod_data: Option<ModApiData>,
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateDigestEntry {
+    #[serde(rename = "modId")]
+    pub mod_id: String,
+    pub name: String,
+    #[serde(rename = "installedVersion")]
+    pub installed_version: String,
+    #[serde(rename = "latestVersion")]
+    pub latest_version: Option<String>,
+    pub steps: Vec<ReleaseChangelog>, // Oldest first, ending at the latest version
+    // Dependencies the latest release declares that the installed one does not
+    #[serde(rename = "newDependencies")]
+    pub new_dependencies: Vec<NewDependency>,
+    #[serde(rename = "dependencyCheckError")]
+    pub dependency_check_error: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ModApiData {
-    releases: Vec<ModRelease>,
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateDigest {
+    #[serde(rename = "gameVersion")]
+    pub game_version: Option<String>,
+    pub entries: Vec<UpdateDigestEntry>,
 }
 
-#[derive(Debug, Deserialize)]
-struct ModRelease {
-    version: Option<String>,
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutdatedDependency {
+    pub modid: String,
+    pub required: String,
+    pub installed: String,
 }
 
 // Base game mods that should be ignored in dependency checks
-fn is_base_game_mod(modid: &str) -> bool {
-    matches!(modid.to_lowercase().as_str(), "game" | "survival" | "creative")
+pub(crate) fn is_base_game_mod(modid: &str) -> bool {
+    matches!(
+        modid.to_lowercase().as_str(),
+        "game" | "survival" | "creative"
+    )
 }
 
 // Parse dependencies from modinfo.json dependencies field
@@ -55,7 +126,15 @@ fn is_base_game_mod(modid: &str) -> bool {
 // - Array of strings: ["modid1", "modid2"]
 // - Array of objects: [{"modid": "modid1", "version": "1.0.0"}]
 // - Object: {"modid1": "1.0.0", "modid2": "2.0.0"}
-fn parse_dependencies(deps: &serde_json::Value) -> Vec<ModDependency> {
+// Base game mods are left out; see `base_game_requirement` for those.
+pub(crate) fn parse_dependencies(deps: &serde_json::Value) -> Vec<ModDependency> {
+    parse_all_dependencies(deps)
+        .into_iter()
+        .filter(|dep| !is_base_game_mod(&dep.modid))
+        .collect()
+}
+
+fn parse_all_dependencies(deps: &serde_json::Value) -> Vec<ModDependency> {
     let mut result = Vec::new();
 
     match deps {
@@ -63,29 +142,21 @@ fn parse_dependencies(deps: &serde_json::Value) -> Vec<ModDependency> {
             for item in arr {
                 match item {
                     serde_json::Value::String(modid) => {
-                        // Filter out base game mods
-                        if !is_base_game_mod(&modid) {
-                            result.push(ModDependency {
-                                modid: modid.clone(),
-                                version: None,
-                            });
-                        }
+                        result.push(ModDependency {
+                            modid: modid.clone(),
+                            version: None,
+                        });
                     }
                     serde_json::Value::Object(obj) => {
-                        if let Some(modid_val) = obj.get("modid") {
-                            if let Some(modid) = modid_val.as_str() {
-                                // Filter out base game mods
-                                if !is_base_game_mod(modid) {
-                                    let version = obj
-                                        .get("version")
-                                        .and_then(|v| v.as_str())
-                                        .map(|s| s.to_string());
-                                    result.push(ModDependency {
-                                        modid: modid.to_string(),
-                                        version,
-                                    });
-                                }
-                            }
+                        if let Some(modid) = obj.get("modid").and_then(|v| v.as_str()) {
+                            let version = obj
+                                .get("version")
+                                .and_then(|v| v.as_str())
+                                .map(|s| s.to_string());
+                            result.push(ModDependency {
+                                modid: modid.to_string(),
+                                version,
+                            });
                         }
                     }
                     _ => {}
@@ -94,14 +165,11 @@ fn parse_dependencies(deps: &serde_json::Value) -> Vec<ModDependency> {
         }
         serde_json::Value::Object(obj) => {
             for (modid, version_val) in obj {
-                // Filter out base game mods
-                if !is_base_game_mod(modid) {
-                    let version = version_val.as_str().map(|s| s.to_string());
-                    result.push(ModDependency {
-                        modid: modid.clone(),
-                        version,
-                    });
-                }
+                let version = version_val.as_str().map(|s| s.to_string());
+                result.push(ModDependency {
+                    modid: modid.clone(),
+                    version,
+                });
             }
         }
         _ => {}
@@ -110,17 +178,105 @@ fn parse_dependencies(deps: &serde_json::Value) -> Vec<ModDependency> {
     result
 }
 
-// Compare version strings (simple comparison, may need semver parsing for complex cases)
-fn compare_versions(installed: &str, required: &str) -> bool {
-    // Simple string comparison - for more complex versioning, use semver
-    installed == required || installed >= required
+// The highest game version the base game dependencies (game, survival,
+// creative) ask for, e.g. "1.20.0" for {"game": "1.20.0"}
+pub(crate) fn base_game_requirement(deps: &serde_json::Value) -> Option<String> {
+    parse_all_dependencies(deps)
+        .into_iter()
+        .filter(|dep| is_base_game_mod(&dep.modid))
+        .filter_map(|dep| dep.version)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty() && v != "*")
+        .max_by(|a, b| crate::version::compare(a, b))
+}
+
+// Releases newer than the installed version up to the one offered as latest,
+// newest first; releases past it (e.g. for another game version) are left
+// out. When the installed version is not on the ModDB, only the latest is
+// listed.
+fn newer_releases(
+    releases: &[crate::api_client::ModRelease],
+    installed_version: &str,
+    latest_version: Option<&str>,
+    game_version: Option<&str>,
+) -> Vec<ReleaseChangelog> {
+    let mut newest_first: Vec<_> = releases.iter().collect();
+    newest_first.sort_by(|a, b| b.created.cmp(&a.created));
+
+    let position = |version: &str| {
+        newest_first.iter().position(|r| {
+            r.modversion
+                .as_deref()
+                .is_some_and(|v| crate::version::same_version(v, version))
+        })
+    };
+    let start = latest_version.and_then(position).unwrap_or(0);
+    let end = match position(installed_version) {
+        Some(installed) => installed,
+        None if latest_version.is_some() => start + 1,
+        None => 0,
+    };
+
+    newest_first[start..end.max(start)]
+        .iter()
+        .map(|release| ReleaseChangelog {
+            version: release.version_label().to_string(),
+            created: release.created.clone(),
+            changelog: release.changelog.clone(),
+            game_versions: release.tags.clone(),
+            compatible: game_version
+                .filter(|_| !release.tags.is_empty())
+                .map(|v| release.supports_game_version(v)),
+        })
+        .collect()
+}
+
+// Dependencies that are not installed, and installed ones older than asked
+// for. A modinfo dependency version is a minimum, as the game reads it, so
+// any newer installed version satisfies it.
+fn dependency_issues(
+    deps: &serde_json::Value,
+    mod_list: &[crate::mod_manager::Mod],
+) -> (Vec<ModDependency>, Vec<OutdatedDependency>) {
+    let mut missing = Vec::new();
+    let mut outdated = Vec::new();
+
+    for dep in parse_dependencies(deps) {
+        let installed_mod = mod_list
+            .iter()
+            .find(|m| m.id.eq_ignore_ascii_case(&dep.modid));
+
+        match installed_mod {
+            None => missing.push(dep),
+            Some(installed) => {
+                let (Some(required_version), Some(installed_info)) =
+                    (&dep.version, &installed.info)
+                else {
+                    continue;
+                };
+                if !crate::version::satisfies_minimum(&installed_info.version, required_version) {
+                    outdated.push(OutdatedDependency {
+                        modid: dep.modid.clone(),
+                        required: required_version.clone(),
+                        installed: installed_info.version.clone(),
+                    });
+                }
+            }
+        }
+    }
+
+    (missing, outdated)
 }
 
 async fn check_mod_status_internal(
     mod_id: String,
     _mods_path: String,
     mod_list: &[crate::mod_manager::Mod],
+    game_version: Option<&str>,
 ) -> Result<ModStatus, String> {
+    use crate::api_client::select_release;
+    use crate::mod_source::releases_for_update_check;
+
     let mod_info = mod_list
         .iter()
         .find(|m| m.id == mod_id)
@@ -134,70 +290,104 @@ async fn check_mod_status_internal(
         latest_version: None,
         missing_dependencies: Vec::new(),
         outdated_dependencies: Vec::new(),
+        compatibility_note: None,
+        fetch_outcome: FetchOutcome::Fresh,
+        fetch_error: None,
+        response_age_secs: None,
+        changelogs: Vec::new(),
+        game_version: game_version.map(|v| v.to_string()),
+        required_game_version: None,
+        meets_game_requirement: None,
+        installed_release_tags: Vec::new(),
+        tagged_compatible: None,
+        checked_at: Some(crate::response_cache::now_secs()),
+        held: None,
+        known_issues: Vec::new(),
     };
 
-    // Check for updates
-    let client = reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-
-    let api_url = format!("{}/mod/{}", MOD_DB_BASE_URL, mod_id);
-    if let Ok(response) = client
-        .get(&api_url)
-        .header(
-            "User-Agent",
-            "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
-        )
-        .send()
-        .await
-    {
-        if response.status().is_success() {
-            if let Ok(text) = response.text().await {
-                if let Ok(api_response) = serde_json::from_str::<ModApiResponse>(&text) {
-                    if let Some(mod_data) = api_response.mod_data {
-                        if let Some(latest_release) = mod_data.releases.first() {
-                            if let Some(latest_version) = &latest_release.version {
-                                status.latest_version = Some(latest_version.clone());
-                                if latest_version != &mod_info.version {
-                                    status.has_update = true;
+    // A base game dependency is the mod's own statement of which game it needs
+    status.required_game_version = mod_info
+        .dependencies
+        .as_ref()
+        .and_then(base_game_requirement);
+    if let (Some(game_version), Some(required)) = (game_version, &status.required_game_version) {
+        status.meets_game_requirement =
+            Some(crate::version::satisfies_minimum(game_version, required));
+    }
+
+    // Check for updates against the newest release compatible with the game
+    // version, asking the source the mod was installed from
+    match releases_for_update_check(&mod_id).await {
+        Ok((releases, meta)) if !releases.is_empty() => {
+            status.fetch_outcome = meta.outcome;
+            status.response_age_secs = Some(meta.age_secs);
+
+            // How the ModDB tagged the release that is installed
+            if let Ok(installed) =
+                crate::api_client::find_release_by_version(&mod_id, &releases, &mod_info.version)
+            {
+                status.installed_release_tags = installed.tags.clone();
+                status.tagged_compatible = game_version
+                    .filter(|_| !installed.tags.is_empty())
+                    .map(|v| installed.supports_game_version(v));
+            }
+
+            match select_release(&mod_id, &releases, game_version) {
+                Ok(latest_release) => {
+                    if let Some(latest_version) = &latest_release.modversion {
+                        status.latest_version = Some(latest_version.clone());
+
+                        // An installed version newer than the selected release
+                        // (e.g. built for a newer game) is not an outdated one
+                        status.has_update =
+                            match crate::version::is_newer(latest_version, &mod_info.version) {
+                                Some(newer) => newer,
+                                // Versions that do not parse fall back to release dates
+                                None => {
+                                    let installed_is_newer = releases
+                                        .iter()
+                                        .find(|r| {
+                                            r.modversion.as_deref().is_some_and(|v| {
+                                                crate::version::same_version(v, &mod_info.version)
+                                            })
+                                        })
+                                        .map(|installed| installed.created > latest_release.created)
+                                        .unwrap_or(false);
+                                    latest_version != &mod_info.version && !installed_is_newer
                                 }
-                            }
-                        }
+                            };
+                    }
+
+                    if status.has_update {
+                        status.changelogs = newer_releases(
+                            &releases,
+                            &mod_info.version,
+                            status.latest_version.as_deref(),
+                            game_version,
+                        );
                     }
                 }
+                Err(note) => {
+                    status.compatibility_note = Some(note);
+                }
             }
         }
+        Ok((_, meta)) => {
+            status.fetch_outcome = meta.outcome;
+            status.response_age_secs = Some(meta.age_secs);
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch releases for {}: {}", mod_id, e);
+            status.fetch_outcome = FetchOutcome::Failed;
+            status.fetch_error = Some(e);
+        }
     }
 
     // Check dependencies
     if let Some(deps) = &mod_info.dependencies {
-        let required_deps = parse_dependencies(deps);
-
-        for dep in required_deps {
-            let installed_mod = mod_list.iter().find(|m| m.id == dep.modid);
-
-            match installed_mod {
-                None => {
-                    // Dependency is missing
-                    status.missing_dependencies.push(dep);
-                }
-                Some(installed) => {
-                    // Check version if required
-                    if let Some(required_version) = &dep.version {
-                        if let Some(installed_info) = &installed.info {
-                            if !compare_versions(&installed_info.version, required_version) {
-                                status.outdated_dependencies.push(OutdatedDependency {
-                                    modid: dep.modid.clone(),
-                                    required: required_version.clone(),
-                                    installed: installed_info.version.clone(),
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        let (missing, outdated) = dependency_issues(deps, mod_list);
+        status.missing_dependencies = missing;
+        status.outdated_dependencies = outdated;
     }
 
     Ok(status)
@@ -205,149 +395,517 @@ async fn check_mod_status_internal(
 
 #[command]
 pub async fn check_mod_status(mod_id: String, mods_path: String) -> Result<ModStatus, String> {
-    use crate::mod_manager;
-
-    let mod_list = mod_manager::get_mod_list(mods_path.clone(), Some(false))
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
         .await
         .map_err(|e| format!("Failed to load mod list: {}", e))?;
 
-    check_mod_status_internal(mod_id, mods_path, &mod_list).await
+    let game_version = crate::game_detector::current_game_version().await;
+    let mut status = check_mod_status_internal(
+        mod_id.clone(),
+        mods_path.clone(),
+        &mod_list,
+        game_version.as_deref(),
+    )
+    .await?;
+
+    let checked = HashMap::from([(mod_id.clone(), status.clone())]);
+    remember_statuses(
+        &mods_path,
+        &mod_list,
+        &checked,
+        game_version.as_deref(),
+        false,
+    );
+    status.held = crate::holds::held_mod(&mods_path, &mod_id);
+    status.known_issues = crate::incompatibilities::known_issues_by_mod(
+        &crate::incompatibilities::matching_enabled(&mod_list),
+    )
+    .remove(&mod_id.to_lowercase())
+    .unwrap_or_default();
+    Ok(status)
 }
 
+// Statuses from the last checks, without touching the network. Holds,
+// known issues and dependency issues are looked up again, as any may have
+// changed since.
 #[command]
-pub async fn check_all_mods_status(
+pub async fn get_cached_mod_statuses(
     mods_path: String,
 ) -> Result<HashMap<String, ModStatus>, String> {
-    use crate::mod_manager;
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let statuses = crate::status_cache::load(&mods_path)
+        .into_iter()
+        .map(|(mod_id, cached)| (mod_id, cached.status))
+        .collect();
+    Ok(with_holds(
+        &mods_path,
+        with_known_issues(&mod_list, with_dependency_issues(&mod_list, statuses)),
+    ))
+}
 
-    let mod_list = mod_manager::get_mod_list(mods_path.clone(), Some(false))
+// With `max_age_secs`, only mods whose cached status is older than that (or
+// out of date with the installed or game version) are checked again
+#[command]
+pub async fn check_all_mods_status(
+    mods_path: String,
+    max_age_secs: Option<u64>,
+) -> Result<HashMap<String, ModStatus>, String> {
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
         .await
         .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let game_version = crate::game_detector::current_game_version().await;
 
-    let mut results = HashMap::new();
+    let cached = match max_age_secs {
+        Some(_) => crate::status_cache::load(&mods_path),
+        None => HashMap::new(),
+    };
+    let now = crate::response_cache::now_secs();
 
-    for mod_item in &mod_list {
-        if mod_item.info.is_some() {
-            match check_mod_status_internal(mod_item.id.clone(), mods_path.clone(), &mod_list).await
+    let mut results = HashMap::new();
+    let mut stale = Vec::new();
+    for mod_item in mod_list.iter() {
+        let Some(info) = &mod_item.info else {
+            continue;
+        };
+        match (max_age_secs, cached.get(&mod_item.id)) {
+            (Some(max_age), Some(entry))
+                if entry.is_fresh(&info.version, game_version.as_deref(), max_age, now) =>
             {
-                Ok(status) => {
-                    results.insert(mod_item.id.clone(), status);
-                }
-                Err(e) => {
-                    eprintln!("Failed to check status for {}: {}", mod_item.id, e);
-                }
+                results.insert(mod_item.id.clone(), entry.status.clone());
             }
+            _ => stale.push(mod_item.id.clone()),
         }
     }
 
-    Ok(results)
+    let checked = check_statuses(
+        mods_path.clone(),
+        Arc::clone(&mod_list),
+        stale,
+        game_version.clone(),
+    )
+    .await;
+    remember_statuses(
+        &mods_path,
+        &mod_list,
+        &checked,
+        game_version.as_deref(),
+        true,
+    );
+    results.extend(checked);
+    Ok(with_holds(
+        &mods_path,
+        with_known_issues(&mod_list, with_dependency_issues(&mod_list, results)),
+    ))
 }
 
-#[command]
-pub async fn install_dependencies(
-    mod_id: String,
+// Dependencies come and go without the dependent mod changing, so cached
+// statuses get them from the current mod list
+fn with_dependency_issues(
+    mod_list: &[crate::mod_manager::Mod],
+    mut statuses: HashMap<String, ModStatus>,
+) -> HashMap<String, ModStatus> {
+    for (mod_id, status) in statuses.iter_mut() {
+        let deps = mod_list
+            .iter()
+            .find(|m| &m.id == mod_id)
+            .and_then(|m| m.info.as_ref())
+            .and_then(|info| info.dependencies.as_ref());
+        let (missing, outdated) = match deps {
+            Some(deps) => dependency_issues(deps, mod_list),
+            None => (Vec::new(), Vec::new()),
+        };
+        status.missing_dependencies = missing;
+        status.outdated_dependencies = outdated;
+    }
+    statuses
+}
+
+// Rules depend on which mods are enabled together, so they are evaluated
+// every time statuses are returned rather than cached
+fn with_known_issues(
+    mod_list: &[crate::mod_manager::Mod],
+    mut statuses: HashMap<String, ModStatus>,
+) -> HashMap<String, ModStatus> {
+    let issues = crate::incompatibilities::known_issues_by_mod(
+        &crate::incompatibilities::matching_enabled(mod_list),
+    );
+    for (mod_id, status) in statuses.iter_mut() {
+        status.known_issues = issues
+            .get(&mod_id.to_lowercase())
+            .cloned()
+            .unwrap_or_default();
+    }
+    statuses
+}
+
+// Mark held mods; holds are looked up on every call so the cache never goes stale
+fn with_holds(
+    mods_path: &str,
+    mut statuses: HashMap<String, ModStatus>,
+) -> HashMap<String, ModStatus> {
+    let holds = crate::holds::holds_for(mods_path);
+    for (mod_id, status) in statuses.iter_mut() {
+        status.held = holds.get(&mod_id.to_lowercase()).cloned();
+    }
+    statuses
+}
+
+// Save new statuses to the status cache. `prune` drops entries for mods that
+// are no longer installed, which only a check of the whole folder can tell.
+fn remember_statuses(
+    mods_path: &str,
+    mod_list: &[crate::mod_manager::Mod],
+    statuses: &HashMap<String, ModStatus>,
+    game_version: Option<&str>,
+    prune: bool,
+) {
+    use crate::status_cache::{self, CachedStatus};
+
+    let entries = mod_list
+        .iter()
+        .filter_map(|mod_item| {
+            let status = statuses.get(&mod_item.id)?;
+            Some((
+                mod_item.id.clone(),
+                CachedStatus {
+                    installed_version: mod_item.info.as_ref()?.version.clone(),
+                    game_version: game_version.map(|v| v.to_string()),
+                    status: status.clone(),
+                },
+            ))
+        })
+        .collect();
+    let installed: Vec<String> = mod_list.iter().map(|m| m.id.clone()).collect();
+
+    if let Err(e) = status_cache::store(mods_path, entries, prune.then_some(installed.as_slice())) {
+        eprintln!("Failed to save status cache: {}", e);
+    }
+}
+
+pub(crate) async fn check_statuses(
     mods_path: String,
-) -> Result<Vec<String>, String> {
-    let status = check_mod_status(mod_id.clone(), mods_path.clone()).await?;
+    mod_list: Arc<Vec<crate::mod_manager::Mod>>,
+    mod_ids: Vec<String>,
+    game_version: Option<String>,
+) -> HashMap<String, ModStatus> {
+    let mut results = HashMap::new();
 
-    let mut installed = Vec::new();
+    // Checks run concurrently, a bounded number at a time; the request policy
+    // separately limits how many hit the ModDB at once
+    let permits = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_STATUS_CHECKS));
+    let mut checks = tokio::task::JoinSet::new();
+    for mod_id in mod_ids {
+        let mods_path = mods_path.clone();
+        let mod_list = Arc::clone(&mod_list);
+        let game_version = game_version.clone();
+        let permits = Arc::clone(&permits);
+        checks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let status = check_mod_status_internal(
+                mod_id.clone(),
+                mods_path,
+                &mod_list,
+                game_version.as_deref(),
+            )
+            .await;
+            (mod_id, status)
+        });
+    }
 
-    // Install missing dependencies
-    for dep in status.missing_dependencies {
-        eprintln!("Installing missing dependency: {}", dep.modid);
-        match crate::api_client::get_mod_download_url(dep.modid.clone(), None).await {
-            Ok(download_url) => {
-                match crate::api_client::download_mod(
-                    dep.modid.clone(),
-                    download_url,
-                    mods_path.clone(),
-                )
-                .await
-                {
-                    Ok(_) => {
-                        // Reindex the newly installed mod
-                        if let Err(e) =
-                            crate::mod_manager::reindex_mod(mods_path.clone(), dep.modid.clone())
-                                .await
-                        {
-                            eprintln!("Failed to reindex {}: {}", dep.modid, e);
-                        }
-                        installed.push(dep.modid.clone());
-                    }
-                    Err(e) => {
-                        return Err(format!(
-                            "Failed to download dependency {}: {}",
-                            dep.modid, e
-                        ));
-                    }
-                }
+    while let Some(joined) = checks.join_next().await {
+        match joined {
+            Ok((mod_id, Ok(status))) => {
+                results.insert(mod_id, status);
+            }
+            Ok((mod_id, Err(e))) => {
+                eprintln!("Failed to check status for {}: {}", mod_id, e);
             }
             Err(e) => {
-                return Err(format!(
-                    "Failed to get download URL for dependency {}: {}",
-                    dep.modid, e
-                ));
+                eprintln!("Status check task failed: {}", e);
+            }
+        }
+    }
+
+    results
+}
+
+// Dependencies an update adds: those the latest release declares that the
+// installed one does not, with the oldest step (versions oldest first, ending
+// at the latest) known to declare each. Only the latest release is downloaded,
+// and only when its source publishes no dependencies; earlier steps are read
+// from metadata or the download cache.
+async fn new_dependencies_in_update(
+    mod_id: &str,
+    installed_deps: &[ModDependency],
+    steps: &[String],
+    mod_list: &[crate::mod_manager::Mod],
+) -> Result<Vec<NewDependency>, String> {
+    use crate::api_client::find_release_by_version;
+    use crate::dependency_resolver::release_dependencies;
+
+    let (latest_version, earlier) = steps
+        .split_last()
+        .ok_or_else(|| format!("No newer release of {}", mod_id))?;
+    let (releases, _) = crate::mod_source::releases_for_update_check(mod_id).await?;
+    let latest = find_release_by_version(mod_id, &releases, latest_version)?;
+    let latest_deps = release_dependencies(mod_id, latest, true).await?;
+
+    // Newest first, up to the first step whose dependencies are unknown
+    let mut known_steps = Vec::new();
+    for version in earlier.iter().rev() {
+        let Ok(release) = find_release_by_version(mod_id, &releases, version) else {
+            break;
+        };
+        match release_dependencies(mod_id, release, false).await {
+            Ok(deps) => known_steps.push((version, deps)),
+            Err(_) => break,
+        }
+    }
+
+    let declares = |deps: &[ModDependency], modid: &str| {
+        deps.iter().any(|d| d.modid.eq_ignore_ascii_case(modid))
+    };
+    Ok(latest_deps
+        .into_iter()
+        .filter(|dep| !declares(installed_deps, &dep.modid))
+        .map(|dep| {
+            let since = known_steps
+                .iter()
+                .take_while(|(_, deps)| declares(deps, &dep.modid))
+                .last()
+                .map(|(version, _)| (*version).clone())
+                .unwrap_or_else(|| latest_version.clone());
+            NewDependency {
+                installed: mod_list
+                    .iter()
+                    .any(|m| m.id.eq_ignore_ascii_case(&dep.modid)),
+                modid: dep.modid,
+                version: dep.version,
+                since,
             }
+        })
+        .collect())
+}
+
+#[command]
+pub async fn get_update_digest(mods_path: String) -> Result<UpdateDigest, String> {
+    use crate::mod_manager;
+
+    let mod_list = mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+
+    let game_version = crate::game_detector::current_game_version().await;
+    let mod_ids = mod_list
+        .iter()
+        .filter(|m| m.info.is_some())
+        .map(|m| m.id.clone())
+        .collect();
+    let statuses = check_statuses(
+        mods_path.clone(),
+        Arc::clone(&mod_list),
+        mod_ids,
+        game_version.clone(),
+    )
+    .await;
+    remember_statuses(
+        &mods_path,
+        &mod_list,
+        &statuses,
+        game_version.as_deref(),
+        true,
+    );
+
+    let mut entries = Vec::new();
+    for mod_item in mod_list.iter() {
+        let (status, info) = match (statuses.get(&mod_item.id), &mod_item.info) {
+            (Some(status), Some(info)) if status.has_update => (status, info),
+            _ => continue,
+        };
+
+        entries.push(UpdateDigestEntry {
+            mod_id: mod_item.id.clone(),
+            name: info.name.clone(),
+            installed_version: info.version.clone(),
+            latest_version: status.latest_version.clone(),
+            steps: status.changelogs.iter().rev().cloned().collect(),
+            new_dependencies: Vec::new(),
+            dependency_check_error: None,
+        });
+    }
+
+    // New dependencies are looked up concurrently, a bounded number at a time
+    let permits = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_STATUS_CHECKS));
+    let mut checks = tokio::task::JoinSet::new();
+    for (index, entry) in entries.iter().enumerate() {
+        let Some(latest_version) = entry.latest_version.clone() else {
+            continue;
+        };
+        let mut steps: Vec<String> = entry.steps.iter().map(|s| s.version.clone()).collect();
+        if !steps
+            .last()
+            .is_some_and(|v| crate::version::same_version(v, &latest_version))
+        {
+            steps.push(latest_version);
         }
+        let installed_deps = mod_list
+            .iter()
+            .find(|m| m.id == entry.mod_id)
+            .and_then(|m| m.info.as_ref()?.dependencies.as_ref())
+            .map(parse_dependencies)
+            .unwrap_or_default();
+        let mod_id = entry.mod_id.clone();
+        let mod_list = Arc::clone(&mod_list);
+        let permits = Arc::clone(&permits);
+        checks.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let result =
+                new_dependencies_in_update(&mod_id, &installed_deps, &steps, &mod_list).await;
+            (index, result)
+        });
+    }
+
+    while let Some(joined) = checks.join_next().await {
+        match joined {
+            Ok((index, Ok(new_deps))) => entries[index].new_dependencies = new_deps,
+            Ok((index, Err(e))) => {
+                let entry = &mut entries[index];
+                eprintln!(
+                    "Failed to inspect {} {}: {}",
+                    entry.mod_id,
+                    entry.latest_version.as_deref().unwrap_or("unknown"),
+                    e
+                );
+                entry.dependency_check_error = Some(e);
+            }
+            Err(e) => eprintln!("Dependency check task failed: {}", e),
+        }
+    }
+
+    entries.sort_by_key(|e| e.name.to_lowercase());
+
+    Ok(UpdateDigest {
+        game_version,
+        entries,
+    })
+}
+
+// Install the dependencies of a mod as previewed by `plan_dependencies`. The
+// plan is worked out again and must match `plan`, so nothing is installed that
+// the user has not seen. A plan with dependencies that cannot be installed is
+// refused unless `allow_incomplete` is set.
+#[command]
+pub async fn install_dependencies(
+    mod_id: String,
+    mods_path: String,
+    plan: crate::dependency_resolver::DependencyPlan,
+    override_holds: Option<bool>,
+    allow_incomplete: Option<bool>,
+) -> Result<Vec<String>, String> {
+    use crate::dependency_resolver::{self, SourceReleaseProvider};
+    use crate::download_queue::{self, DownloadRequest};
+
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let game_version = crate::game_detector::current_game_version().await;
+    let current = dependency_resolver::plan_dependencies_for(
+        &[mod_id],
+        &mod_list,
+        game_version.as_deref(),
+        &SourceReleaseProvider,
+    )
+    .await;
+    current.check_unchanged(&plan)?;
+    if !allow_incomplete.unwrap_or(false) {
+        current.check_complete()?;
     }
 
-    // Update outdated dependencies
-    for dep in status.outdated_dependencies {
+    // Queue every step of the plan first so they download in parallel, each
+    // pinned to the previewed version. The queue leaves held mods alone unless
+    // the caller overrides their holds.
+    let mut queued = Vec::new();
+    let mut failures = Vec::new();
+    for step in &current.steps {
         eprintln!(
-            "Updating outdated dependency: {} ({} -> {})",
-            dep.modid, dep.installed, dep.required
+            "Installing dependency: {} {:?} -> {}",
+            step.mod_id, step.from_version, step.to_version
         );
-        match crate::api_client::get_mod_download_url(dep.modid.clone(), None).await {
-            Ok(download_url) => {
-                match crate::api_client::download_mod(
-                    dep.modid.clone(),
-                    download_url,
-                    mods_path.clone(),
-                )
-                .await
+        queued.push(download_queue::enqueue(DownloadRequest {
+            source: crate::mod_source::registered_source(&step.mod_id),
+            mod_id: step.mod_id.clone(),
+            version: Some(step.to_version.clone()),
+            download_url: None,
+            mods_path: mods_path.clone(),
+            expected_hash: None,
+            override_hold: override_holds.unwrap_or(false),
+        }));
+    }
+
+    // Whatever cannot be installed is reported, but does not stop the rest
+    let mut installed = Vec::new();
+    for item in queued {
+        match download_queue::wait_for(&item.id).await {
+            Ok(_) => {
+                // Reindex the newly installed mod
+                if let Err(e) =
+                    crate::mod_manager::reindex_mod(mods_path.clone(), item.mod_id.clone()).await
                 {
-                    Ok(_) => {
-                        // Reindex the updated mod
-                        if let Err(e) =
-                            crate::mod_manager::reindex_mod(mods_path.clone(), dep.modid.clone())
-                                .await
-                        {
-                            eprintln!("Failed to reindex {}: {}", dep.modid, e);
-                        }
-                        installed.push(dep.modid.clone());
-                    }
-                    Err(e) => {
-                        return Err(format!("Failed to update dependency {}: {}", dep.modid, e));
-                    }
+                    eprintln!("Failed to reindex {}: {}", item.mod_id, e);
                 }
+                installed.push(item.mod_id);
             }
-            Err(e) => {
-                return Err(format!(
-                    "Failed to get download URL for dependency {}: {}",
-                    dep.modid, e
-                ));
-            }
+            Err(e) => failures.push(format!("{}: {}", item.mod_id, e)),
         }
     }
 
+    if !failures.is_empty() {
+        return Err(format!(
+            "Failed to install dependencies ({}); installed: {}",
+            failures.join("; "),
+            if installed.is_empty() {
+                "none".to_string()
+            } else {
+                installed.join(", ")
+            }
+        ));
+    }
+
     Ok(installed)
 }
 
 #[command]
-pub async fn update_mod(mod_id: String, mods_path: String) -> Result<(), String> {
+pub async fn update_mod(
+    mod_id: String,
+    mods_path: String,
+    override_hold: Option<bool>,
+) -> Result<(), String> {
     let status = check_mod_status(mod_id.clone(), mods_path.clone()).await?;
 
     if !status.has_update {
         return Err("Mod is already up to date".to_string());
     }
 
-    let download_url = crate::api_client::get_mod_download_url(mod_id.clone(), None)
-        .await
-        .map_err(|e| format!("Failed to get download URL: {}", e))?;
+    // Update from wherever the mod was installed from
+    let source = crate::mod_source::registered_source(&mod_id);
+    let download_url =
+        crate::api_client::get_mod_download_url(mod_id.clone(), None, None, source.clone())
+            .await
+            .map_err(|e| format!("Failed to get download URL: {}", e))?;
 
-    crate::api_client::download_mod(mod_id.clone(), download_url, mods_path.clone())
-        .await
-        .map_err(|e| format!("Failed to download mod: {}", e))?;
+    crate::api_client::download_mod(
+        mod_id.clone(),
+        download_url,
+        mods_path.clone(),
+        None,
+        status.latest_version.clone(),
+        source,
+        override_hold,
+    )
+    .await
+    .map_err(|e| format!("Failed to download mod: {}", e))?;
 
     // Reindex the updated mod
     crate::mod_manager::reindex_mod(mods_path, mod_id)
@@ -356,3 +914,134 @@ pub async fn update_mod(mod_id: String, mods_path: String) -> Result<(), String>
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_client::ModRelease;
+
+    fn releases() -> Vec<ModRelease> {
+        serde_json::from_str(
+            r#"[
+                {"mainfile": "a", "modversion": "1.2.0", "tags": ["v1.20.0"], "created": "2024-03-01 00:00:00", "changelog": "Third"},
+                {"mainfile": "b", "modversion": "1.1.0", "tags": ["v1.19.8"], "created": "2024-02-01 00:00:00", "changelog": "Second"},
+                {"mainfile": "c", "modversion": "1.0.0", "tags": ["v1.19.8"], "created": "2024-01-01 00:00:00", "changelog": "First"}
+            ]"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_base_game_requirement() {
+        let deps =
+            serde_json::json!({"game": "1.20.0", "survival": "1.19.8", "examplelib": "1.0.0"});
+        assert_eq!(base_game_requirement(&deps).as_deref(), Some("1.20.0"));
+        let deps_only = parse_dependencies(&deps);
+        assert_eq!(deps_only.len(), 1);
+        assert_eq!(deps_only[0].modid, "examplelib");
+
+        let deps = serde_json::json!([{"modid": "game", "version": "*"}, "examplelib"]);
+        assert_eq!(base_game_requirement(&deps), None);
+    }
+
+    #[test]
+    fn test_dependency_versions_are_minimums() {
+        use crate::mod_manager::test_mod;
+        use serde_json::json;
+
+        let mod_list = vec![
+            test_mod("commonlib", "2.9.1", true, json!({})),
+            test_mod("uilib", "1.0.0", true, json!({})),
+        ];
+        let deps =
+            json!({"CommonLib": "2.8.0", "uilib": "1.2.0", "maplib": "1.0.0", "game": "1.20.0"});
+
+        let (missing, outdated) = dependency_issues(&deps, &mod_list);
+        let missing: Vec<&str> = missing.iter().map(|d| d.modid.as_str()).collect();
+        assert_eq!(missing, vec!["maplib"]);
+        assert_eq!(outdated.len(), 1);
+        assert_eq!(
+            (outdated[0].modid.as_str(), outdated[0].required.as_str()),
+            ("uilib", "1.2.0")
+        );
+    }
+
+    #[test]
+    fn test_cached_status_follows_installed_dependencies() {
+        use crate::mod_manager::test_mod;
+        use serde_json::json;
+
+        // Checked while maplib was missing
+        let cached = ModStatus {
+            has_update: false,
+            latest_version: None,
+            missing_dependencies: vec![ModDependency {
+                modid: "maplib".to_string(),
+                version: Some("1.0.0".to_string()),
+            }],
+            outdated_dependencies: Vec::new(),
+            compatibility_note: None,
+            fetch_outcome: FetchOutcome::Fresh,
+            fetch_error: None,
+            response_age_secs: None,
+            changelogs: Vec::new(),
+            game_version: None,
+            required_game_version: None,
+            meets_game_requirement: None,
+            installed_release_tags: Vec::new(),
+            tagged_compatible: None,
+            checked_at: Some(0),
+            held: None,
+            known_issues: Vec::new(),
+        };
+        let statuses = HashMap::from([("mapview".to_string(), cached)]);
+
+        let mut mod_list = vec![
+            test_mod(
+                "mapview",
+                "1.0.0",
+                true,
+                json!({"dependencies": {"maplib": "1.0.0"}}),
+            ),
+            test_mod("maplib", "1.1.0", true, json!({})),
+        ];
+        let statuses = with_dependency_issues(&mod_list, statuses);
+        assert!(statuses["mapview"].missing_dependencies.is_empty());
+
+        // Removing it again brings the issue back
+        mod_list.pop();
+        let statuses = with_dependency_issues(&mod_list, statuses);
+        let missing: Vec<&str> = statuses["mapview"]
+            .missing_dependencies
+            .iter()
+            .map(|d| d.modid.as_str())
+            .collect();
+        assert_eq!(missing, vec!["maplib"]);
+    }
+
+    #[test]
+    fn test_newer_releases_stop_at_installed() {
+        let steps = newer_releases(&releases(), "1.0.0", Some("1.2.0"), Some("1.19.8"));
+        let versions: Vec<&str> = steps.iter().map(|s| s.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.2.0", "1.1.0"]);
+        assert_eq!(steps[0].compatible, Some(false));
+        assert_eq!(steps[1].compatible, Some(true));
+        assert_eq!(steps[1].changelog.as_deref(), Some("Second"));
+    }
+
+    #[test]
+    fn test_newer_releases_unknown_installed_version() {
+        let steps = newer_releases(&releases(), "0.9.0-dev", Some("1.1.0"), None);
+        let versions: Vec<&str> = steps.iter().map(|s| s.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.1.0"]);
+        assert_eq!(steps[0].compatible, None);
+    }
+
+    #[test]
+    fn test_newer_releases_end_at_latest() {
+        // 1.2.0 is only for a newer game, so 1.1.0 is offered as latest
+        let steps = newer_releases(&releases(), "1.0.0", Some("1.1.0"), Some("1.19.8"));
+        let versions: Vec<&str> = steps.iter().map(|s| s.version.as_str()).collect();
+        assert_eq!(versions, vec!["1.1.0"]);
+    }
+}
@@ -0,0 +1,597 @@
+// Checks the JSON patches mods ship in `assets/<domain>/patches/`. The game
+// skips a patch whose target file does not exist without saying so, which
+// is easy to miss when a mod it relies on is disabled or renamed a file.
+// Patches are resolved against the game's own assets and the files of every
+// installed mod, the way the game would resolve them.
+
+use crate::asset_conflicts::{asset_paths, dir_asset_files, normalize_asset_path};
+use crate::mod_manager::Mod;
+use crate::mod_status::is_base_game_mod;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use tauri::command;
+use zip::ZipArchive;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchRef {
+    pub mod_id: String,
+    pub patch_file: String, // Asset path of the file the patch is in
+    pub index: usize,       // Position of the patch in that file
+    pub op: String,
+    pub path: String,
+    pub file: String, // Target as written in the patch
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingTarget {
+    pub patch: PatchRef,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DisabledDependency {
+    pub patch: PatchRef,
+    pub disabled_mod: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchOverlap {
+    pub file: String, // Target as domain:path
+    pub path: String,
+    pub patches: Vec<PatchRef>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchReport {
+    pub mods_scanned: usize,
+    pub patches_checked: usize,
+    pub game_assets_path: Option<String>,
+    pub missing_targets: Vec<MissingTarget>,
+    pub disabled_dependencies: Vec<DisabledDependency>,
+    // Patches from different mods that change the same place in the same file
+    pub overlaps: Vec<PatchOverlap>,
+    pub errors: Vec<String>, // Patch files or mods that could not be read
+    pub warnings: Vec<String>,
+}
+
+// One patch of an enabled mod, as far as the analysis needs it
+struct Patch {
+    patch: PatchRef,
+    depends_on: Vec<(String, bool)>, // Lowercase modid, inverted
+}
+
+// What an installed mod provides
+struct InstalledMod {
+    mod_id: String,
+    enabled: bool,
+    assets: BTreeSet<String>,
+}
+
+// The game reads asset JSON leniently: comments, trailing commas, unquoted
+// keys and single-quoted strings are all common in patch files. Rewrite
+// those into strict JSON so serde can parse it.
+fn to_strict_json(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    // The next character that is not whitespace or part of a comment
+    let next_significant = |mut j: usize| -> Option<char> {
+        while j < chars.len() {
+            match (chars[j], chars.get(j + 1)) {
+                (c, _) if c.is_whitespace() => j += 1,
+                ('/', Some('/')) => {
+                    while j < chars.len() && chars[j] != '\n' {
+                        j += 1;
+                    }
+                }
+                ('/', Some('*')) => {
+                    j += 2;
+                    while j < chars.len() && !(chars[j] == '*' && chars.get(j + 1) == Some(&'/')) {
+                        j += 1;
+                    }
+                    j += 2;
+                }
+                (c, _) => return Some(c),
+            }
+        }
+        None
+    };
+
+    while i < chars.len() {
+        let c = chars[i];
+        match (c, chars.get(i + 1)) {
+            ('"' | '\'', _) => {
+                out.push('"');
+                i += 1;
+                while i < chars.len() && chars[i] != c {
+                    match chars[i] {
+                        '\\' if i + 1 < chars.len() => {
+                            if chars[i + 1] == '\'' {
+                                out.push('\'');
+                            } else {
+                                out.push('\\');
+                                out.push(chars[i + 1]);
+                            }
+                            i += 1;
+                        }
+                        '"' => out.push_str("\\\""),
+                        ch => out.push(ch),
+                    }
+                    i += 1;
+                }
+                out.push('"');
+                i += 1;
+            }
+            ('/', Some('/')) => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            ('/', Some('*')) => {
+                i += 2;
+                while i < chars.len() && !(chars[i] == '*' && chars.get(i + 1) == Some(&'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            (',', _) => {
+                if !matches!(next_significant(i + 1), Some('}' | ']')) {
+                    out.push(',');
+                }
+                i += 1;
+            }
+            (c, _) if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' => {
+                while i < chars.len()
+                    && (chars[i].is_ascii_alphanumeric() || matches!(chars[i], '.' | '-' | '+'))
+                {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+            }
+            (c, _) if c.is_alphabetic() || c == '_' || c == '$' => {
+                let start = i;
+                while i < chars.len()
+                    && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                {
+                    i += 1;
+                }
+                let word: String = chars[start..i].iter().collect();
+                if next_significant(i) == Some(':') {
+                    out.push('"');
+                    out.push_str(&word);
+                    out.push('"');
+                } else {
+                    out.push_str(&word);
+                }
+            }
+            (c, _) => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+fn parse_patch_file(content: &str) -> Result<Vec<Value>, String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let value: Value = serde_json::from_str(content)
+        .or_else(|_| serde_json::from_str(&to_strict_json(content)))
+        .map_err(|e| format!("Failed to parse patch file: {}", e))?;
+    match value {
+        Value::Array(patches) => Ok(patches),
+        Value::Object(_) => Ok(vec![value]),
+        _ => Err("Patch file is not a list of patches".to_string()),
+    }
+}
+
+// Keys are matched case-insensitively, like the game does
+fn field<'a>(patch: &'a Value, name: &str) -> Option<&'a Value> {
+    patch
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value)
+}
+
+fn patches_in_file(mod_id: &str, patch_file: &str, content: &str) -> Result<Vec<Patch>, String> {
+    let text = |patch: &Value, name: &str| {
+        field(patch, name)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    Ok(parse_patch_file(content)?
+        .iter()
+        .enumerate()
+        .filter(|(_, patch)| field(patch, "enabled").and_then(Value::as_bool) != Some(false))
+        .map(|(index, patch)| Patch {
+            patch: PatchRef {
+                mod_id: mod_id.to_string(),
+                patch_file: patch_file.to_string(),
+                index,
+                op: text(patch, "op"),
+                path: text(patch, "path"),
+                file: text(patch, "file"),
+            },
+            depends_on: field(patch, "dependsOn")
+                .and_then(Value::as_array)
+                .map(|deps| {
+                    deps.iter()
+                        .filter_map(|dep| {
+                            let modid = field(dep, "modid")?.as_str()?.to_lowercase();
+                            let invert = field(dep, "invert").and_then(Value::as_bool);
+                            Some((modid, invert.unwrap_or(false)))
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+// Whether an asset path is a patch file
+fn is_patch_file(asset_path: &str) -> bool {
+    asset_path.split('/').nth(2) == Some("patches") && asset_path.ends_with(".json")
+}
+
+// Asset path of a patch file and its contents
+type PatchFile = (String, Result<String, String>);
+
+// The contents of every patch file of a zip or folder mod
+fn read_patch_files(mod_item: &Mod) -> Result<Vec<PatchFile>, String> {
+    let mut files = Vec::new();
+    if mod_item.is_zip {
+        let file =
+            File::open(&mod_item.path).map_err(|e| format!("Failed to open zip file: {}", e))?;
+        let mut archive =
+            ZipArchive::new(file).map_err(|e| format!("Failed to read zip archive: {}", e))?;
+        for index in 0..archive.len() {
+            let mut entry = archive
+                .by_index(index)
+                .map_err(|e| format!("Failed to read zip archive: {}", e))?;
+            let Some(asset_path) = normalize_asset_path(entry.name()).filter(|p| is_patch_file(p))
+            else {
+                continue;
+            };
+            let mut content = String::new();
+            let result = entry
+                .read_to_string(&mut content)
+                .map(|_| content)
+                .map_err(|e| format!("Failed to read {}: {}", asset_path, e));
+            files.push((asset_path, result));
+        }
+    } else {
+        for (asset_path, path) in dir_asset_files(Path::new(&mod_item.path))? {
+            if is_patch_file(&asset_path) {
+                let result = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("Failed to read {}: {}", asset_path, e));
+                files.push((asset_path, result));
+            }
+        }
+    }
+    Ok(files)
+}
+
+// A patch target as (domain, path); the game domain is the default
+fn parse_target(file: &str) -> (String, String) {
+    let file = file.trim().replace('\\', "/").to_lowercase();
+    let (domain, path) = file.split_once(':').unwrap_or(("game", &file));
+    (
+        domain.trim().to_string(),
+        path.trim_start_matches('/').to_string(),
+    )
+}
+
+// Appending to an array (a path ending in "/-") does not clash with others doing the same
+fn is_append(patch: &PatchRef) -> bool {
+    patch.path.trim_end().ends_with("/-") || patch.op.eq_ignore_ascii_case("addeach")
+}
+
+// Resolve every patch against the installed mods; `game_has` tells whether
+// the base game has an asset (None when the game assets cannot be read)
+fn analyze(
+    patches: Vec<Patch>,
+    installed: &[InstalledMod],
+    game_has: impl Fn(&str) -> Option<bool>,
+    report: &mut PatchReport,
+) {
+    let enabled_ids: BTreeSet<String> = installed
+        .iter()
+        .filter(|m| m.enabled)
+        .map(|m| m.mod_id.to_lowercase())
+        .collect();
+    let installed_ids: BTreeMap<String, &InstalledMod> = installed
+        .iter()
+        .map(|m| (m.mod_id.to_lowercase(), m))
+        .collect();
+    let mut game_unknown = false;
+    let mut touched: BTreeMap<(String, String), Vec<PatchRef>> = BTreeMap::new();
+
+    'patches: for Patch { patch, depends_on } in patches {
+        report.patches_checked += 1;
+
+        // The game skips patches whose dependsOn is not met, so only a
+        // disabled dependency is worth mentioning
+        for (modid, invert) in &depends_on {
+            let active = enabled_ids.contains(modid);
+            if active == *invert {
+                if let Some(dependency) = installed_ids.get(modid).filter(|_| !invert) {
+                    report.disabled_dependencies.push(DisabledDependency {
+                        reason: format!("Only applies while {} is enabled", dependency.mod_id),
+                        disabled_mod: dependency.mod_id.clone(),
+                        patch,
+                    });
+                }
+                continue 'patches;
+            }
+        }
+
+        if patch.file.trim().is_empty() {
+            report.missing_targets.push(MissingTarget {
+                reason: "The patch does not name a file".to_string(),
+                patch,
+            });
+            continue;
+        }
+        let (domain, path) = parse_target(&patch.file);
+        touched
+            .entry((
+                format!("{}:{}", domain, path),
+                patch.path.trim().to_lowercase(),
+            ))
+            .or_default()
+            .push(patch.clone());
+        // Wildcard targets are matched by the game at load time; nothing to resolve here
+        if path.contains('*') {
+            continue;
+        }
+
+        let asset = format!(
+            "assets/{}/{}",
+            if is_base_game_mod(&domain) {
+                "game"
+            } else {
+                &domain
+            },
+            path
+        );
+        let providers: Vec<&InstalledMod> = installed
+            .iter()
+            .filter(|m| m.assets.contains(&asset))
+            .collect();
+        if providers.iter().any(|m| m.enabled) {
+            continue;
+        }
+        if is_base_game_mod(&domain) {
+            match game_has(&path) {
+                Some(true) => continue,
+                Some(false) => {}
+                None => {
+                    game_unknown = true;
+                    continue;
+                }
+            }
+        }
+
+        if let Some(provider) = providers.first() {
+            report.disabled_dependencies.push(DisabledDependency {
+                reason: format!("Targets a file of {}, which is disabled", provider.mod_id),
+                disabled_mod: provider.mod_id.clone(),
+                patch,
+            });
+        } else if let Some(owner) = installed_ids.get(&domain).filter(|m| !m.enabled) {
+            report.disabled_dependencies.push(DisabledDependency {
+                reason: format!("Targets {}, which is disabled", owner.mod_id),
+                disabled_mod: owner.mod_id.clone(),
+                patch,
+            });
+        } else {
+            let reason = if is_base_game_mod(&domain) {
+                format!("The game has no {}", path)
+            } else if installed_ids.contains_key(&domain) {
+                format!("{} has no {}", domain, path)
+            } else {
+                format!("Mod {} is not installed", domain)
+            };
+            report.missing_targets.push(MissingTarget { patch, reason });
+        }
+    }
+
+    if game_unknown {
+        report.warnings.push(
+            "The game's assets folder was not found, so patches to game files were not checked; set the game install folder in the settings".to_string(),
+        );
+    }
+
+    report.overlaps = touched
+        .into_iter()
+        .filter(|(_, patches)| {
+            let mods: BTreeSet<&str> = patches
+                .iter()
+                .filter(|p| !is_append(p))
+                .map(|p| p.mod_id.as_str())
+                .collect();
+            mods.len() > 1
+        })
+        .map(|((file, path), patches)| PatchOverlap {
+            file,
+            path,
+            patches,
+        })
+        .collect();
+}
+
+#[command]
+pub async fn analyze_mod_patches(mods_path: String) -> Result<PatchReport, String> {
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let game_assets = crate::game_detector::game_assets_dir().await;
+
+    let mut report = PatchReport {
+        game_assets_path: game_assets
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+
+    let mut installed = Vec::new();
+    let mut patches = Vec::new();
+    for mod_item in mod_list.iter() {
+        match asset_paths(mod_item) {
+            Ok(assets) => installed.push(InstalledMod {
+                mod_id: mod_item.id.clone(),
+                enabled: mod_item.enabled,
+                assets,
+            }),
+            Err(e) => report.errors.push(format!("{}: {}", mod_item.id, e)),
+        }
+        if !mod_item.enabled {
+            continue;
+        }
+
+        report.mods_scanned += 1;
+        match read_patch_files(mod_item) {
+            Ok(files) => {
+                for (patch_file, content) in files {
+                    match content.and_then(|c| patches_in_file(&mod_item.id, &patch_file, &c)) {
+                        Ok(found) => patches.extend(found),
+                        Err(e) => report
+                            .errors
+                            .push(format!("{} {}: {}", mod_item.id, patch_file, e)),
+                    }
+                }
+            }
+            Err(e) => report.errors.push(format!("{}: {}", mod_item.id, e)),
+        }
+    }
+
+    // Base game assets are spread over the game, survival and creative folders
+    let game_has = |path: &str| {
+        let assets = game_assets.as_ref()?;
+        Some(
+            ["game", "survival", "creative"]
+                .iter()
+                .any(|folder| assets.join(folder).join(path).is_file()),
+        )
+    };
+    analyze(patches, &installed, game_has, &mut report);
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_strict_json() {
+        let lenient = r#"[
+            // Make pans cheaper
+            { op: 'replace', path: "/attributes/price", value: 1.5e2, file: "game:itemtypes/pan.json", },
+            /* disabled for now */
+            { "op": "add", "path": "/tags/-", "value": 'it\'s "quoted"', enabled: false },
+        ]"#;
+        let patches = parse_patch_file(lenient).unwrap();
+        assert_eq!(patches.len(), 2);
+        assert_eq!(patches[0]["op"], "replace");
+        assert_eq!(patches[0]["value"], 150.0);
+        assert_eq!(patches[1]["value"], "it's \"quoted\"");
+        assert_eq!(patches[1]["enabled"], false);
+    }
+
+    #[test]
+    fn test_analyze_resolves_targets() {
+        let patch = |mod_id: &str, file: &str, path: &str, depends_on: &[(&str, bool)]| Patch {
+            patch: PatchRef {
+                mod_id: mod_id.to_string(),
+                patch_file: format!("assets/{}/patches/test.json", mod_id),
+                index: 0,
+                op: "replace".to_string(),
+                path: path.to_string(),
+                file: file.to_string(),
+            },
+            depends_on: depends_on
+                .iter()
+                .map(|(m, invert)| (m.to_string(), *invert))
+                .collect(),
+        };
+        let installed = vec![
+            InstalledMod {
+                mod_id: "pans".to_string(),
+                enabled: true,
+                assets: BTreeSet::from(["assets/pans/itemtypes/pan.json".to_string()]),
+            },
+            InstalledMod {
+                mod_id: "carts".to_string(),
+                enabled: false,
+                assets: BTreeSet::from(["assets/carts/entities/cart.json".to_string()]),
+            },
+        ];
+        let patches = vec![
+            patch("pans", "game:itemtypes/bowl.json", "/maxstacksize", &[]),
+            patch("tweaks", "itemtypes/bowl.json", "/maxstacksize", &[]),
+            patch("tweaks", "game:itemtypes/nothing.json", "/x", &[]),
+            patch("tweaks", "pans:itemtypes/pan.json", "/x", &[]),
+            patch("tweaks", "carts:entities/cart.json", "/x", &[]),
+            patch(
+                "tweaks",
+                "carts:entities/cart.json",
+                "/y",
+                &[("carts", false)],
+            ),
+            patch("tweaks", "wagons:entities/wagon.json", "/x", &[]),
+            patch(
+                "tweaks",
+                "wagons:entities/wagon.json",
+                "/y",
+                &[("wagons", false)],
+            ),
+        ];
+
+        let mut report = PatchReport::default();
+        analyze(
+            patches,
+            &installed,
+            |path| Some(path == "itemtypes/bowl.json"),
+            &mut report,
+        );
+
+        assert_eq!(report.patches_checked, 8);
+        let missing: Vec<&str> = report
+            .missing_targets
+            .iter()
+            .map(|m| m.reason.as_str())
+            .collect();
+        assert_eq!(
+            missing,
+            vec![
+                "The game has no itemtypes/nothing.json",
+                "Mod wagons is not installed"
+            ]
+        );
+        let disabled: Vec<(&str, &str)> = report
+            .disabled_dependencies
+            .iter()
+            .map(|d| (d.disabled_mod.as_str(), d.patch.path.as_str()))
+            .collect();
+        assert_eq!(disabled, vec![("carts", "/x"), ("carts", "/y")]);
+
+        assert_eq!(report.overlaps.len(), 1);
+        assert_eq!(report.overlaps[0].file, "game:itemtypes/bowl.json");
+        assert_eq!(report.overlaps[0].patches.len(), 2);
+    }
+}
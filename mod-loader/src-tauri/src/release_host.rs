@@ -0,0 +1,258 @@
+// Mods published as release assets on GitHub or a Gitea/Forgejo instance.
+// Releases come from the host's releases API; each release is one mod version
+// and its zip asset is the file. The base URL is configurable so a mirror or a
+// local stand-in for the API can be used.
+
+use crate::api_client::{self, ModRelease};
+use crate::mod_source::{ModSource, ResolvedMod};
+use crate::request_policy::{self, ResponseMeta};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+const GITHUB_API_URL: &str = "https://api.github.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReleaseHostKind {
+    Github,
+    Gitea,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostRelease {
+    tag_name: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    published_at: Option<String>,
+    #[serde(default)]
+    created_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<HostAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    download_count: Option<u64>,
+}
+
+pub(crate) struct ReleaseHostSource {
+    pub kind: ReleaseHostKind,
+    pub repo: String,             // "owner/name"
+    pub base_url: Option<String>, // Required for Gitea; GitHub defaults to api.github.com
+    pub prereleases: bool,
+}
+
+impl ReleaseHostSource {
+    fn releases_url(&self) -> Result<String, String> {
+        let repo = self.repo.trim().trim_matches('/');
+        if repo.split('/').filter(|part| !part.is_empty()).count() != 2 {
+            return Err(format!(
+                "Invalid repository \"{}\", expected owner/name",
+                self.repo
+            ));
+        }
+
+        let base = self
+            .base_url
+            .as_deref()
+            .map(|url| url.trim_end_matches('/'));
+        match (self.kind, base) {
+            (ReleaseHostKind::Github, base) => Ok(format!(
+                "{}/repos/{}/releases?per_page=100",
+                base.unwrap_or(GITHUB_API_URL),
+                repo
+            )),
+            (ReleaseHostKind::Gitea, Some(base)) => {
+                Ok(format!("{}/api/v1/repos/{}/releases?limit=50", base, repo))
+            }
+            (ReleaseHostKind::Gitea, None) => Err(format!(
+                "No base URL configured for Gitea repository {}",
+                repo
+            )),
+        }
+    }
+
+    // Releases as the rest of the loader sees them, newest first. Drafts are
+    // never listed, and neither are releases without a zip asset.
+    pub(crate) async fn fetch_releases(
+        &self,
+        mod_id: &str,
+    ) -> Result<(Vec<ModRelease>, ResponseMeta), String> {
+        let url = self.releases_url()?;
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(15))
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let (body, meta) = request_policy::get_text(&client, &url).await?;
+        let host_releases: Vec<HostRelease> = serde_json::from_str(&body)
+            .map_err(|e| format!("Failed to parse releases of {}: {}", self.repo, e))?;
+
+        let mut releases = to_mod_releases(host_releases, mod_id, self.prereleases);
+        releases.sort_by(|a, b| b.created.cmp(&a.created));
+        Ok((releases, meta))
+    }
+}
+
+fn to_mod_releases(
+    host_releases: Vec<HostRelease>,
+    mod_id: &str,
+    prereleases: bool,
+) -> Vec<ModRelease> {
+    host_releases
+        .into_iter()
+        .filter(|release| !release.draft && (prereleases || !release.prerelease))
+        .filter_map(|release| {
+            let asset = pick_zip_asset(&release.assets, mod_id)?;
+            Some(ModRelease {
+                mainfile: asset.browser_download_url.clone(),
+                modversion: Some(crate::version::normalize(&release.tag_name).to_string()),
+                // Release hosts know nothing about game versions
+                tags: Vec::new(),
+                fileid: None,
+                created: release.published_at.or(release.created_at),
+                releaseid: None,
+                filename: Some(asset.name.clone()),
+                modidstr: None,
+                downloads: asset.download_count,
+                changelog: release.body.filter(|b| !b.trim().is_empty()),
+                dependencies: None,
+            })
+        })
+        .collect()
+}
+
+// The zip asset of a release. Repositories that ship several mods attach one
+// zip each, so prefer the one named after the mod.
+fn pick_zip_asset<'a>(assets: &'a [HostAsset], mod_id: &str) -> Option<&'a HostAsset> {
+    let mod_id = mod_id.to_lowercase();
+    let zips: Vec<&HostAsset> = assets
+        .iter()
+        .filter(|asset| asset.name.to_lowercase().ends_with(".zip"))
+        .collect();
+
+    zips.iter()
+        .find(|asset| asset.name.to_lowercase().starts_with(&mod_id))
+        .or_else(|| zips.first())
+        .copied()
+}
+
+impl ModSource for ReleaseHostSource {
+    async fn resolve(
+        &self,
+        mod_id: &str,
+        version: Option<&str>,
+        game_version: Option<&str>,
+    ) -> Result<ResolvedMod, String> {
+        let (releases, _) = self.fetch_releases(mod_id).await?;
+        let release = match version {
+            Some(version) => api_client::find_release_by_version(mod_id, &releases, version)
+                .map_err(|_| {
+                    format!(
+                        "Version {} of {} not found in {}",
+                        version, mod_id, self.repo
+                    )
+                })?,
+            None if releases.is_empty() => {
+                return Err(format!("No releases with a zip asset in {}", self.repo))
+            }
+            None => api_client::select_release(mod_id, &releases, game_version)?,
+        };
+
+        Ok(ResolvedMod {
+            version: release.modversion.clone(),
+            location: release.mainfile.clone(),
+        })
+    }
+
+    async fn list_releases(&self, mod_id: &str) -> Result<Vec<ModRelease>, String> {
+        self.fetch_releases(mod_id)
+            .await
+            .map(|(releases, _)| releases)
+    }
+
+    async fn download(
+        &self,
+        mod_id: &str,
+        resolved: &ResolvedMod,
+        mods_dir: &Path,
+        expected_hash: Option<&str>,
+        on_verifying: impl FnOnce(),
+    ) -> Result<PathBuf, String> {
+        // Verification reads the modinfo, so an asset for another mod is refused
+        api_client::fetch_and_install(
+            mod_id,
+            &resolved.location,
+            mods_dir,
+            expected_hash,
+            resolved.version.as_deref(),
+            on_verifying,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_releases_from_host_json() {
+        let json = r#"[
+            {"tag_name": "v1.3.0-rc.1", "prerelease": true, "published_at": "2024-03-01T10:00:00Z",
+             "assets": [{"name": "examplemod-1.3.0-rc.1.zip", "browser_download_url": "http://localhost/rc.zip"}]},
+            {"tag_name": "v1.2.0", "body": "Fixes", "published_at": "2024-02-01T10:00:00Z",
+             "assets": [
+                {"name": "othermod-1.2.0.zip", "browser_download_url": "http://localhost/other.zip"},
+                {"name": "examplemod-1.2.0.zip", "browser_download_url": "http://localhost/example.zip", "download_count": 7}
+             ]},
+            {"tag_name": "v1.1.0", "published_at": "2024-01-01T10:00:00Z",
+             "assets": [{"name": "source.tar.gz", "browser_download_url": "http://localhost/src.tar.gz"}]},
+            {"tag_name": "v1.4.0", "draft": true, "assets": []}
+        ]"#;
+        let host_releases: Vec<HostRelease> = serde_json::from_str(json).unwrap();
+
+        let releases = to_mod_releases(host_releases, "ExampleMod", false);
+        assert_eq!(releases.len(), 1);
+        assert_eq!(releases[0].modversion.as_deref(), Some("1.2.0"));
+        assert_eq!(releases[0].mainfile, "http://localhost/example.zip");
+        assert_eq!(releases[0].changelog.as_deref(), Some("Fixes"));
+        assert_eq!(releases[0].downloads, Some(7));
+
+        let host_releases: Vec<HostRelease> = serde_json::from_str(json).unwrap();
+        let releases = to_mod_releases(host_releases, "examplemod", true);
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].modversion.as_deref(), Some("1.3.0-rc.1"));
+    }
+
+    #[test]
+    fn test_releases_url() {
+        let mut source = ReleaseHostSource {
+            kind: ReleaseHostKind::Github,
+            repo: "owner/examplemod".to_string(),
+            base_url: None,
+            prereleases: false,
+        };
+        assert_eq!(
+            source.releases_url().unwrap(),
+            "https://api.github.com/repos/owner/examplemod/releases?per_page=100"
+        );
+
+        source.kind = ReleaseHostKind::Gitea;
+        assert!(source.releases_url().is_err());
+        source.base_url = Some("http://127.0.0.1:3000/".to_string());
+        assert_eq!(
+            source.releases_url().unwrap(),
+            "http://127.0.0.1:3000/api/v1/repos/owner/examplemod/releases?limit=50"
+        );
+
+        source.repo = "examplemod".to_string();
+        assert!(source.releases_url().is_err());
+    }
+}
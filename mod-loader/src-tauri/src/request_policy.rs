@@ -0,0 +1,359 @@
+// Shared policy for ModDB API requests: bounded concurrency, exponential
+// backoff on 5xx responses and timeouts, `Retry-After` support and a per-host
+// rate limit, so checking a large library neither hammers the ModDB nor turns
+// a transient failure into "no update". Responses go through the on-disk
+// response cache, which also answers every request in offline mode.
+
+use crate::response_cache::{self, CachedResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+
+const MAX_CONCURRENT_REQUESTS: usize = 4;
+const MAX_RETRIES: u32 = 3;
+const BASE_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+const MIN_HOST_INTERVAL: Duration = Duration::from_millis(250);
+const DEFAULT_CACHE_TTL_MINUTES: u64 = 30;
+
+// How a response was obtained, reported alongside results that depend on it
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchOutcome {
+    Fresh,   // First attempt succeeded
+    Retried, // Succeeded after one or more retries
+    Failed,  // Gave up; the result carries no server data
+    Cached,  // Served from the response cache without reaching the ModDB
+}
+
+// How a response was obtained and how old its data is
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResponseMeta {
+    pub outcome: FetchOutcome,
+    pub age_secs: u64,
+}
+
+enum Fetched {
+    Body {
+        text: String,
+        etag: Option<String>,
+        last_modified: Option<String>,
+    },
+    NotModified,
+}
+
+struct RequestPolicy {
+    permits: Semaphore,
+    next_slot: Mutex<HashMap<String, Instant>>, // host -> earliest time of next request
+}
+
+fn policy() -> &'static RequestPolicy {
+    static POLICY: OnceLock<RequestPolicy> = OnceLock::new();
+    POLICY.get_or_init(|| RequestPolicy {
+        permits: Semaphore::new(MAX_CONCURRENT_REQUESTS),
+        next_slot: Mutex::new(HashMap::new()),
+    })
+}
+
+impl RequestPolicy {
+    // Reserve the next free slot for `host` and wait for it
+    async fn wait_for_host_slot(&self, host: &str) {
+        let wait = {
+            let mut next_slot = self.next_slot.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let slot = next_slot
+                .get(host)
+                .copied()
+                .filter(|t| *t > now)
+                .unwrap_or(now);
+            next_slot.insert(host.to_string(), slot + MIN_HOST_INTERVAL);
+            slot - now
+        };
+
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+fn backoff_delay(attempt: u32) -> Duration {
+    BASE_BACKOFF
+        .saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+// Only the delay-seconds form is used by the ModDB; dates fall back to backoff
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(|secs| Duration::from_secs(secs).min(MAX_BACKOFF))
+}
+
+fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+fn header_string(
+    response: &reqwest::Response,
+    name: reqwest::header::HeaderName,
+) -> Option<String> {
+    response
+        .headers()
+        .get(name)?
+        .to_str()
+        .ok()
+        .map(|v| v.to_string())
+}
+
+// Cached entries younger than the TTL are used as-is; offline mode uses any entry
+fn usable_without_network(cached: &CachedResponse, offline: bool, ttl_secs: u64) -> bool {
+    offline || cached.age_secs() < ttl_secs
+}
+
+// GET `url` under the request policy and return the body text
+pub(crate) async fn get_text(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<(String, ResponseMeta), String> {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+    let ttl_secs = settings
+        .api_cache_ttl_minutes
+        .unwrap_or(DEFAULT_CACHE_TTL_MINUTES)
+        .saturating_mul(60);
+    let cached = response_cache::load(url);
+
+    match &cached {
+        Some(cached) if usable_without_network(cached, settings.offline_mode, ttl_secs) => {
+            let meta = ResponseMeta {
+                outcome: FetchOutcome::Cached,
+                age_secs: cached.age_secs(),
+            };
+            return Ok((cached.body.clone(), meta));
+        }
+        None if settings.offline_mode => {
+            return Err(format!("Offline mode: no cached response for {}", url));
+        }
+        _ => {}
+    }
+
+    match fetch_with_retries(client, url, cached.as_ref()).await {
+        Ok((
+            Fetched::Body {
+                text,
+                etag,
+                last_modified,
+            },
+            outcome,
+        )) => {
+            let entry = CachedResponse {
+                url: url.to_string(),
+                body: text,
+                etag,
+                last_modified,
+                fetched_at: response_cache::now_secs(),
+            };
+            if let Err(e) = response_cache::save(&entry) {
+                eprintln!("[request_policy] {}", e);
+            }
+            Ok((
+                entry.body,
+                ResponseMeta {
+                    outcome,
+                    age_secs: 0,
+                },
+            ))
+        }
+        Ok((Fetched::NotModified, outcome)) => {
+            // Validators are only sent when an entry exists
+            let mut entry = cached.ok_or("Server returned 304 without a cached response")?;
+            entry.fetched_at = response_cache::now_secs();
+            if let Err(e) = response_cache::save(&entry) {
+                eprintln!("[request_policy] {}", e);
+            }
+            Ok((
+                entry.body,
+                ResponseMeta {
+                    outcome,
+                    age_secs: 0,
+                },
+            ))
+        }
+        Err(e) => match cached {
+            // Stale data, clearly marked, beats no data
+            Some(entry) => {
+                eprintln!(
+                    "[request_policy] {}; serving cached response from {}s ago",
+                    e,
+                    entry.age_secs()
+                );
+                let meta = ResponseMeta {
+                    outcome: FetchOutcome::Cached,
+                    age_secs: entry.age_secs(),
+                };
+                Ok((entry.body, meta))
+            }
+            None => Err(e),
+        },
+    }
+}
+
+async fn fetch_with_retries(
+    client: &reqwest::Client,
+    url: &str,
+    cached: Option<&CachedResponse>,
+) -> Result<(Fetched, FetchOutcome), String> {
+    let policy = policy();
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(|h| h.to_string()))
+        .unwrap_or_default();
+
+    let mut attempt = 0;
+    loop {
+        let result = {
+            let _permit = policy
+                .permits
+                .acquire()
+                .await
+                .map_err(|e| format!("Request queue closed: {}", e))?;
+            policy.wait_for_host_slot(&host).await;
+
+            let mut request = client.get(url).header(
+                "User-Agent",
+                "Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36",
+            );
+            if let Some(cached) = cached {
+                if let Some(etag) = &cached.etag {
+                    request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &cached.last_modified {
+                    request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+
+            match request.send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    if status == reqwest::StatusCode::NOT_MODIFIED && cached.is_some() {
+                        Ok(Fetched::NotModified)
+                    } else if status.is_success() {
+                        let etag = header_string(&response, reqwest::header::ETAG);
+                        let last_modified =
+                            header_string(&response, reqwest::header::LAST_MODIFIED);
+                        response
+                            .text()
+                            .await
+                            .map(|text| Fetched::Body {
+                                text,
+                                etag,
+                                last_modified,
+                            })
+                            .map_err(|e| {
+                                (
+                                    format!("Failed to read response: {}", e),
+                                    Some(backoff_delay(attempt)),
+                                )
+                            })
+                    } else if is_retryable_status(status) {
+                        let delay = retry_after(&response).unwrap_or(backoff_delay(attempt));
+                        Err((format!("Server returned status: {}", status), Some(delay)))
+                    } else {
+                        return Err(format!("Server returned status: {}", status));
+                    }
+                }
+                Err(e) if is_retryable_error(&e) => Err((
+                    format!("Request failed: {}", e),
+                    Some(backoff_delay(attempt)),
+                )),
+                Err(e) => return Err(format!("Request failed: {}", e)),
+            }
+        };
+
+        match result {
+            Ok(fetched) => {
+                let outcome = if attempt == 0 {
+                    FetchOutcome::Fresh
+                } else {
+                    FetchOutcome::Retried
+                };
+                return Ok((fetched, outcome));
+            }
+            Err((error, Some(delay))) if attempt < MAX_RETRIES => {
+                attempt += 1;
+                eprintln!(
+                    "[request_policy] {} for {}, retry {}/{} in {:?}",
+                    error, url, attempt, MAX_RETRIES, delay
+                );
+                // The permit is released while waiting so other requests can proceed
+                tokio::time::sleep(delay).await;
+            }
+            Err((error, _)) => {
+                return Err(if attempt > 0 {
+                    format!("{} (after {} retries)", error, attempt)
+                } else {
+                    error
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_delay_grows_and_caps() {
+        assert_eq!(backoff_delay(0), BASE_BACKOFF);
+        assert_eq!(backoff_delay(1), BASE_BACKOFF * 2);
+        assert_eq!(backoff_delay(2), BASE_BACKOFF * 4);
+        assert_eq!(backoff_delay(20), MAX_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn test_host_slots_are_spaced() {
+        let policy = RequestPolicy {
+            permits: Semaphore::new(1),
+            next_slot: Mutex::new(HashMap::new()),
+        };
+
+        let start = Instant::now();
+        policy.wait_for_host_slot("example.com").await;
+        policy.wait_for_host_slot("example.com").await;
+        assert!(start.elapsed() >= MIN_HOST_INTERVAL);
+
+        // Other hosts are not held back
+        let other = Instant::now();
+        policy.wait_for_host_slot("example.org").await;
+        assert!(other.elapsed() < MIN_HOST_INTERVAL);
+    }
+
+    #[test]
+    fn test_cache_usable_without_network() {
+        let cached = CachedResponse {
+            url: "http://mods.vintagestory.at/api/mods".to_string(),
+            body: String::new(),
+            etag: None,
+            last_modified: None,
+            fetched_at: response_cache::now_secs() - 600,
+        };
+
+        assert!(usable_without_network(&cached, false, 3600));
+        assert!(!usable_without_network(&cached, false, 60));
+        // Offline mode serves entries of any age
+        assert!(usable_without_network(&cached, true, 60));
+        assert!(!usable_without_network(&cached, false, 0));
+    }
+}
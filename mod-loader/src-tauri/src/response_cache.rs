@@ -0,0 +1,146 @@
+// On-disk cache of ModDB API responses. Entries keep the ETag and
+// Last-Modified validators so stale entries can be revalidated cheaply, and
+// serve as the only data source when offline mode is on.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tauri::command;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct CachedResponse {
+    pub url: String,
+    pub body: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub fetched_at: u64, // Unix seconds of the last successful fetch or revalidation
+}
+
+impl CachedResponse {
+    pub(crate) fn age_secs(&self) -> u64 {
+        now_secs().saturating_sub(self.fetched_at)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResponseCacheInfo {
+    pub path: String,
+    pub entry_count: usize,
+    pub total_bytes: u64,
+}
+
+pub(crate) fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn get_cache_dir() -> Result<PathBuf, String> {
+    let cache_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader")
+        .join("response-cache");
+    std::fs::create_dir_all(&cache_dir)
+        .map_err(|e| format!("Failed to create response cache directory: {}", e))?;
+    Ok(cache_dir)
+}
+
+fn entry_path(cache_dir: &Path, url: &str) -> PathBuf {
+    let digest = hex::encode(Sha256::digest(url.as_bytes()));
+    cache_dir.join(format!("{}.json", &digest[..32]))
+}
+
+fn load_from(cache_dir: &Path, url: &str) -> Option<CachedResponse> {
+    let content = std::fs::read_to_string(entry_path(cache_dir, url)).ok()?;
+    match serde_json::from_str::<CachedResponse>(&content) {
+        // Guard against digest prefix collisions
+        Ok(cached) if cached.url == url => Some(cached),
+        Ok(_) => None,
+        Err(e) => {
+            eprintln!("Failed to parse cached response for {}: {}", url, e);
+            None
+        }
+    }
+}
+
+fn save_to(cache_dir: &Path, cached: &CachedResponse) -> Result<(), String> {
+    let content = serde_json::to_string(cached)
+        .map_err(|e| format!("Failed to serialize cached response: {}", e))?;
+    std::fs::write(entry_path(cache_dir, &cached.url), content)
+        .map_err(|e| format!("Failed to write cached response: {}", e))?;
+    Ok(())
+}
+
+pub(crate) fn load(url: &str) -> Option<CachedResponse> {
+    load_from(&get_cache_dir().ok()?, url)
+}
+
+pub(crate) fn save(cached: &CachedResponse) -> Result<(), String> {
+    save_to(&get_cache_dir()?, cached)
+}
+
+#[command]
+pub async fn get_response_cache_info() -> Result<ResponseCacheInfo, String> {
+    let cache_dir = get_cache_dir()?;
+    let mut entry_count = 0;
+    let mut total_bytes = 0;
+
+    let entries = std::fs::read_dir(&cache_dir)
+        .map_err(|e| format!("Failed to read response cache: {}", e))?;
+    for entry in entries.flatten() {
+        if let Ok(metadata) = entry.metadata() {
+            entry_count += 1;
+            total_bytes += metadata.len();
+        }
+    }
+
+    Ok(ResponseCacheInfo {
+        path: cache_dir.to_string_lossy().to_string(),
+        entry_count,
+        total_bytes,
+    })
+}
+
+#[command]
+pub async fn clear_response_cache() -> Result<usize, String> {
+    let cache_dir = get_cache_dir()?;
+    let mut removed = 0;
+
+    let entries = std::fs::read_dir(&cache_dir)
+        .map_err(|e| format!("Failed to read response cache: {}", e))?;
+    for entry in entries.flatten() {
+        match std::fs::remove_file(entry.path()) {
+            Ok(()) => removed += 1,
+            Err(e) => eprintln!("Failed to remove {}: {}", entry.path().display(), e),
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_response_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cached = CachedResponse {
+            url: "http://mods.vintagestory.at/api/mod/example".to_string(),
+            body: "{\"mod\":{}}".to_string(),
+            etag: Some("\"abc\"".to_string()),
+            last_modified: None,
+            fetched_at: now_secs() - 120,
+        };
+
+        save_to(dir.path(), &cached).unwrap();
+        let loaded = load_from(dir.path(), &cached.url).unwrap();
+        assert_eq!(loaded.body, cached.body);
+        assert_eq!(loaded.etag, cached.etag);
+        assert!(loaded.age_secs() >= 120);
+
+        assert!(load_from(dir.path(), "http://mods.vintagestory.at/api/mod/other").is_none());
+    }
+}
@@ -0,0 +1,140 @@
+// Last known status of every mod, kept on disk so the UI can show it at
+// startup before any request is made. An entry is reused until it is older
+// than the caller allows, or the mod or the game version has changed since.
+
+use crate::mod_status::ModStatus;
+use crate::request_policy::FetchOutcome;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct CachedStatus {
+    pub installed_version: String,
+    pub game_version: Option<String>,
+    pub status: ModStatus,
+}
+
+impl CachedStatus {
+    // Whether the entry can stand in for a new check
+    pub(crate) fn is_fresh(
+        &self,
+        installed_version: &str,
+        game_version: Option<&str>,
+        max_age_secs: u64,
+        now: u64,
+    ) -> bool {
+        // Failed checks are retried every time
+        self.status.fetch_outcome != FetchOutcome::Failed
+            && self.installed_version == installed_version
+            && self.game_version.as_deref() == game_version
+            && self
+                .status
+                .checked_at
+                .is_some_and(|checked_at| now.saturating_sub(checked_at) <= max_age_secs)
+    }
+}
+
+// Entries per mods folder, then per modid
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct StatusCacheFile {
+    folders: HashMap<String, HashMap<String, CachedStatus>>,
+}
+
+static STATUS_CACHE_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_status_cache_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_dir.join("status-cache.json"))
+}
+
+fn load_file() -> StatusCacheFile {
+    let path = match get_status_cache_path() {
+        Ok(path) if path.exists() => path,
+        _ => return StatusCacheFile::default(),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse status cache: {}", e);
+            StatusCacheFile::default()
+        }),
+        Err(e) => {
+            eprintln!("Failed to read status cache: {}", e);
+            StatusCacheFile::default()
+        }
+    }
+}
+
+pub(crate) fn load(mods_path: &str) -> HashMap<String, CachedStatus> {
+    let _guard = STATUS_CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    load_file().folders.remove(mods_path).unwrap_or_default()
+}
+
+// Record new results for `mods_path`. With `installed` set, entries for mods
+// no longer in the folder are dropped.
+pub(crate) fn store(
+    mods_path: &str,
+    entries: HashMap<String, CachedStatus>,
+    installed: Option<&[String]>,
+) -> Result<(), String> {
+    let _guard = STATUS_CACHE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = load_file();
+    let folder = file.folders.entry(mods_path.to_string()).or_default();
+    folder.extend(entries);
+    if let Some(installed) = installed {
+        folder.retain(|mod_id, _| installed.contains(mod_id));
+    }
+
+    let content = serde_json::to_string(&file)
+        .map_err(|e| format!("Failed to serialize status cache: {}", e))?;
+    std::fs::write(get_status_cache_path()?, content)
+        .map_err(|e| format!("Failed to write status cache: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cached_status_freshness() {
+        let status: ModStatus = serde_json::from_value(serde_json::json!({
+            "hasUpdate": false,
+            "latestVersion": "1.2.0",
+            "missingDependencies": [],
+            "outdatedDependencies": [],
+            "compatibilityNote": null,
+            "fetchOutcome": "fresh",
+            "fetchError": null,
+            "responseAgeSecs": 0,
+            "changelogs": [],
+            "gameVersion": "1.20.0",
+            "requiredGameVersion": null,
+            "meetsGameRequirement": null,
+            "installedReleaseTags": [],
+            "taggedCompatible": null,
+            "checkedAt": 1000
+        }))
+        .unwrap();
+        let mut cached = CachedStatus {
+            installed_version: "1.2.0".to_string(),
+            game_version: Some("1.20.0".to_string()),
+            status,
+        };
+
+        assert!(cached.is_fresh("1.2.0", Some("1.20.0"), 600, 1500));
+        assert!(!cached.is_fresh("1.2.0", Some("1.20.0"), 600, 1700));
+        // Updating the mod or the game invalidates the entry
+        assert!(!cached.is_fresh("1.3.0", Some("1.20.0"), 600, 1500));
+        assert!(!cached.is_fresh("1.2.0", Some("1.21.0"), 600, 1500));
+
+        cached.status.fetch_outcome = FetchOutcome::Failed;
+        assert!(!cached.is_fresh("1.2.0", Some("1.20.0"), 600, 1500));
+    }
+}
@@ -0,0 +1,853 @@
+// Updating every enabled mod in one go. `update_all` first returns a plan of
+// what would change; running it installs that plan as one batch and keeps
+// the files it replaced, so `rollback_update` can put back the exact set of
+// mods that was there before. A batch is all or nothing: when any install
+// fails, the ones that succeeded are undone.
+
+use crate::api_client::ModRelease;
+use crate::dependency_resolver::{self, DependencyPlan, PlanAction, SourceReleaseProvider};
+use crate::mod_manager::Mod;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::command;
+
+const BATCH_FILE_NAME: &str = "batch.json";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedUpdate {
+    pub mod_id: String,
+    pub name: String,
+    pub from_version: String,
+    pub to_version: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePlan {
+    pub game_version: Option<String>,
+    pub updates: Vec<PlannedUpdate>,
+    // Updates left out because the mod is held at its version
+    pub held: Vec<PlannedUpdate>,
+    // Dependencies the new releases need that are missing or too old
+    pub dependencies: DependencyPlan,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum BatchAction {
+    Update,
+    Install, // A dependency that was not installed before
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEntry {
+    pub mod_id: String,
+    pub action: BatchAction,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub original_path: Option<String>, // Where the replaced mod was
+    pub backup_path: Option<String>,   // Its copy in the batch folder
+    pub installed_path: String,
+    // SHA-256 of the installed file, to tell whether it was replaced since
+    #[serde(default)]
+    pub installed_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateBatch {
+    pub id: String,
+    pub mods_path: String,
+    pub created_at: u64, // Unix seconds
+    pub entries: Vec<BatchEntry>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateAllResult {
+    pub plan: UpdatePlan,
+    pub batch: Option<UpdateBatch>, // Set when the plan was carried out
+}
+
+impl UpdatePlan {
+    fn summary(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .updates
+            .iter()
+            .map(|u| format!("{} -> {}", u.mod_id.to_lowercase(), u.to_version))
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    // An error when the plan would install something other than `previewed`
+    fn check_unchanged(&self, previewed: &UpdatePlan) -> Result<(), String> {
+        let (now, before) = (self.summary(), previewed.summary());
+        if now != before {
+            return Err(format!(
+                "The updates changed since they were previewed (now: {}; previewed: {}); review them again",
+                if now.is_empty() { "none".to_string() } else { now.join(", ") },
+                if before.is_empty() { "none".to_string() } else { before.join(", ") }
+            ));
+        }
+        self.dependencies.check_unchanged(&previewed.dependencies)
+    }
+
+    // Everything the plan installs: (modid, action, from version, to version)
+    fn installs(&self) -> Vec<(String, BatchAction, Option<String>, String)> {
+        let updates = self.updates.iter().map(|u| {
+            (
+                u.mod_id.clone(),
+                BatchAction::Update,
+                Some(u.from_version.clone()),
+                u.to_version.clone(),
+            )
+        });
+        let dependencies = self.dependencies.steps.iter().map(|step| {
+            let action = match step.action {
+                PlanAction::Upgrade => BatchAction::Update,
+                PlanAction::Install => BatchAction::Install,
+            };
+            (
+                step.mod_id.clone(),
+                action,
+                step.from_version.clone(),
+                step.to_version.clone(),
+            )
+        });
+        updates.chain(dependencies).collect()
+    }
+}
+
+fn get_backups_dir() -> Result<PathBuf, String> {
+    let backups_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader")
+        .join("update-backups");
+    std::fs::create_dir_all(&backups_dir)
+        .map_err(|e| format!("Failed to create update backup directory: {}", e))?;
+    Ok(backups_dir)
+}
+
+fn new_batch_id() -> String {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!("batch-{}", millis)
+}
+
+fn save_batch(batch_dir: &Path, batch: &UpdateBatch) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(batch)
+        .map_err(|e| format!("Failed to serialize update batch: {}", e))?;
+    std::fs::write(batch_dir.join(BATCH_FILE_NAME), content)
+        .map_err(|e| format!("Failed to write update batch: {}", e))
+}
+
+fn load_batches() -> Result<Vec<UpdateBatch>, String> {
+    let entries = std::fs::read_dir(get_backups_dir()?)
+        .map_err(|e| format!("Failed to read update backup directory: {}", e))?;
+
+    let mut batches: Vec<UpdateBatch> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let content = std::fs::read_to_string(e.path().join(BATCH_FILE_NAME)).ok()?;
+            serde_json::from_str(&content)
+                .map_err(|err| eprintln!("Failed to parse update batch {:?}: {}", e.path(), err))
+                .ok()
+        })
+        .collect();
+    batches.sort_by(|a, b| b.created_at.cmp(&a.created_at).then(b.id.cmp(&a.id)));
+    Ok(batches)
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> Result<(), String> {
+    if from.is_dir() {
+        std::fs::create_dir_all(to)
+            .map_err(|e| format!("Failed to create {}: {}", to.display(), e))?;
+        let entries = std::fs::read_dir(from)
+            .map_err(|e| format!("Failed to read {}: {}", from.display(), e))?;
+        for entry in entries.filter_map(|e| e.ok()) {
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        Ok(())
+    } else {
+        std::fs::copy(from, to)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))
+    }
+}
+
+fn remove_path(path: &Path) -> Result<(), String> {
+    let result = if path.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else if path.exists() {
+        std::fs::remove_file(path)
+    } else {
+        return Ok(());
+    };
+    result.map_err(|e| format!("Failed to remove {}: {}", path.display(), e))
+}
+
+// The release each outdated enabled mod would update to
+async fn find_updates(
+    mods_path: &str,
+    mod_list: &Arc<Vec<Mod>>,
+    game_version: Option<&str>,
+    warnings: &mut Vec<String>,
+) -> Vec<(PlannedUpdate, ModRelease)> {
+    let mod_ids = mod_list
+        .iter()
+        .filter(|m| m.enabled && m.info.is_some())
+        .map(|m| m.id.clone())
+        .collect();
+    let statuses = crate::mod_status::check_statuses(
+        mods_path.to_string(),
+        Arc::clone(mod_list),
+        mod_ids,
+        game_version.map(|v| v.to_string()),
+    )
+    .await;
+
+    let mut updates = Vec::new();
+    for mod_item in mod_list.iter().filter(|m| m.enabled) {
+        let (Some(status), Some(info)) = (statuses.get(&mod_item.id), &mod_item.info) else {
+            continue;
+        };
+        if let Some(error) = &status.fetch_error {
+            warnings.push(format!(
+                "{}: could not check for updates: {}",
+                mod_item.name, error
+            ));
+        }
+        if let Some(note) = &status.compatibility_note {
+            warnings.push(format!("{}: {}", mod_item.name, note));
+        }
+        let latest_version = match (&status.latest_version, status.has_update) {
+            (Some(latest_version), true) => latest_version,
+            _ => continue,
+        };
+
+        let release = crate::mod_source::releases_for_update_check(&mod_item.id)
+            .await
+            .and_then(|(releases, _)| {
+                crate::api_client::find_release_by_version(&mod_item.id, &releases, latest_version)
+                    .cloned()
+            });
+        match release {
+            Ok(release) => {
+                if game_version.is_some() && release.tags.is_empty() {
+                    warnings.push(format!(
+                        "{} {} does not say which game versions it supports",
+                        mod_item.name, latest_version
+                    ));
+                }
+                updates.push((
+                    PlannedUpdate {
+                        mod_id: mod_item.id.clone(),
+                        name: mod_item.name.clone(),
+                        from_version: info.version.clone(),
+                        to_version: latest_version.clone(),
+                    },
+                    release,
+                ));
+            }
+            Err(e) => warnings.push(format!("{}: {}", mod_item.name, e)),
+        }
+    }
+
+    updates.sort_by_key(|(update, _)| update.name.to_lowercase());
+    updates
+}
+
+async fn build_plan(mods_path: &str, mod_list: &Arc<Vec<Mod>>, override_holds: bool) -> UpdatePlan {
+    let game_version = crate::game_detector::current_game_version().await;
+    let mut warnings = Vec::new();
+    let holds = crate::holds::holds_for(mods_path);
+    let is_held = |mod_id: &str| !override_holds && holds.contains_key(&mod_id.to_lowercase());
+
+    let (held, updates): (Vec<_>, Vec<_>) =
+        find_updates(mods_path, mod_list, game_version.as_deref(), &mut warnings)
+            .await
+            .into_iter()
+            .partition(|(update, _)| is_held(&update.mod_id));
+
+    let releases: Vec<(String, ModRelease)> = updates
+        .iter()
+        .map(|(update, release)| (update.mod_id.clone(), release.clone()))
+        .collect();
+    let mut dependencies = dependency_resolver::plan_upgrade_dependencies(
+        &releases,
+        mod_list,
+        game_version.as_deref(),
+        &SourceReleaseProvider,
+    )
+    .await;
+
+    // A held dependency stays where it is, even if that leaves an update short
+    dependencies.steps.retain(|step| {
+        if step.action != PlanAction::Upgrade || !is_held(&step.mod_id) {
+            return true;
+        }
+        warnings.push(format!(
+            "{} is held at {}, but {} needs {}",
+            step.mod_id,
+            step.from_version.as_deref().unwrap_or("its version"),
+            step.required_by
+                .iter()
+                .map(|r| r.mod_id.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            step.to_version
+        ));
+        false
+    });
+
+    UpdatePlan {
+        game_version,
+        updates: updates.into_iter().map(|(update, _)| update).collect(),
+        held: held.into_iter().map(|(update, _)| update).collect(),
+        dependencies,
+        warnings,
+    }
+}
+
+// Install everything in the plan, backing up each mod before it is replaced
+async fn run_plan(
+    mods_path: &str,
+    mod_list: &[Mod],
+    plan: &UpdatePlan,
+    override_holds: bool,
+) -> Result<UpdateBatch, String> {
+    use crate::download_queue::{self, DownloadRequest};
+
+    let batch_id = new_batch_id();
+    let batch_dir = get_backups_dir()?.join(&batch_id);
+    let entries = install_batch(&batch_dir, mod_list, plan, |mod_id, version| {
+        // Everything is queued before the first wait, so downloads run in parallel
+        let item = download_queue::enqueue(DownloadRequest {
+            source: crate::mod_source::registered_source(mod_id),
+            mod_id: mod_id.to_string(),
+            version: Some(version.to_string()),
+            download_url: None,
+            mods_path: mods_path.to_string(),
+            expected_hash: None,
+            override_hold: override_holds,
+        });
+        async move { download_queue::wait_for(&item.id).await }
+    })
+    .await?;
+
+    for entry in &entries {
+        if let Err(e) =
+            crate::mod_manager::reindex_mod(mods_path.to_string(), entry.mod_id.clone()).await
+        {
+            eprintln!("Failed to reindex {}: {}", entry.mod_id, e);
+        }
+    }
+
+    let batch = UpdateBatch {
+        id: batch_id,
+        mods_path: mods_path.to_string(),
+        created_at: crate::response_cache::now_secs(),
+        entries,
+    };
+    save_batch(&batch_dir, &batch)?;
+    Ok(batch)
+}
+
+// Back up what the plan replaces into `batch_dir`, then install every mod
+// with `install(modid, version)`, which returns the installed path. If any
+// install fails the others are undone, the backup is removed and the error
+// names every failure.
+async fn install_batch<F, Fut>(
+    batch_dir: &Path,
+    mod_list: &[Mod],
+    plan: &UpdatePlan,
+    mut install: F,
+) -> Result<Vec<BatchEntry>, String>
+where
+    F: FnMut(&str, &str) -> Fut,
+    Fut: std::future::Future<Output = Result<String, String>>,
+{
+    std::fs::create_dir_all(batch_dir)
+        .map_err(|e| format!("Failed to create update backup directory: {}", e))?;
+    let discard_backup = || {
+        if let Err(e) = std::fs::remove_dir_all(batch_dir) {
+            eprintln!(
+                "Failed to remove update backup {}: {}",
+                batch_dir.display(),
+                e
+            );
+        }
+    };
+
+    let installed: HashMap<String, &Mod> =
+        mod_list.iter().map(|m| (m.id.to_lowercase(), m)).collect();
+
+    let mut entries = Vec::new();
+    for (mod_id, action, from_version, to_version) in plan.installs() {
+        let original = installed.get(&mod_id.to_lowercase());
+        let backup_path = match original {
+            Some(original) => {
+                let original_path = Path::new(&original.path);
+                let file_name = original_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| mod_id.clone());
+                let backup_path = batch_dir.join(file_name);
+                if let Err(e) = copy_recursive(original_path, &backup_path) {
+                    discard_backup();
+                    return Err(format!("Failed to back up {}: {}", mod_id, e));
+                }
+                Some(backup_path.to_string_lossy().to_string())
+            }
+            None => None,
+        };
+        entries.push(BatchEntry {
+            mod_id,
+            action,
+            from_version,
+            to_version,
+            original_path: original.map(|m| m.path.clone()),
+            backup_path,
+            installed_path: String::new(),
+            installed_hash: None,
+        });
+    }
+
+    let pending: Vec<Fut> = entries
+        .iter()
+        .map(|entry| install(&entry.mod_id, &entry.to_version))
+        .collect();
+    let mut installed_entries = Vec::new();
+    let mut failures = Vec::new();
+    for (mut entry, result) in entries.into_iter().zip(pending) {
+        match result.await {
+            Ok(path) => {
+                entry.installed_hash = crate::mod_manager::hash_file(Path::new(&path)).ok();
+                entry.installed_path = path;
+                installed_entries.push(entry);
+            }
+            Err(e) => failures.push(format!("{}: {}", entry.mod_id, e)),
+        }
+    }
+
+    if !failures.is_empty() {
+        let mut message = format!(
+            "Nothing was updated because some mods could not be installed ({})",
+            failures.join("; ")
+        );
+        let errors = restore_entries(&installed_entries);
+        if errors.is_empty() {
+            discard_backup();
+        } else {
+            message.push_str(&format!(
+                "; undoing the other installs failed, their backups are kept in {} ({})",
+                batch_dir.display(),
+                errors.join("; ")
+            ));
+        }
+        return Err(message);
+    }
+
+    // A mod installed under another file name would otherwise be loaded
+    // twice; the backup keeps it for a rollback
+    for entry in &installed_entries {
+        if let Some(original_path) = &entry.original_path {
+            if Path::new(original_path) != Path::new(&entry.installed_path) {
+                if let Err(e) = remove_path(Path::new(original_path)) {
+                    eprintln!("[update_all] {}", e);
+                }
+            }
+        }
+    }
+    Ok(installed_entries)
+}
+
+// Undo the entries of a batch, last installed first; returns what failed
+fn restore_entries(entries: &[BatchEntry]) -> Vec<String> {
+    let mut errors = Vec::new();
+    for entry in entries.iter().rev() {
+        let installed_path = Path::new(&entry.installed_path);
+        let restore = match (&entry.original_path, &entry.backup_path) {
+            (Some(original_path), Some(backup_path)) => Some((original_path, backup_path)),
+            _ => None,
+        };
+
+        let replaced_in_place =
+            restore.is_some_and(|(original_path, _)| Path::new(original_path) == installed_path);
+        if !replaced_in_place {
+            if let Err(e) = remove_path(installed_path) {
+                errors.push(format!("{}: {}", entry.mod_id, e));
+                continue;
+            }
+        }
+
+        if let Some((original_path, backup_path)) = restore {
+            let original_path = Path::new(original_path);
+            let result = remove_path(original_path)
+                .and_then(|_| copy_recursive(Path::new(backup_path), original_path));
+            if let Err(e) = result {
+                errors.push(format!("{}: {}", entry.mod_id, e));
+            }
+        }
+    }
+
+    crate::mod_manager::invalidate_scan();
+    errors
+}
+
+// Without `dry_run: false` this only returns the plan. Running it takes the
+// previewed `plan` and plans again first, refusing if the two differ, so
+// what gets installed is what the user saw. Held mods are left out unless
+// `override_holds` is set, and dependencies that cannot be installed stop
+// the update unless `allow_incomplete` is set.
+#[command]
+pub async fn update_all(
+    mods_path: String,
+    dry_run: Option<bool>,
+    override_holds: Option<bool>,
+    plan: Option<UpdatePlan>,
+    allow_incomplete: Option<bool>,
+) -> Result<UpdateAllResult, String> {
+    let mod_list = crate::mod_manager::scanned_mod_list(&mods_path)
+        .await
+        .map_err(|e| format!("Failed to load mod list: {}", e))?;
+    let previewed = plan;
+    let override_holds = override_holds.unwrap_or(false);
+    let plan = build_plan(&mods_path, &mod_list, override_holds).await;
+
+    if dry_run.unwrap_or(true) {
+        return Ok(UpdateAllResult { plan, batch: None });
+    }
+    let previewed = previewed.ok_or("Preview the update first and pass its plan to run it")?;
+    plan.check_unchanged(&previewed)?;
+    if plan.updates.is_empty() && plan.dependencies.steps.is_empty() {
+        return Ok(UpdateAllResult { plan, batch: None });
+    }
+    if !allow_incomplete.unwrap_or(false) {
+        plan.dependencies.check_complete()?;
+    }
+
+    let batch = run_plan(&mods_path, &mod_list, &plan, override_holds).await?;
+    Ok(UpdateAllResult {
+        plan,
+        batch: Some(batch),
+    })
+}
+
+// Batches that can still be rolled back, newest first
+#[command]
+pub async fn get_update_batches() -> Result<Vec<UpdateBatch>, String> {
+    load_batches()
+}
+
+// An error when rolling back `batch` would overwrite something newer: a later
+// batch in `newer` that changed the same mods, or installed files that were
+// replaced or removed since the batch put them there
+fn check_rollback(batch: &UpdateBatch, newer: &[UpdateBatch]) -> Result<(), String> {
+    let touches = |other: &UpdateBatch, entry: &BatchEntry| {
+        other
+            .entries
+            .iter()
+            .any(|e| e.mod_id.eq_ignore_ascii_case(&entry.mod_id))
+    };
+    let later: Vec<&str> = newer
+        .iter()
+        .filter(|other| {
+            other.mods_path == batch.mods_path
+                && batch.entries.iter().any(|entry| touches(other, entry))
+        })
+        .map(|other| other.id.as_str())
+        .collect();
+    if !later.is_empty() {
+        return Err(format!(
+            "Update {} cannot be rolled back while a later update of the same mods is kept; roll back {} first",
+            batch.id,
+            later.join(", ")
+        ));
+    }
+
+    let changed: Vec<&str> = batch
+        .entries
+        .iter()
+        .filter(|entry| {
+            // Batches saved before hashes were recorded can only be checked for presence
+            let installed_path = Path::new(&entry.installed_path);
+            !installed_path.exists()
+                || entry.installed_hash.as_ref().is_some_and(|hash| {
+                    crate::mod_manager::hash_file(installed_path).ok().as_ref() != Some(hash)
+                })
+        })
+        .map(|entry| entry.mod_id.as_str())
+        .collect();
+    if !changed.is_empty() {
+        return Err(format!(
+            "Update {} cannot be rolled back because these mods were changed or removed since: {}",
+            batch.id,
+            changed.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+// Put back the mods a batch replaced and remove the ones it newly installed.
+// Without `batch_id` the latest batch is rolled back. Only a batch that no
+// later batch and no manual change has built on can be rolled back.
+#[command]
+pub async fn rollback_update(batch_id: Option<String>) -> Result<UpdateBatch, String> {
+    let mut batches = load_batches()?;
+    let position = match &batch_id {
+        Some(id) => batches.iter().position(|b| &b.id == id),
+        None => (!batches.is_empty()).then_some(0),
+    }
+    .ok_or_else(|| match &batch_id {
+        Some(id) => format!("Update batch {} not found", id),
+        None => "There is no update to roll back".to_string(),
+    })?;
+
+    // Batches are sorted newest first
+    check_rollback(&batches[position], &batches[..position])?;
+    let batch = batches.swap_remove(position);
+
+    let errors = restore_entries(&batch.entries);
+    if !errors.is_empty() {
+        return Err(format!(
+            "Failed to roll back update {}: {}",
+            batch.id,
+            errors.join("; ")
+        ));
+    }
+
+    // The restored files are indexed again by the next scan
+    let batch_dir = get_backups_dir()?.join(&batch.id);
+    if let Err(e) = std::fs::remove_dir_all(&batch_dir) {
+        eprintln!("Failed to remove update backup {}: {}", batch.id, e);
+    }
+    Ok(batch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_restore_entries() {
+        let mods_dir = TempDir::new().unwrap();
+        let backup_dir = TempDir::new().unwrap();
+        let path = |dir: &TempDir, name: &str| dir.path().join(name).to_string_lossy().to_string();
+
+        // Updated in place, updated under a new file name, and newly installed
+        std::fs::write(mods_dir.path().join("inplace.zip"), "new").unwrap();
+        std::fs::write(backup_dir.path().join("inplace.zip"), "old").unwrap();
+        std::fs::write(mods_dir.path().join("renamed.zip"), "new").unwrap();
+        std::fs::write(backup_dir.path().join("Renamed_v1.0.0.zip"), "old").unwrap();
+        std::fs::write(mods_dir.path().join("fresh.zip"), "new").unwrap();
+
+        let entry = |mod_id: &str, original: Option<&str>, installed: &str| BatchEntry {
+            mod_id: mod_id.to_string(),
+            action: if original.is_some() {
+                BatchAction::Update
+            } else {
+                BatchAction::Install
+            },
+            from_version: original.map(|_| "1.0.0".to_string()),
+            to_version: "2.0.0".to_string(),
+            original_path: original.map(|name| path(&mods_dir, name)),
+            backup_path: original.map(|name| path(&backup_dir, name)),
+            installed_path: path(&mods_dir, installed),
+            installed_hash: None,
+        };
+        let entries = vec![
+            entry("inplace", Some("inplace.zip"), "inplace.zip"),
+            entry("renamed", Some("Renamed_v1.0.0.zip"), "renamed.zip"),
+            entry("fresh", None, "fresh.zip"),
+        ];
+
+        assert!(restore_entries(&entries).is_empty());
+
+        let read = |name: &str| std::fs::read_to_string(mods_dir.path().join(name)).ok();
+        assert_eq!(read("inplace.zip").as_deref(), Some("old"));
+        assert_eq!(read("Renamed_v1.0.0.zip").as_deref(), Some("old"));
+        assert_eq!(read("renamed.zip"), None);
+        assert_eq!(read("fresh.zip"), None);
+    }
+
+    // Two installed mods to update, one under a versioned file name, and a
+    // new dependency; returns the mods folder, the mod list and the plan
+    fn batch_fixture() -> (TempDir, Vec<Mod>, UpdatePlan) {
+        use crate::dependency_resolver::PlanStep;
+
+        let mods_dir = TempDir::new().unwrap();
+        let mut mod_list = Vec::new();
+        for (mod_id, file_name) in [
+            ("inplace", "inplace.zip"),
+            ("renamed", "Renamed_v1.0.0.zip"),
+        ] {
+            let path = mods_dir.path().join(file_name);
+            std::fs::write(&path, "old").unwrap();
+            let mut installed =
+                crate::mod_manager::test_mod(mod_id, "1.0.0", true, serde_json::json!({}));
+            installed.path = path.to_string_lossy().to_string();
+            mod_list.push(installed);
+        }
+
+        let update = |mod_id: &str| PlannedUpdate {
+            mod_id: mod_id.to_string(),
+            name: mod_id.to_string(),
+            from_version: "1.0.0".to_string(),
+            to_version: "2.0.0".to_string(),
+        };
+        let plan = UpdatePlan {
+            game_version: None,
+            updates: vec![update("inplace"), update("renamed")],
+            held: Vec::new(),
+            dependencies: DependencyPlan {
+                steps: vec![PlanStep {
+                    mod_id: "fresh".to_string(),
+                    action: PlanAction::Install,
+                    from_version: None,
+                    to_version: "1.0.0".to_string(),
+                    required_by: Vec::new(),
+                }],
+                ..Default::default()
+            },
+            warnings: Vec::new(),
+        };
+        (mods_dir, mod_list, plan)
+    }
+
+    // Installs `<modid>.zip` holding "new", failing for `fail`
+    fn fake_install<'a>(
+        mods_dir: &'a Path,
+        fail: Option<&'static str>,
+    ) -> impl FnMut(&str, &str) -> std::future::Ready<Result<String, String>> + 'a {
+        move |mod_id, _version| {
+            let result = if Some(mod_id) == fail {
+                Err("download failed".to_string())
+            } else {
+                let path = mods_dir.join(format!("{}.zip", mod_id));
+                std::fs::write(&path, "new").unwrap();
+                Ok(path.to_string_lossy().to_string())
+            };
+            std::future::ready(result)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_install_batch() {
+        let (mods_dir, mod_list, plan) = batch_fixture();
+        let backup_dir = TempDir::new().unwrap();
+        let batch_dir = backup_dir.path().join("batch");
+
+        let entries = install_batch(
+            &batch_dir,
+            &mod_list,
+            &plan,
+            fake_install(mods_dir.path(), None),
+        )
+        .await
+        .unwrap();
+
+        let ids: Vec<&str> = entries.iter().map(|e| e.mod_id.as_str()).collect();
+        assert_eq!(ids, vec!["inplace", "renamed", "fresh"]);
+        assert_eq!(entries[2].action, BatchAction::Install);
+        let read = |name: &str| std::fs::read_to_string(mods_dir.path().join(name)).ok();
+        assert_eq!(read("inplace.zip").as_deref(), Some("new"));
+        assert_eq!(read("renamed.zip").as_deref(), Some("new"));
+        assert_eq!(read("Renamed_v1.0.0.zip"), None);
+        assert_eq!(read("fresh.zip").as_deref(), Some("new"));
+        assert_eq!(
+            std::fs::read_to_string(batch_dir.join("Renamed_v1.0.0.zip")).unwrap(),
+            "old"
+        );
+
+        // The backup is enough to undo it all
+        assert!(restore_entries(&entries).is_empty());
+        assert_eq!(read("inplace.zip").as_deref(), Some("old"));
+        assert_eq!(read("Renamed_v1.0.0.zip").as_deref(), Some("old"));
+        assert_eq!(read("fresh.zip"), None);
+    }
+
+    #[tokio::test]
+    async fn test_install_batch_rolls_back_on_failure() {
+        let (mods_dir, mod_list, plan) = batch_fixture();
+        let backup_dir = TempDir::new().unwrap();
+        let batch_dir = backup_dir.path().join("batch");
+
+        let err = install_batch(
+            &batch_dir,
+            &mod_list,
+            &plan,
+            fake_install(mods_dir.path(), Some("renamed")),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.contains("renamed: download failed"), "{}", err);
+
+        let read = |name: &str| std::fs::read_to_string(mods_dir.path().join(name)).ok();
+        assert_eq!(read("inplace.zip").as_deref(), Some("old"));
+        assert_eq!(read("Renamed_v1.0.0.zip").as_deref(), Some("old"));
+        assert_eq!(read("renamed.zip"), None);
+        assert_eq!(read("fresh.zip"), None);
+        assert!(!batch_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_only_undoes_unchanged_latest_batch() {
+        let (mods_dir, mod_list, plan) = batch_fixture();
+        let backup_dir = TempDir::new().unwrap();
+        let entries = install_batch(
+            &backup_dir.path().join("batch"),
+            &mod_list,
+            &plan,
+            fake_install(mods_dir.path(), None),
+        )
+        .await
+        .unwrap();
+        let batch = |id: &str, entries: Vec<BatchEntry>| UpdateBatch {
+            id: id.to_string(),
+            mods_path: mods_dir.path().to_string_lossy().to_string(),
+            created_at: 0,
+            entries,
+        };
+        let older = batch("batch-1", entries.clone());
+        assert!(check_rollback(&older, &[]).is_ok());
+
+        // A later batch that updated one of the same mods comes first
+        let newer = batch("batch-2", vec![entries[1].clone()]);
+        let err = check_rollback(&older, std::slice::from_ref(&newer)).unwrap_err();
+        assert!(err.contains("batch-2"), "{}", err);
+        let unrelated = batch("batch-3", Vec::new());
+        assert!(check_rollback(&older, &[unrelated]).is_ok());
+
+        // So does a mod that was replaced by hand
+        std::fs::write(mods_dir.path().join("fresh.zip"), "manual").unwrap();
+        let err = check_rollback(&older, &[]).unwrap_err();
+        assert!(err.ends_with("fresh"), "{}", err);
+        std::fs::remove_file(mods_dir.path().join("fresh.zip")).unwrap();
+        assert!(check_rollback(&older, &[]).is_err());
+    }
+
+    #[test]
+    fn test_update_plan_drift() {
+        let (_mods_dir, _mod_list, previewed) = batch_fixture();
+        assert!(previewed.check_unchanged(&previewed.clone()).is_ok());
+
+        let mut newer = previewed.clone();
+        newer.updates[0].to_version = "2.0.1".to_string();
+        let err = newer.check_unchanged(&previewed).unwrap_err();
+        assert!(err.contains("inplace -> 2.0.1"), "{}", err);
+
+        let mut fewer_dependencies = previewed.clone();
+        fewer_dependencies.dependencies.steps.clear();
+        assert!(fewer_dependencies.check_unchanged(&previewed).is_err());
+    }
+}
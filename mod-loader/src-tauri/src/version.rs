@@ -0,0 +1,271 @@
+// Versions as Vintage Story uses them for mods and the game itself: dotted
+// numbers with an optional pre-release suffix, e.g. 1.2.3, 1.2.3-rc.1,
+// 1.2.3-pre.2 or v1.2. Missing components count as zero (1.2 == 1.2.0) and a
+// pre-release sorts before its release (1.2.3-rc.1 < 1.2.3).
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PreReleaseKind {
+    Dev,
+    Alpha,
+    Beta,
+    Pre,
+    Rc,
+}
+
+impl PreReleaseKind {
+    fn parse(label: &str) -> Option<Self> {
+        match label.to_ascii_lowercase().as_str() {
+            "dev" => Some(PreReleaseKind::Dev),
+            "alpha" | "a" => Some(PreReleaseKind::Alpha),
+            "beta" | "b" => Some(PreReleaseKind::Beta),
+            "pre" | "preview" => Some(PreReleaseKind::Pre),
+            "rc" => Some(PreReleaseKind::Rc),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            PreReleaseKind::Dev => "dev",
+            PreReleaseKind::Alpha => "alpha",
+            PreReleaseKind::Beta => "beta",
+            PreReleaseKind::Pre => "pre",
+            PreReleaseKind::Rc => "rc",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PreRelease {
+    pub kind: PreReleaseKind,
+    pub number: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub numbers: Vec<u64>,
+    pub pre: Option<PreRelease>,
+}
+
+impl Version {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let without_prefix = normalize(text);
+        let (numeric, suffix) = match without_prefix.split_once('-') {
+            Some((numeric, suffix)) => (numeric, Some(suffix)),
+            None => (without_prefix, None),
+        };
+
+        let numbers = numeric
+            .split('.')
+            .map(|part| part.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("Invalid version \"{}\"", text))?;
+        if numbers.is_empty() || numbers.len() > 4 {
+            return Err(format!("Invalid version \"{}\"", text));
+        }
+
+        let pre = match suffix {
+            Some(suffix) => Some(
+                Self::parse_pre_release(suffix)
+                    .ok_or_else(|| format!("Invalid pre-release in version \"{}\"", text))?,
+            ),
+            None => None,
+        };
+
+        Ok(Version { numbers, pre })
+    }
+
+    // "rc.1", "rc1" and "rc" (number 0) are all accepted
+    fn parse_pre_release(suffix: &str) -> Option<PreRelease> {
+        let split = suffix
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(suffix.len());
+        let (label, rest) = suffix.split_at(split);
+        let kind = PreReleaseKind::parse(label)?;
+        let rest = rest.strip_prefix('.').unwrap_or(rest);
+        let number = if rest.is_empty() {
+            0
+        } else {
+            rest.parse().ok()?
+        };
+        Some(PreRelease { kind, number })
+    }
+
+    fn number(&self, index: usize) -> u64 {
+        self.numbers.get(index).copied().unwrap_or(0)
+    }
+}
+
+impl FromStr for Version {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Version::parse(text)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let numbers: Vec<String> = self.numbers.iter().map(|n| n.to_string()).collect();
+        write!(f, "{}", numbers.join("."))?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}.{}", pre.kind.label(), pre.number)?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.numbers.len().max(other.numbers.len());
+        for i in 0..len {
+            match self.number(i).cmp(&other.number(i)) {
+                Ordering::Equal => {}
+                ordering => return ordering,
+            }
+        }
+
+        // A release sorts after all of its pre-releases
+        match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+// A version as written, without surrounding space or a leading v, for
+// showing and storing it: " v1.2.3 " becomes "1.2.3"
+pub(crate) fn normalize(text: &str) -> &str {
+    let trimmed = text.trim();
+    trimmed
+        .strip_prefix('v')
+        .or_else(|| trimmed.strip_prefix('V'))
+        .unwrap_or(trimmed)
+}
+
+// Order two version strings; versions that do not parse sort before those
+// that do, and among themselves by plain string order
+pub(crate) fn compare(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Greater,
+        (Err(_), Ok(_)) => Ordering::Less,
+        (Err(_), Err(_)) => a.trim().cmp(b.trim()),
+    }
+}
+
+// Whether two strings name the same version, e.g. "v1.2" and "1.2.0"
+pub(crate) fn same_version(a: &str, b: &str) -> bool {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => normalize(a) == normalize(b),
+    }
+}
+
+// Whether `candidate` is newer than `current`; None when either cannot be parsed
+pub(crate) fn is_newer(candidate: &str, current: &str) -> Option<bool> {
+    let candidate = Version::parse(candidate).ok()?;
+    let current = Version::parse(current).ok()?;
+    Some(candidate > current)
+}
+
+// The game's dependency semantics: the version in a modinfo dependency is the
+// minimum required, and an empty version or "*" accepts any. A version that
+// cannot be parsed only satisfies an identical requirement.
+pub(crate) fn satisfies_minimum(installed: &str, required: &str) -> bool {
+    let required = required.trim();
+    if required.is_empty() || required == "*" {
+        return true;
+    }
+    match (Version::parse(installed), Version::parse(required)) {
+        (Ok(installed), Ok(required)) => installed >= required,
+        _ => same_version(installed, required),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(text: &str) -> Version {
+        Version::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_parse_vs_versions() {
+        assert_eq!(v("1.2.3").numbers, vec![1, 2, 3]);
+        assert_eq!(v("v1.2").numbers, vec![1, 2]);
+        assert_eq!(
+            v("1.2.3-rc.1").pre,
+            Some(PreRelease {
+                kind: PreReleaseKind::Rc,
+                number: 1
+            })
+        );
+        assert_eq!(v("1.2.3-pre.2").to_string(), "1.2.3-pre.2");
+        assert_eq!(v("1.20.0-rc2").to_string(), "1.20.0-rc.2");
+
+        assert!(Version::parse("").is_err());
+        assert!(Version::parse("1.x").is_err());
+        assert!(Version::parse("1.2.3-banana").is_err());
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        assert!(v("1.10.0") > v("1.9.0"));
+        assert!(v("1.2.3-pre.2") < v("1.2.3-rc.1"));
+        assert!(v("1.2.3-rc.1") < v("1.2.3-rc.2"));
+        assert!(v("1.2.3-rc.2") < v("1.2.3"));
+        assert!(v("1.2.3") < v("1.2.4-pre.1"));
+        assert_eq!(v("v1.2"), v("1.2.0"));
+
+        let mut versions = vec!["1.10.0", "1.2.0-rc.1", "v1.9", "1.2.0"];
+        versions.sort_by(|a, b| compare(a, b));
+        assert_eq!(versions, vec!["1.2.0-rc.1", "1.2.0", "v1.9", "1.10.0"]);
+    }
+
+    #[test]
+    fn test_minimum_version_semantics() {
+        assert!(satisfies_minimum("1.10.0", "1.9.0"));
+        assert!(satisfies_minimum("1.9.0", "v1.9"));
+        assert!(!satisfies_minimum("1.9.0", "1.10.0"));
+        assert!(!satisfies_minimum("1.2.3-rc.1", "1.2.3"));
+        assert!(satisfies_minimum("0.1.0", ""));
+        assert!(satisfies_minimum("0.1.0", "*"));
+        assert!(satisfies_minimum("custom", "custom"));
+        assert!(!satisfies_minimum("custom", "1.0.0"));
+
+        assert_eq!(is_newer("1.10.0", "1.9.0"), Some(true));
+        assert_eq!(is_newer("1.2.0", "v1.2"), Some(false));
+        assert_eq!(is_newer("custom", "1.0.0"), None);
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(" v1.2.3 "), "1.2.3");
+        assert_eq!(normalize("V1.20.0-rc.1"), "1.20.0-rc.1");
+        assert_eq!(normalize("1.2"), "1.2");
+        assert_eq!(normalize("custom"), "custom");
+        assert!(same_version("vcustom", "custom"));
+    }
+}
@@ -0,0 +1,383 @@
+// Mods we are waiting on without having them installed, e.g. until they are
+// ported to our game version. A background task checks the ModDB on a
+// configurable interval and emits an event when a watched mod gets a new
+// release or its first release tagged for the current game version.
+
+use crate::api_client::ModRelease;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{command, AppHandle, Emitter};
+
+const DEFAULT_INTERVAL_MINUTES: u64 = 60;
+const MIN_INTERVAL_MINUTES: u64 = 5;
+// How often a disabled check looks at the setting again
+const DISABLED_POLL: Duration = Duration::from_secs(5 * 60);
+// How many watched mods are fetched from the ModDB at once
+const MAX_CONCURRENT_CHECKS: usize = 4;
+
+pub const NEW_RELEASE_EVENT: &str = "watchlist-new-release";
+pub const COMPATIBLE_RELEASE_EVENT: &str = "watchlist-compatible-release";
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SeenRelease {
+    pub version: Option<String>,
+    pub created: Option<String>,
+    pub game_versions: Vec<String>,
+}
+
+impl SeenRelease {
+    fn from_release(release: &ModRelease) -> Self {
+        SeenRelease {
+            version: release.modversion.clone(),
+            created: release.created.clone(),
+            game_versions: release.tags.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WatchedMod {
+    pub mod_id: String, // ModDB id: numeric or the modid string
+    pub name: Option<String>,
+    pub added_at: u64,             // Unix seconds
+    pub last_checked: Option<u64>, // Unix seconds of the last successful check
+    pub last_seen_release: Option<SeenRelease>,
+    // Newest release tagged for the game version at the last check
+    pub compatible_release: Option<SeenRelease>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct WatchEvent {
+    pub mod_id: String,
+    pub name: Option<String>,
+    pub release: SeenRelease,
+    pub game_version: Option<String>, // Set for compatible-release events
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+struct WatchlistFile {
+    mods: Vec<WatchedMod>,
+}
+
+static WATCHLIST_LOCK: Mutex<()> = Mutex::new(());
+
+fn get_watchlist_path() -> Result<PathBuf, String> {
+    let config_dir = dirs::config_dir()
+        .ok_or("Failed to get config directory")?
+        .join("vs-mod-loader");
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_dir.join("watchlist.json"))
+}
+
+fn load_watchlist() -> WatchlistFile {
+    let path = match get_watchlist_path() {
+        Ok(path) if path.exists() => path,
+        _ => return WatchlistFile::default(),
+    };
+
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("Failed to parse watchlist: {}", e);
+            WatchlistFile::default()
+        }),
+        Err(e) => {
+            eprintln!("Failed to read watchlist: {}", e);
+            WatchlistFile::default()
+        }
+    }
+}
+
+fn update_watchlist<T>(f: impl FnOnce(&mut WatchlistFile) -> T) -> Result<T, String> {
+    let _guard = WATCHLIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut file = load_watchlist();
+    let result = f(&mut file);
+    let content = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Failed to serialize watchlist: {}", e))?;
+    std::fs::write(get_watchlist_path()?, content)
+        .map_err(|e| format!("Failed to write watchlist: {}", e))?;
+    Ok(result)
+}
+
+fn same_mod(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+// Record the releases found by a check and return what is worth telling the
+// user. The first check of a mod only sets the baseline.
+fn apply_check(
+    watched: &mut WatchedMod,
+    releases: &[ModRelease],
+    game_version: Option<&str>,
+    now: u64,
+) -> (Option<WatchEvent>, Option<WatchEvent>) {
+    let mut newest_first: Vec<&ModRelease> = releases.iter().collect();
+    newest_first.sort_by(|a, b| b.created.cmp(&a.created));
+
+    let newest = newest_first
+        .first()
+        .map(|release| SeenRelease::from_release(release));
+    let compatible = game_version.and_then(|game_version| {
+        newest_first
+            .iter()
+            .find(|release| release.supports_game_version(game_version))
+            .map(|release| SeenRelease::from_release(release))
+    });
+
+    let has_baseline = watched.last_checked.is_some();
+    let event = |release: &SeenRelease, game_version: Option<&str>| WatchEvent {
+        mod_id: watched.mod_id.clone(),
+        name: watched.name.clone(),
+        release: release.clone(),
+        game_version: game_version.map(|v| v.to_string()),
+    };
+
+    let new_release = newest
+        .as_ref()
+        .filter(|release| has_baseline && watched.last_seen_release.as_ref() != Some(*release))
+        .map(|release| event(release, None));
+    let compatible_release = compatible
+        .as_ref()
+        .filter(|release| has_baseline && watched.compatible_release.as_ref() != Some(*release))
+        .map(|release| event(release, game_version));
+
+    watched.last_seen_release = newest;
+    watched.compatible_release = compatible;
+    watched.last_checked = Some(now);
+    watched.last_error = None;
+
+    (new_release, compatible_release)
+}
+
+// Check every watched mod and emit events for what changed
+async fn check_watchlist(app: Option<&AppHandle>) -> Result<Vec<WatchedMod>, String> {
+    let game_version = crate::game_detector::current_game_version().await;
+    let watched_ids: Vec<String> = {
+        let _guard = WATCHLIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load_watchlist()
+            .mods
+            .into_iter()
+            .map(|m| m.mod_id)
+            .collect()
+    };
+
+    let permits = Arc::new(tokio::sync::Semaphore::new(MAX_CONCURRENT_CHECKS));
+    let mut fetches = tokio::task::JoinSet::new();
+    for mod_id in watched_ids {
+        let permits = Arc::clone(&permits);
+        fetches.spawn(async move {
+            let _permit = permits.acquire_owned().await;
+            let fetched = crate::api_client::get_mod_details(mod_id.clone(), None).await;
+            (mod_id, fetched)
+        });
+    }
+
+    // Results are recorded as they come in, each under the watchlist lock
+    while let Some(joined) = fetches.join_next().await {
+        let (mod_id, fetched) = match joined {
+            Ok(result) => result,
+            Err(e) => {
+                eprintln!("[watchlist] Check task failed: {}", e);
+                continue;
+            }
+        };
+        let events = update_watchlist(|file| {
+            let watched = file
+                .mods
+                .iter_mut()
+                .find(|m| same_mod(&m.mod_id, &mod_id))?;
+            match &fetched {
+                Ok(details) => {
+                    watched.name = details.name.clone().or(watched.name.take());
+                    Some(apply_check(
+                        watched,
+                        &details.releases,
+                        game_version.as_deref(),
+                        crate::response_cache::now_secs(),
+                    ))
+                }
+                Err(e) => {
+                    eprintln!("[watchlist] Failed to check {}: {}", mod_id, e);
+                    watched.last_error = Some(e.clone());
+                    None
+                }
+            }
+        })?;
+
+        if let (Some(app), Some((new_release, compatible_release))) = (app, events) {
+            for (name, event) in [
+                (NEW_RELEASE_EVENT, new_release),
+                (COMPATIBLE_RELEASE_EVENT, compatible_release),
+            ] {
+                if let Some(event) = event {
+                    eprintln!(
+                        "[watchlist] {}: {} {}",
+                        name,
+                        event.mod_id,
+                        event.release.version.as_deref().unwrap_or("(no version)")
+                    );
+                    if let Err(e) = app.emit(name, event) {
+                        eprintln!("[watchlist] Failed to emit {}: {}", name, e);
+                    }
+                }
+            }
+        }
+    }
+
+    let _guard = WATCHLIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_watchlist().mods)
+}
+
+async fn interval() -> Option<Duration> {
+    let settings = crate::config::get_settings().await.unwrap_or_default();
+    match settings
+        .watchlist_interval_minutes
+        .unwrap_or(DEFAULT_INTERVAL_MINUTES)
+    {
+        0 => None,
+        minutes => Some(Duration::from_secs(minutes.max(MIN_INTERVAL_MINUTES) * 60)),
+    }
+}
+
+// Run the background check for the lifetime of the app
+pub(crate) fn start_background_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match interval().await {
+                Some(interval) => {
+                    if let Err(e) = check_watchlist(Some(&app)).await {
+                        eprintln!("[watchlist] Check failed: {}", e);
+                    }
+                    tokio::time::sleep(interval).await;
+                }
+                None => tokio::time::sleep(DISABLED_POLL).await,
+            }
+        }
+    });
+}
+
+#[command]
+pub async fn get_watchlist() -> Result<Vec<WatchedMod>, String> {
+    let _guard = WATCHLIST_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    Ok(load_watchlist().mods)
+}
+
+// Watch a ModDB mod; its current releases become the baseline for later events
+#[command]
+pub async fn add_to_watchlist(mod_id: String) -> Result<WatchedMod, String> {
+    let mod_id = mod_id.trim().to_string();
+    if mod_id.is_empty() {
+        return Err("Mod id must not be empty".to_string());
+    }
+
+    let details = crate::api_client::get_mod_details(mod_id.clone(), None).await?;
+    let game_version = crate::game_detector::current_game_version().await;
+    let now = crate::response_cache::now_secs();
+
+    update_watchlist(|file| {
+        if let Some(existing) = file.mods.iter().find(|m| same_mod(&m.mod_id, &mod_id)) {
+            return existing.clone();
+        }
+
+        let mut watched = WatchedMod {
+            mod_id,
+            name: details.name.clone(),
+            added_at: now,
+            last_checked: None,
+            last_seen_release: None,
+            compatible_release: None,
+            last_error: None,
+        };
+        apply_check(
+            &mut watched,
+            &details.releases,
+            game_version.as_deref(),
+            now,
+        );
+        file.mods.push(watched.clone());
+        watched
+    })
+}
+
+// Returns whether the mod was on the watchlist
+#[command]
+pub async fn remove_from_watchlist(mod_id: String) -> Result<bool, String> {
+    update_watchlist(|file| {
+        let before = file.mods.len();
+        file.mods.retain(|m| !same_mod(&m.mod_id, &mod_id));
+        file.mods.len() != before
+    })
+}
+
+// Check now instead of waiting for the next interval
+#[command]
+pub async fn check_watchlist_now(app: AppHandle) -> Result<Vec<WatchedMod>, String> {
+    check_watchlist(Some(&app)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, tags: &[&str], created: &str) -> ModRelease {
+        ModRelease {
+            mainfile: format!("https://mods.vintagestory.at/download/{}.zip", version),
+            modversion: Some(version.to_string()),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            fileid: None,
+            created: Some(created.to_string()),
+            releaseid: None,
+            filename: None,
+            modidstr: None,
+            downloads: None,
+            changelog: None,
+            dependencies: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_check_events() {
+        let mut watched = WatchedMod {
+            mod_id: "examplemod".to_string(),
+            name: None,
+            added_at: 0,
+            last_checked: None,
+            last_seen_release: None,
+            compatible_release: None,
+            last_error: None,
+        };
+        let mut releases = vec![release("1.0.0", &["v1.19.8"], "2024-01-01 00:00:00")];
+
+        // The first check only records the baseline
+        let (new_release, compatible) = apply_check(&mut watched, &releases, Some("1.20.0"), 1);
+        assert!(new_release.is_none() && compatible.is_none());
+        assert_eq!(
+            watched
+                .last_seen_release
+                .as_ref()
+                .unwrap()
+                .version
+                .as_deref(),
+            Some("1.0.0")
+        );
+
+        // Nothing changed
+        let (new_release, compatible) = apply_check(&mut watched, &releases, Some("1.20.0"), 2);
+        assert!(new_release.is_none() && compatible.is_none());
+
+        // A port to our game version is both a new and a compatible release
+        releases.push(release("1.1.0", &["v1.20.0"], "2024-02-01 00:00:00"));
+        let (new_release, compatible) = apply_check(&mut watched, &releases, Some("1.20.0"), 3);
+        assert_eq!(
+            new_release.unwrap().release.version.as_deref(),
+            Some("1.1.0")
+        );
+        let compatible = compatible.unwrap();
+        assert_eq!(compatible.release.version.as_deref(), Some("1.1.0"));
+        assert_eq!(compatible.game_version.as_deref(), Some("1.20.0"));
+        assert_eq!(watched.last_checked, Some(3));
+    }
+}
@@ -12,6 +12,7 @@ import { ToastProvider } from "./components/Toast";
 import { ModPackApplicationProvider } from "./contexts/ModPackApplicationContext";
 import { ModListProvider } from "./contexts/ModListContext";
 import ModPackProgressBar from "./components/ModPackProgressBar";
+import WatchlistNotifier from "./components/WatchlistNotifier";
 
 function App() {
   const [theme, setTheme] = useState<"light" | "dark">("light");
@@ -68,6 +69,7 @@ function App() {
               </Routes>
             </main>
             <ModPackProgressBar />
+            <WatchlistNotifier />
           </div>
           </BrowserRouter>
         </ModPackApplicationProvider>
@@ -10,6 +10,8 @@ export default function ModBrowser() {
   const [mods, setMods] = useState<ModDatabaseMod[]>([]);
   const [searchQuery, setSearchQuery] = useState("");
   const [page, setPage] = useState(1);
+  const [totalPages, setTotalPages] = useState<number | undefined>(undefined);
+  const [total, setTotal] = useState(0);
   const [loading, setLoading] = useState(false);
   const [downloading, setDownloading] = useState<Set<string>>(new Set());
   const { showToast } = useToast();
@@ -26,6 +28,8 @@ export default function ModBrowser() {
       setLoading(true);
       const result = await searchMods(searchQuery || undefined, page);
       setMods(result.mods);
+      setTotal(result.total);
+      setTotalPages(result.total_pages);
     } catch (error) {
       console.error("Failed to load mods:", error);
       const errorMessage = error instanceof Error ? error.message : String(error);
@@ -165,8 +169,13 @@ export default function ModBrowser() {
           <button onClick={() => setPage(p => Math.max(1, p - 1))} disabled={page === 1}>
             Previous
           </button>
-          <span>Page {page}</span>
-          <button onClick={() => setPage(p => p + 1)} disabled={mods.length === 0}>
+          <span>
+            Page {page}{totalPages ? ` of ${totalPages}` : ""} ({total} mods)
+          </span>
+          <button
+            onClick={() => setPage(p => p + 1)}
+            disabled={mods.length === 0 || (totalPages !== undefined && page >= totalPages)}
+          >
             Next
           </button>
         </div>
@@ -1,13 +1,99 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import type { ModStatus } from "../types/mod";
+import type { ModStatus, DependencyPlan, RemovalReport, UpdatePlan, AssetConflictReport, PatchRef, PatchReport, ModSide } from "../types/mod";
+import { chooseDependentsAction } from "./dependentsPrompt";
+import { planDependencies, getCachedModStatuses, checkAllModsStatus, updateAll, rollbackUpdate, holdMod, releaseModHold, analyzeAssetConflicts, analyzeModPatches, filterModsBySide } from "../services/api";
 import { useToast } from "./Toast";
 import { useModList } from "../contexts/ModListContext";
 
+function describeUpdatePlan(plan: UpdatePlan): string {
+  const lines = plan.updates.map(u => `Update ${u.name} ${u.fromVersion} -> ${u.toVersion}`);
+  if (plan.held.length > 0) {
+    lines.push("", "Held (not updated):", ...plan.held.map(u => `${u.name} ${u.fromVersion} (latest ${u.toVersion})`));
+  }
+  const dependencies = describePlanLines(plan.dependencies);
+  if (dependencies.length > 0) {
+    lines.push("", "Dependencies:", ...dependencies);
+  }
+  if (plan.warnings.length > 0) {
+    lines.push("", "Warnings:", ...plan.warnings);
+  }
+  return `${lines.join("\n")}\n\nUpdate now? The current files are kept so the update can be undone.`;
+}
+
+function describeAssetConflicts(report: AssetConflictReport): string {
+  const lines = [`Checked ${report.modsScanned} enabled mod(s).`];
+  for (const conflict of report.conflicts) {
+    const label = conflict.kind === "modConflict" ? "Conflict" : "Both replace the same game assets, only one is used";
+    lines.push("", `${label}: ${conflict.nameA} and ${conflict.nameB}`);
+    // Only a few paths each, the rest are counted
+    for (const paths of [conflict.modConflicts, conflict.vanillaOverrides]) {
+      lines.push(...paths.slice(0, 5).map(path => `  ${path}`));
+      if (paths.length > 5) {
+        lines.push(`  ...and ${paths.length - 5} more`);
+      }
+    }
+  }
+  if (report.errors.length > 0) {
+    lines.push("", "Could not read:", ...report.errors);
+  }
+  return lines.join("\n");
+}
+
+function describePatchReport(report: PatchReport): string {
+  const lines = [`Checked ${report.patchesChecked} patch(es) in ${report.modsScanned} enabled mod(s).`];
+  const where = (patch: PatchRef) => `${patch.modId} ${patch.patchFile} #${patch.index}`;
+  if (report.missingTargets.length > 0) {
+    lines.push("", "Missing targets:", ...report.missingTargets.map(m => `  ${where(m.patch)}: ${m.reason}`));
+  }
+  if (report.disabledDependencies.length > 0) {
+    lines.push("", "Need a disabled mod:", ...report.disabledDependencies.map(d => `  ${where(d.patch)}: ${d.reason}`));
+  }
+  if (report.overlaps.length > 0) {
+    lines.push("", "Patched by more than one mod:");
+    for (const overlap of report.overlaps) {
+      lines.push(`  ${overlap.file} ${overlap.path}: ${[...new Set(overlap.patches.map(p => p.modId))].join(", ")}`);
+    }
+  }
+  if (report.errors.length > 0) {
+    lines.push("", "Could not read:", ...report.errors);
+  }
+  lines.push(...report.warnings.map(w => `\n${w}`));
+  return lines.join("\n");
+}
+
+function describePlan(modId: string, plan: DependencyPlan): string {
+  return `Dependencies of ${modId}:\n\n${describePlanLines(plan).join("\n")}\n\nContinue?`;
+}
+
+function describePlanLines(plan: DependencyPlan): string[] {
+  const lines = plan.steps.map(step =>
+    step.action === "upgrade"
+      ? `Upgrade ${step.modId} ${step.fromVersion} -> ${step.toVersion}`
+      : `Install ${step.modId} ${step.toVersion}`
+  );
+  for (const problem of plan.problems) {
+    if (problem.kind === "cycle") {
+      lines.push(`Circular dependency: ${problem.modIds.join(" -> ")}`);
+    } else if (problem.kind === "unsatisfiable") {
+      lines.push(`Cannot install ${problem.modId}: needs ${problem.minVersion}, newest is ${problem.newestAvailable ?? "none"}`);
+    } else {
+      lines.push(`Cannot install ${problem.modId}: ${problem.error}`);
+    }
+  }
+  lines.push(...plan.warnings);
+  return lines;
+}
+
+// Statuses checked more recently than this are not checked again
+const STATUS_MAX_AGE_SECS = 15 * 60;
+
 export default function ModList() {
   const { mods, modsPath, loading, refreshMods } = useModList();
   const [selectedMods, setSelectedMods] = useState<Set<string>>(new Set());
   const [searchQuery, setSearchQuery] = useState("");
+  const [sideFilter, setSideFilter] = useState<ModSide | "all">("all");
+  const [sideModIds, setSideModIds] = useState<Set<string> | null>(null);
   const [modStatuses, setModStatuses] = useState<Map<string, ModStatus>>(new Map());
   const [checkingStatus, setCheckingStatus] = useState<Set<string>>(new Set());
   const { showToast } = useToast();
@@ -23,9 +109,30 @@ export default function ModList() {
     }
   }, [mods, modsPath]);
 
+  // Mods that run on the chosen side, as the backend reads their modinfo
+  useEffect(() => {
+    if (sideFilter === "all" || !modsPath) {
+      setSideModIds(null);
+      return;
+    }
+    filterModsBySide(modsPath, sideFilter)
+      .then(infos => setSideModIds(new Set(infos.map(info => info.modId))))
+      .catch((error) => {
+        console.error("Failed to filter mods by side:", error);
+        setSideModIds(null);
+      });
+  }, [mods, modsPath, sideFilter]);
+
   async function checkAllModStatuses(path: string) {
+    // Show the last known statuses right away, then refresh the stale ones
+    try {
+      const cached = await getCachedModStatuses(path);
+      setModStatuses(prev => (prev.size > 0 ? prev : new Map(Object.entries(cached))));
+    } catch (error) {
+      console.error("Failed to load cached mod statuses:", error);
+    }
     try {
-      const statuses = await invoke<Record<string, ModStatus>>("check_all_mods_status", { modsPath: path });
+      const statuses = await checkAllModsStatus(path, STATUS_MAX_AGE_SECS);
       setModStatuses(new Map(Object.entries(statuses)));
     } catch (error) {
       console.error("Failed to check mod statuses:", error);
@@ -33,9 +140,13 @@ export default function ModList() {
   }
 
   async function handleUpdateMod(modId: string) {
+    const held = modStatuses.get(modId)?.held;
+    if (held && !window.confirm(`${modId} is held at ${held.version}${held.reason ? ` (${held.reason})` : ""}. Update it anyway?`)) {
+      return;
+    }
     try {
       setCheckingStatus(prev => new Set(prev).add(modId));
-      await invoke("update_mod", { modId, modsPath: modsPath });
+      await invoke("update_mod", { modId, modsPath: modsPath, overrideHold: !!held });
       showToast(`Updated ${modId} successfully`, "success");
       // Refresh mods in background without blocking
       refreshMods().catch((error) => {
@@ -53,10 +164,110 @@ export default function ModList() {
     }
   }
 
+  async function handleUpdateAll() {
+    try {
+      const { plan } = await updateAll(modsPath);
+      if (plan.updates.length === 0 && plan.dependencies.steps.length === 0) {
+        showToast("All mods are up to date", "success");
+        return;
+      }
+      if (!window.confirm(describeUpdatePlan(plan))) {
+        return;
+      }
+      // The confirmation listed the dependencies that cannot be installed
+      const allowIncomplete = plan.dependencies.problems.some(problem => problem.kind !== "cycle");
+      const { batch } = await updateAll(modsPath, false, false, plan, allowIncomplete);
+      refreshMods().catch((error) => {
+        console.error("Failed to refresh mods after updating:", error);
+      });
+      showToast(`Updated ${batch?.entries.length ?? 0} mod(s)`, "success");
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to update mods: ${errorMessage}`, "error");
+    }
+  }
+
+  async function handleCheckAssetConflicts() {
+    try {
+      const report = await analyzeAssetConflicts(modsPath);
+      if (report.conflicts.length === 0 && report.errors.length === 0) {
+        showToast(`No asset conflicts between ${report.modsScanned} enabled mod(s)`, "success");
+        return;
+      }
+      window.alert(describeAssetConflicts(report));
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to check asset conflicts: ${errorMessage}`, "error");
+    }
+  }
+
+  async function handleCheckPatches() {
+    try {
+      const report = await analyzeModPatches(modsPath);
+      const problems = report.missingTargets.length + report.disabledDependencies.length + report.overlaps.length;
+      if (problems === 0 && report.errors.length === 0 && report.warnings.length === 0) {
+        showToast(`All ${report.patchesChecked} patch(es) apply cleanly`, "success");
+        return;
+      }
+      window.alert(describePatchReport(report));
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to check patches: ${errorMessage}`, "error");
+    }
+  }
+
+  async function handleRollbackUpdate() {
+    if (!window.confirm("Restore the mods as they were before the last update?")) {
+      return;
+    }
+    try {
+      const batch = await rollbackUpdate();
+      refreshMods().catch((error) => {
+        console.error("Failed to refresh mods after rolling back:", error);
+      });
+      showToast(`Restored ${batch.entries.length} mod(s)`, "success");
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to undo the update: ${errorMessage}`, "error");
+    }
+  }
+
+  async function handleToggleHold(modId: string) {
+    const status = modStatuses.get(modId);
+    try {
+      if (status?.held) {
+        await releaseModHold(modsPath, modId);
+        showToast(`${modId} is no longer held`, "success");
+      } else {
+        const reason = window.prompt(`Hold ${modId} at its installed version? Optionally say why:`);
+        if (reason === null) {
+          return;
+        }
+        const held = await holdMod(modsPath, modId, undefined, reason);
+        showToast(`${modId} is held at ${held.version}`, "success");
+      }
+      await checkAllModStatuses(modsPath);
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to change the hold on ${modId}: ${errorMessage}`, "error");
+    }
+  }
+
   async function handleInstallDependencies(modId: string) {
     try {
       setCheckingStatus(prev => new Set(prev).add(modId));
-      const installed = await invoke<string[]>("install_dependencies", { modId, modsPath: modsPath });
+      const plan = await planDependencies(modId, modsPath);
+      if (plan.steps.length === 0 && plan.problems.length === 0) {
+        showToast(`All dependencies of ${modId} are installed`, "success");
+        return;
+      }
+      if (!window.confirm(describePlan(modId, plan))) {
+        return;
+      }
+      // The backend refuses if the plan changed since this preview; confirming it
+      // accepts that the dependencies listed as problems are left out
+      const allowIncomplete = plan.problems.some(problem => problem.kind !== "cycle");
+      const installed = await invoke<string[]>("install_dependencies", { modId, modsPath: modsPath, plan, allowIncomplete });
       showToast(`Installed ${installed.length} dependency/dependencies for ${modId}`, "success");
       // Refresh mods in background without blocking
       refreshMods().catch((error) => {
@@ -112,13 +323,20 @@ export default function ModList() {
   async function disableSelectedMods() {
     try {
       const modIds = Array.from(selectedMods);
-      await invoke("disable_mods", { modsPath: modsPath, modIds });
+      let report = await invoke<RemovalReport>("disable_mods", { modsPath: modsPath, modIds });
+      if (!report.applied) {
+        const onDependents = chooseDependentsAction("disable", report);
+        if (!onDependents) {
+          return;
+        }
+        report = await invoke<RemovalReport>("disable_mods", { modsPath: modsPath, modIds, onDependents });
+      }
       // Refresh mods in background without blocking
       refreshMods().catch((error) => {
         console.error("Failed to refresh mods after disabling:", error);
       });
       setSelectedMods(new Set());
-      showToast(`Disabled ${modIds.length} mod(s)`, "success");
+      showToast(`Disabled ${report.affected.length} mod(s)`, "success");
     } catch (error) {
       console.error("Failed to disable mods:", error);
       const errorMessage = error instanceof Error ? error.message : String(error);
@@ -143,13 +361,20 @@ export default function ModList() {
     }
     
     try {
-      await invoke("delete_mods", { modsPath: modsPath, modIds });
+      let report = await invoke<RemovalReport>("delete_mods", { modsPath: modsPath, modIds });
+      if (!report.applied) {
+        const onDependents = chooseDependentsAction("delete", report);
+        if (!onDependents) {
+          return;
+        }
+        report = await invoke<RemovalReport>("delete_mods", { modsPath: modsPath, modIds, onDependents });
+      }
       // Refresh mods in background without blocking
       refreshMods().catch((error) => {
         console.error("Failed to refresh mods after deleting:", error);
       });
       setSelectedMods(new Set());
-      showToast(`Deleted ${count} mod(s)`, "success");
+      showToast(`Deleted ${report.affected.length} mod(s)`, "success");
     } catch (error) {
       console.error("Failed to delete mods:", error);
       const errorMessage = error instanceof Error ? error.message : String(error);
@@ -158,8 +383,9 @@ export default function ModList() {
   }
 
   const filteredMods = mods.filter(mod =>
-    mod.name.toLowerCase().includes(searchQuery.toLowerCase()) ||
-    mod.id.toLowerCase().includes(searchQuery.toLowerCase())
+    (mod.name.toLowerCase().includes(searchQuery.toLowerCase()) ||
+      mod.id.toLowerCase().includes(searchQuery.toLowerCase())) &&
+    (!sideModIds || sideModIds.has(mod.id))
   );
 
   if (loading) {
@@ -181,7 +407,16 @@ export default function ModList() {
             onChange={(e) => setSearchQuery(e.target.value)}
             style={{ flex: 1 }}
           />
+          <select value={sideFilter} onChange={(e) => setSideFilter(e.target.value as ModSide | "all")}>
+            <option value="all">All sides</option>
+            <option value="client">Runs on client</option>
+            <option value="server">Runs on server</option>
+          </select>
           <button onClick={() => refreshMods()}>Refresh</button>
+          <button onClick={handleUpdateAll}>Update All</button>
+          <button onClick={handleRollbackUpdate}>Undo Last Update</button>
+          <button onClick={handleCheckAssetConflicts}>Check Conflicts</button>
+          <button onClick={handleCheckPatches}>Check Patches</button>
           <button onClick={toggleSelectAll}>
             {selectedMods.size === filteredMods.length ? "Deselect All" : "Select All"}
           </button>
@@ -249,6 +484,11 @@ export default function ModList() {
                           (Latest: {status.latestVersion})
                         </span>
                       )}
+                      {status?.held && (
+                        <span style={{ color: "#8e44ad", marginLeft: "0.5rem" }} title={status.held.reason}>
+                          Held at {status.held.version}
+                        </span>
+                      )}
                     </p>
                     {mod.info?.description && (
                       <p style={{ marginTop: "0.5rem" }}>{mod.info.description}</p>
@@ -258,6 +498,25 @@ export default function ModList() {
                         Missing dependencies: {status!.missingDependencies.map((d: { modid: string }) => d.modid).join(", ")}
                       </p>
                     )}
+                    {status?.knownIssues?.map(issue => (
+                      <p
+                        key={issue.ruleId}
+                        style={{ marginTop: "0.5rem", color: issue.severity === "error" ? "#e74c3c" : "#f39c12", fontSize: "0.875rem" }}
+                        title={issue.link}
+                      >
+                        Known issue{issue.otherMod ? ` with ${issue.otherMod}` : ""}: {issue.note}
+                      </p>
+                    ))}
+                    {status?.meetsGameRequirement === false && (
+                      <p style={{ marginTop: "0.5rem", color: "#e74c3c", fontSize: "0.875rem" }}>
+                        Requires game {status.requiredGameVersion} (installed: {status.gameVersion})
+                      </p>
+                    )}
+                    {status?.taggedCompatible === false && (
+                      <p style={{ marginTop: "0.5rem", color: "#f39c12", fontSize: "0.875rem" }}>
+                        This release is tagged for {status.installedReleaseTags?.join(", ")}, not {status.gameVersion}
+                      </p>
+                    )}
                     {hasOutdatedDeps && (
                       <p style={{ marginTop: "0.5rem", color: "#f39c12", fontSize: "0.875rem" }}>
                         Outdated dependencies: {status!.outdatedDependencies.map((d: { modid: string; installed: string; required: string }) => `${d.modid} (${d.installed} → ${d.required})`).join(", ")}
@@ -309,6 +568,22 @@ export default function ModList() {
                       >
                         {isChecking ? "Installing..." : "Install Deps"}
                       </button>
+                      <button
+                        onClick={() => handleToggleHold(mod.id)}
+                        disabled={isChecking}
+                        style={{
+                          padding: "0.25rem 0.75rem",
+                          fontSize: "0.875rem",
+                          backgroundColor: status?.held ? "#8e44ad" : "#7f8c8d",
+                          color: "white",
+                          border: "none",
+                          borderRadius: "4px",
+                          cursor: isChecking ? "not-allowed" : "pointer",
+                        }}
+                        title={status?.held ? "Let this mod be updated again" : "Keep this mod at its installed version"}
+                      >
+                        {status?.held ? "Release" : "Hold"}
+                      </button>
                     </div>
                   </div>
                 </div>
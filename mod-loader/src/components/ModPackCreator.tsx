@@ -96,6 +96,7 @@ export default function ModPackCreator() {
           id: m.id,
           version: m.version,
           ...(hash ? { hash } : {}),
+          source: { type: "moddb" as const },
         };
       }),
     }));
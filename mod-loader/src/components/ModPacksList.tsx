@@ -2,6 +2,9 @@ import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { getSettings } from "../services/storage";
 import { useToast } from "./Toast";
+import { chooseDependentsAction } from "./dependentsPrompt";
+import type { RemovalReport, PackEnableReport } from "../types/mod";
+import { validatePackSide, clientModsForServer } from "../services/api";
 
 export interface ModPackInfo {
   name: string;
@@ -45,10 +48,26 @@ export default function ModPacksList() {
   async function handleTogglePack(pack: ModPackInfo) {
     try {
       if (pack.enabled) {
-        await invoke("disable_mod_pack", { packPath: pack.path, modsPath });
+        const report = await invoke<RemovalReport>("disable_mod_pack", { packPath: pack.path, modsPath });
+        if (!report.applied) {
+          const onDependents = chooseDependentsAction("disable", report);
+          if (!onDependents) {
+            return;
+          }
+          await invoke("disable_mod_pack", { packPath: pack.path, modsPath, onDependents });
+        }
         showToast(`Disabled mod pack: ${pack.name}`, "success");
       } else {
-        await invoke("enable_mod_pack", { packPath: pack.path, modsPath });
+        const report = await invoke<PackEnableReport>("enable_mod_pack", { packPath: pack.path, modsPath });
+        if (!report.applied) {
+          const issues = report.incompatibilities
+            .map(m => `[${m.rule.severity}] ${m.rule.mod.modId}${m.rule.conflictsWith ? ` + ${m.rule.conflictsWith.modId}` : ""}: ${m.rule.note}`)
+            .join("\n");
+          if (!window.confirm(`Known incompatibilities with this pack:\n\n${issues}\n\nEnable it anyway?`)) {
+            return;
+          }
+          await invoke("enable_mod_pack", { packPath: pack.path, modsPath, ignoreIncompatibilities: true });
+        }
         showToast(`Enabled mod pack: ${pack.name}`, "success");
       }
       await loadModPacks(); // Reload to update state
@@ -59,6 +78,32 @@ export default function ModPacksList() {
     }
   }
 
+  // Check the pack can run on a server and list what clients joining it need
+  async function handleCheckServerPack(pack: ModPackInfo) {
+    try {
+      const report = await validatePackSide(pack.path, modsPath, "server");
+      const clients = await clientModsForServer(modsPath, { packPath: pack.path });
+      const lines: string[] = [];
+      if (report.wrongSide.length > 0) {
+        lines.push("Client-only mods (remove them for a server):", ...report.wrongSide.map(m => `  ${m.name} ${m.version}`), "");
+      }
+      lines.push(`Clients need (${clients.required.length}):`, ...clients.required.map(m => `  ${m.name} ${m.version}`));
+      if (clients.optional.length > 0) {
+        lines.push("", "Optional for clients:", ...clients.optional.map(m => `  ${m.name} ${m.version}`));
+      }
+      if (clients.serverOnly.length > 0) {
+        lines.push("", "Server only:", ...clients.serverOnly.map(m => `  ${m.name} ${m.version}`));
+      }
+      if (report.unknown.length > 0) {
+        lines.push("", `Not installed, side unknown: ${report.unknown.join(", ")}`);
+      }
+      window.alert(lines.join("\n"));
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to check ${pack.name} for a server: ${errorMessage}`, "error", 6000);
+    }
+  }
+
   function toggleExpand(packPath: string) {
     const newExpanded = new Set(expandedPacks);
     if (newExpanded.has(packPath)) {
@@ -137,6 +182,16 @@ export default function ModPacksList() {
                   >
                     {expandedPacks.has(pack.path) ? "Hide Mods" : "Show Mods"}
                   </button>
+                  <button
+                    onClick={() => handleCheckServerPack(pack)}
+                    style={{
+                      padding: "0.25rem 0.75rem",
+                      fontSize: "0.875rem",
+                    }}
+                    title="Check for client-only mods and list what clients need to join"
+                  >
+                    Server Check
+                  </button>
                   <button
                     onClick={() => handleTogglePack(pack)}
                     style={{
@@ -2,6 +2,8 @@ import { useState, useEffect } from "react";
 import { getSettings, saveSettings, type Settings as SettingsType } from "../services/storage";
 import { invoke } from "@tauri-apps/api/core";
 import { useToast } from "./Toast";
+import { open, save } from "@tauri-apps/plugin-dialog";
+import { importIncompatibilityRules, exportIncompatibilityRules } from "../services/api";
 
 export default function Settings() {
   const [settings, setSettings] = useState<SettingsType>({
@@ -53,6 +55,41 @@ export default function Settings() {
     }
   }
 
+  async function handleImportRules() {
+    try {
+      const filePath = await open({
+        filters: [{ name: "Incompatibility Rules", extensions: ["json"] }],
+        multiple: false,
+        title: "Select Incompatibility Rules File",
+      });
+      if (typeof filePath !== "string") {
+        return;
+      }
+      const count = await importIncompatibilityRules(filePath);
+      showToast(`Imported ${count} incompatibility rule(s)`, "success");
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to import incompatibility rules: ${errorMessage}`, "error");
+    }
+  }
+
+  async function handleExportRules() {
+    try {
+      const filePath = await save({
+        filters: [{ name: "Incompatibility Rules", extensions: ["json"] }],
+        defaultPath: "incompatibility-rules.json",
+      });
+      if (!filePath) {
+        return;
+      }
+      await exportIncompatibilityRules(filePath);
+      showToast("Exported incompatibility rules", "success");
+    } catch (error) {
+      const errorMessage = error instanceof Error ? error.message : String(error);
+      showToast(`Failed to export incompatibility rules: ${errorMessage}`, "error");
+    }
+  }
+
   if (loading) {
     return <div className="card">Loading settings...</div>;
   }
@@ -85,6 +122,91 @@ export default function Settings() {
             </button>
           </div>
 
+          <div>
+            <label>Game Version</label>
+            <input
+              type="text"
+              value={settings.game_version || ""}
+              onChange={(e) => setSettings(prev => ({ ...prev, game_version: e.target.value || undefined }))}
+              placeholder="Detected from the game install or logs (e.g. 1.19.8)"
+            />
+          </div>
+
+          <div>
+            <label>Game Install Folder</label>
+            <input
+              type="text"
+              value={settings.game_install_path || ""}
+              onChange={(e) => setSettings(prev => ({ ...prev, game_install_path: e.target.value || undefined }))}
+              placeholder="Folder containing VintagestoryAPI.dll"
+            />
+          </div>
+
+          <div>
+            <label>
+              <input
+                type="checkbox"
+                checked={settings.offline_mode || false}
+                onChange={(e) => setSettings(prev => ({ ...prev, offline_mode: e.target.checked }))}
+              />
+              Offline Mode (use cached ModDB data only)
+            </label>
+          </div>
+
+          <div>
+            <label>
+              <input
+                type="checkbox"
+                checked={settings.enable_html_scraping || false}
+                onChange={(e) => setSettings(prev => ({ ...prev, enable_html_scraping: e.target.checked }))}
+              />
+              Fall back to ModDB mod pages when the API cannot find a download
+            </label>
+          </div>
+
+          <div>
+            <label>ModDB Cache Lifetime (minutes)</label>
+            <input
+              type="number"
+              min={0}
+              value={settings.api_cache_ttl_minutes ?? ""}
+              onChange={(e) => setSettings(prev => ({
+                ...prev,
+                api_cache_ttl_minutes: e.target.value === "" ? undefined : Number(e.target.value),
+              }))}
+              placeholder="30"
+            />
+          </div>
+
+          <div>
+            <label>Parallel Downloads</label>
+            <input
+              type="number"
+              min={1}
+              max={8}
+              value={settings.parallel_downloads ?? ""}
+              onChange={(e) => setSettings(prev => ({
+                ...prev,
+                parallel_downloads: e.target.value === "" ? undefined : Number(e.target.value),
+              }))}
+              placeholder="3"
+            />
+          </div>
+
+          <div>
+            <label>Watchlist Check Interval (minutes, 0 = off)</label>
+            <input
+              type="number"
+              min={0}
+              value={settings.watchlist_interval_minutes ?? ""}
+              onChange={(e) => setSettings(prev => ({
+                ...prev,
+                watchlist_interval_minutes: e.target.value === "" ? undefined : Number(e.target.value),
+              }))}
+              placeholder="60"
+            />
+          </div>
+
           <div>
             <label>API Username</label>
             <input
@@ -118,6 +240,14 @@ export default function Settings() {
               <option value="dark">Dark</option>
             </select>
           </div>
+
+          <div>
+            <label>Incompatibility Rules</label>
+            <div style={{ display: "flex", gap: "0.5rem" }}>
+              <button onClick={handleImportRules}>Import Rules...</button>
+              <button onClick={handleExportRules}>Export Rules...</button>
+            </div>
+          </div>
         </div>
 
         <div style={{ marginTop: "2rem" }}>
@@ -0,0 +1,27 @@
+import { useEffect } from "react";
+import { onWatchlistRelease } from "../services/api";
+import { useToast } from "./Toast";
+
+// Tells the user about releases the background watchlist check finds
+export default function WatchlistNotifier() {
+  const { showToast } = useToast();
+
+  useEffect(() => {
+    const unlisten = onWatchlistRelease((event, compatible) => {
+      const name = event.name || event.mod_id;
+      const version = event.release.version || "a new version";
+      showToast(
+        compatible
+          ? `${name} ${version} supports game version ${event.game_version}`
+          : `${name} has a new release: ${version}`,
+        compatible ? "success" : "info",
+        10000
+      );
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, [showToast]);
+
+  return null;
+}
@@ -0,0 +1,16 @@
+import type { DependentsAction, RemovalReport } from "../types/mod";
+
+// Ask what to do with enabled mods that depend on the mods being removed;
+// null cancels the whole operation
+export function chooseDependentsAction(verb: string, report: RemovalReport): DependentsAction | null {
+  const dependents = report.wouldBreak
+    .map(d => `${d.name} (needs ${d.requires.join(", ")})`)
+    .join("\n");
+  if (window.confirm(`These enabled mods depend on what you are about to ${verb}:\n\n${dependents}\n\n${verb[0].toUpperCase()}${verb.slice(1)} them as well?`)) {
+    return "cascade";
+  }
+  if (window.confirm(`Only ${verb} the mods nothing enabled depends on?`)) {
+    return "keep";
+  }
+  return null;
+}
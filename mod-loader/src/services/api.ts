@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { ModSearchResult, ModDatabaseMod, ModStatus } from "../types/mod";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import type { ModSearchResult, ModDatabaseMod, ModStatus, ModDetails, UpdateDigest, DependencyPlan, UpdatePlan, UpdateBatch, HeldMod, AssetConflictReport, PatchReport, IncompatibilityRule, ModSide, ModSideInfo, PackSideReport, ClientRequirements, DownloadItem, FetchOutcome, ModSource, RepositoryIndex, RepositoryMod, WatchedMod, WatchEvent } from "../types/mod";
 
 // VS Mod DB API: http://mods.vintagestory.at/api
 // API docs: https://raw.githubusercontent.com/anegostudios/vsmoddb/refs/heads/master/README.md
@@ -13,23 +14,31 @@ export interface ModSearchItem {
   thumbnail?: string;
   category?: string;
   tags?: string[];
-  releases?: Array<{ mainfile: string; version?: string }>;
+  releases?: Array<{ mainfile: string; modversion?: string; tags?: string[] }>;
 }
 
 export interface ModSearchApiResult {
   status_code?: number;
   mods: ModSearchItem[];
+  fetch_outcome?: FetchOutcome;
+  age_secs?: number;
+  // Pagination over the full result set, done by the backend
+  total?: number;
+  page?: number;
+  per_page?: number;
+  total_pages?: number;
 }
 
-export async function searchMods(query?: string, page?: number): Promise<ModSearchResult> {
-  const result = await invoke<ModSearchApiResult>("search_mods", { query, page });
+export async function searchMods(query?: string, page?: number, perPage?: number): Promise<ModSearchResult> {
+  // Fails with the backend's error instead of returning an empty page
+  const result = await invoke<ModSearchApiResult>("search_mods", { query, page, perPage });
   
   // Convert API response to frontend format
   const mods: ModDatabaseMod[] = result.mods.map((item) => {
     // Get download URL from first release if available
     const downloadUrl = item.releases?.[0]?.mainfile;
     // Get version from first release if available
-    const version = item.releases?.[0]?.version || "unknown";
+    const version = item.releases?.[0]?.modversion || "unknown";
     
     return {
       id: item.modid || item.id?.toString() || "unknown",
@@ -46,21 +55,196 @@ export async function searchMods(query?: string, page?: number): Promise<ModSear
   
   return {
     mods,
-    total: mods.length,
-    page: page || 1,
-    per_page: 20,
+    total: result.total ?? mods.length,
+    page: result.page ?? (page || 1),
+    per_page: result.per_page ?? 20,
+    total_pages: result.total_pages,
+    fetch_outcome: result.fetch_outcome,
+    age_secs: result.age_secs,
   };
 }
 
-export async function getModDownloadUrl(modId: string, modUrl?: string): Promise<string> {
-  return await invoke("get_mod_download_url", { modId, modUrl });
+export async function getModDetails(modId: string, modUrl?: string): Promise<ModDetails> {
+  return await invoke("get_mod_details", { modId, modUrl });
 }
 
-export async function downloadMod(modId: string, downloadUrl: string, modsPath: string): Promise<string> {
-  return await invoke("download_mod", { modId, downloadUrl, modsPath });
+export async function getModDownloadUrl(modId: string, modUrl?: string, version?: string, source?: ModSource): Promise<string> {
+  // When a version is given the backend resolves exactly that release instead of the latest one;
+  // without a source the backend infers one from modUrl
+  return await invoke("get_mod_download_url", { modId, modUrl, version, source });
+}
+
+export async function downloadMod(
+  modId: string,
+  downloadUrl: string,
+  modsPath: string,
+  expectedHash?: string,
+  version?: string,
+  source?: ModSource,
+  overrideHold = false,
+): Promise<string> {
+  // The backend verifies the file (and the SHA-256 hash when given) before replacing the installed mod;
+  // hash or version let it reuse a copy from the download cache instead of the network.
+  // A held mod is only replaced by another version with overrideHold, which is only sent when set
+  return await invoke("download_mod", {
+    modId, downloadUrl, modsPath, expectedHash, version, source, ...(overrideHold ? { overrideHold } : {}),
+  });
 }
 
 export async function checkModStatus(modId: string, modsPath: string): Promise<ModStatus> {
   return await invoke("check_mod_status", { modId, modsPath });
 }
 
+
+export async function getCachedModStatuses(modsPath: string): Promise<Record<string, ModStatus>> {
+  // Last known statuses, instantly and without any requests
+  return await invoke("get_cached_mod_statuses", { modsPath });
+}
+
+export async function checkAllModsStatus(modsPath: string, maxAgeSecs?: number): Promise<Record<string, ModStatus>> {
+  // With maxAgeSecs, only statuses older than that are checked again
+  return await invoke("check_all_mods_status", { modsPath, maxAgeSecs });
+}
+
+export async function getUpdateDigest(modsPath: string): Promise<UpdateDigest> {
+  // Changelogs and new dependencies for every outdated mod; may download latest releases whose source publishes no dependencies
+  return await invoke("get_update_digest", { modsPath });
+}
+
+export async function planDependencies(modId: string, modsPath: string): Promise<DependencyPlan> {
+  // Everything installing the mod's dependencies would do, in install order; downloads nothing
+  return await invoke("plan_dependencies", { modId, modsPath });
+}
+
+export async function updateAll(
+  modsPath: string,
+  dryRun = true,
+  overrideHolds = false,
+  plan?: UpdatePlan,
+  allowIncomplete = false,
+): Promise<{ plan: UpdatePlan; batch?: UpdateBatch }> {
+  // With dryRun (the default) nothing is changed. Running needs the previewed plan and fails if the
+  // updates changed since; it installs all of them or none, keeping the replaced files for rollbackUpdate.
+  // Held mods are left out unless overrideHolds is set
+  return await invoke("update_all", { modsPath, dryRun, overrideHolds, plan, allowIncomplete });
+}
+
+export async function getUpdateBatches(): Promise<UpdateBatch[]> {
+  return await invoke("get_update_batches");
+}
+
+export async function rollbackUpdate(batchId?: string): Promise<UpdateBatch> {
+  // Without batchId the latest batch is rolled back
+  return await invoke("rollback_update", { batchId });
+}
+
+export async function analyzeAssetConflicts(modsPath: string): Promise<AssetConflictReport> {
+  // Pairs of enabled mods that ship the same asset paths
+  return await invoke("analyze_asset_conflicts", { modsPath });
+}
+
+export async function analyzeModPatches(modsPath: string): Promise<PatchReport> {
+  // Patches of enabled mods whose targets are missing, need a disabled mod, or clash with another mod's patch
+  return await invoke("analyze_mod_patches", { modsPath });
+}
+
+export async function getIncompatibilityRules(): Promise<IncompatibilityRule[]> {
+  return await invoke("get_incompatibility_rules");
+}
+
+export async function importIncompatibilityRules(filePath: string): Promise<number> {
+  // Rules with the same id as existing ones replace them
+  return await invoke("import_incompatibility_rules", { filePath });
+}
+
+export async function exportIncompatibilityRules(filePath: string, name?: string): Promise<void> {
+  return await invoke("export_incompatibility_rules", { filePath, name });
+}
+
+export async function filterModsBySide(modsPath: string, side: ModSide, enabledOnly = false): Promise<ModSideInfo[]> {
+  // Installed mods that run on the side; "universal" lists them all
+  return await invoke("filter_mods_by_side", { modsPath, side, enabledOnly });
+}
+
+export async function validatePackSide(packPath: string, modsPath: string, side: ModSide): Promise<PackSideReport> {
+  // Pack mods that would not run on the side, e.g. client-only mods in a server pack
+  return await invoke("validate_pack_side", { packPath, modsPath, side });
+}
+
+export async function clientModsForServer(modsPath: string, server: { modIds?: string[]; packPath?: string }): Promise<ClientRequirements> {
+  // What a client needs to join a server running the given mods or pack
+  return await invoke("client_mods_for_server", { modsPath, modIds: server.modIds, packPath: server.packPath });
+}
+
+export async function getModHolds(modsPath: string): Promise<HeldMod[]> {
+  return await invoke("get_mod_holds", { modsPath });
+}
+
+export async function holdMod(modsPath: string, modId: string, version?: string, reason?: string): Promise<HeldMod> {
+  // Without a version the mod is held at the installed one
+  return await invoke("hold_mod", { modsPath, modId, version, reason });
+}
+
+export async function releaseModHold(modsPath: string, modId: string): Promise<boolean> {
+  return await invoke("release_mod_hold", { modsPath, modId });
+}
+
+export async function getModSources(): Promise<Record<string, ModSource>> {
+  // Mods installed from somewhere other than the ModDB, keyed by lowercase modid
+  return await invoke("get_mod_sources");
+}
+
+export async function setModSource(modId: string, source?: ModSource): Promise<void> {
+  // Without a source the mod goes back to the ModDB for update checks
+  return await invoke("set_mod_source", { modId, source });
+}
+
+export async function generateRepositoryIndex(folder: string, name?: string): Promise<RepositoryIndex> {
+  // Writes index.json into the folder, describing every mod zip below it
+  return await invoke("generate_repository_index", { folder, name });
+}
+
+export async function searchRepository(repositoryUrl: string, query?: string): Promise<RepositoryMod[]> {
+  return await invoke("search_repository", { repositoryUrl, query });
+}
+
+export async function getWatchlist(): Promise<WatchedMod[]> {
+  return await invoke("get_watchlist");
+}
+
+export async function addToWatchlist(modId: string): Promise<WatchedMod> {
+  return await invoke("add_to_watchlist", { modId });
+}
+
+export async function removeFromWatchlist(modId: string): Promise<boolean> {
+  return await invoke("remove_from_watchlist", { modId });
+}
+
+export async function checkWatchlistNow(): Promise<WatchedMod[]> {
+  return await invoke("check_watchlist_now");
+}
+
+// Calls `onEvent` for every release the background watchlist check finds;
+// `compatible` is true for the first release tagged for our game version
+export async function onWatchlistRelease(
+  onEvent: (event: WatchEvent, compatible: boolean) => void
+): Promise<UnlistenFn> {
+  const unlistenNew = await listen<WatchEvent>("watchlist-new-release", (e) => onEvent(e.payload, false));
+  const unlistenCompatible = await listen<WatchEvent>("watchlist-compatible-release", (e) => onEvent(e.payload, true));
+  return () => {
+    unlistenNew();
+    unlistenCompatible();
+  };
+}
+
+export async function getDownloadQueue(): Promise<DownloadItem[]> {
+  return await invoke("get_download_queue");
+}
+
+export async function retryDownload(id: string): Promise<DownloadItem> {
+  return await invoke("retry_download", { id });
+}
+
+export async function clearFinishedDownloads(): Promise<number> {
+  return await invoke("clear_finished_downloads");
+}
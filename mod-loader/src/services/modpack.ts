@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { open, save } from "@tauri-apps/plugin-dialog";
-import type { ModPack, ModPackMetadata } from "../types/mod";
+import type { ModPack, ModPackMetadata, ModPackMod, ModSource } from "../types/mod";
 import { downloadMod as apiDownloadMod, getModDownloadUrl as apiGetModDownloadUrl } from "./api";
 
 export async function exportModPack(pack: ModPack): Promise<void> {
@@ -112,6 +112,8 @@ export interface ApplyModPackOptions {
   onFailed?: (modId: string, error: string) => void;
   onSkipped?: (modId: string) => void;
   abortSignal?: AbortSignal;
+  // Replace mods held at another version instead of failing them
+  overrideHolds?: boolean;
 }
 
 export async function applyModPack(
@@ -119,26 +121,98 @@ export async function applyModPack(
   modsPath: string,
   options: ApplyModPackOptions = {}
 ): Promise<ApplyModPackResult> {
-  const { showToast, onProgress, onSuccess, onFailed, onSkipped, abortSignal } = options;
+  const { showToast, onProgress, onSuccess, onFailed, onSkipped, abortSignal, overrideHolds = false } = options;
   // Download missing mods and enable all mods in pack
   const { invoke } = await import("@tauri-apps/api/core");
   
-  // Helper function to get download URL from mod page
-  async function getDownloadUrl(modId: string, _version: string, url?: string): Promise<string | null> {
+  // Helper function to get download URL for the pinned version of a mod
+  async function getDownloadUrl(modId: string, version?: string, url?: string, source?: ModSource): Promise<{ url: string | null; error?: string }> {
+    // A recorded source is resolved by the backend
+    if (source) {
+      try {
+        return { url: await apiGetModDownloadUrl(modId, url, version, source) };
+      } catch (error) {
+        console.warn(`[getDownloadUrl] Failed to resolve ${modId} from its ${source.type} source:`, error);
+        return { url: null, error: error instanceof Error ? error.message : String(error) };
+      }
+    }
+
     // If URL is already a direct download URL, use it
     if (url && url.includes('/download/') && (url.endsWith('.zip') || url.endsWith('.tar') || url.endsWith('.tar.gz'))) {
-      return url;
+      return { url };
     }
     
     // Try to fetch the download URL from the mod page
     try {
-      console.log(`[getDownloadUrl] Fetching download URL for ${modId} from mod page...`);
-      const downloadUrl = await apiGetModDownloadUrl(modId, url);
+      console.log(`[getDownloadUrl] Fetching download URL for ${modId}@${version ?? "latest"}...`);
+      const downloadUrl = await apiGetModDownloadUrl(modId, url, version);
       console.log(`[getDownloadUrl] Got download URL for ${modId}: ${downloadUrl}`);
-      return downloadUrl;
+      return { url: downloadUrl };
     } catch (error) {
       console.warn(`[getDownloadUrl] Failed to get download URL for ${modId}:`, error);
-      return null;
+      // Surface why the pinned version could not be resolved (e.g. removed from the ModDB)
+      return { url: null, error: error instanceof Error ? error.message : String(error) };
+    }
+  }
+
+  // Install a copy fetched earlier (for this or another pack) without touching the network
+  async function installFromCache(modId: string, version?: string, expectedHash?: string): Promise<boolean> {
+    if (!version && !expectedHash) {
+      return false;
+    }
+    try {
+      const cachedPath = await invoke<string | null>("install_cached_mod", {
+        modId, modsPath, version, expectedHash, ...(overrideHolds ? { overrideHold: true } : {}),
+      });
+      if (!cachedPath) {
+        return false;
+      }
+      try {
+        await invoke("reindex_mod", { modsPath, modId });
+      } catch (reindexError) {
+        console.warn(`[applyModPack] Failed to reindex ${modId}:`, reindexError);
+      }
+      return true;
+    } catch (error) {
+      console.warn(`[installFromCache] Cache lookup failed for ${modId}:`, error);
+      return false;
+    }
+  }
+
+  type Acquired =
+    | { kind: "cache" }
+    | { kind: "downloaded" }
+    | { kind: "failed"; error: string; downloadError?: boolean };
+
+  // Get one missing mod onto disk: from the cache, or through the backend download queue
+  async function acquireMod(modPackMod: ModPackMod, pinnedVersion?: string): Promise<Acquired> {
+    if (await installFromCache(modPackMod.id, pinnedVersion, modPackMod.hash)) {
+      return { kind: "cache" };
+    }
+
+    // Resolve the download URL of the version pinned in the pack
+    const resolved = await getDownloadUrl(modPackMod.id, pinnedVersion, modPackMod.url, modPackMod.source);
+    const downloadUrl = resolved.url;
+    if (!downloadUrl || downloadUrl.trim() === '') {
+      // No download URL available and mod is not installed - this is a failure
+      console.warn(`[applyModPack] No download URL available for ${modPackMod.id}, cannot download`);
+      return { kind: "failed", error: resolved.error || `No download URL available for ${modPackMod.id}` };
+    }
+
+    console.log(`[applyModPack] Downloading ${modPackMod.id} from ${downloadUrl}`);
+    try {
+      await apiDownloadMod(modPackMod.id, downloadUrl, modsPath, modPackMod.hash, pinnedVersion, modPackMod.source, overrideHolds);
+
+      // Reindex the newly downloaded mod
+      try {
+        await invoke("reindex_mod", { modsPath, modId: modPackMod.id });
+      } catch (reindexError) {
+        console.warn(`[applyModPack] Failed to reindex ${modPackMod.id}:`, reindexError);
+      }
+      return { kind: "downloaded" };
+    } catch (error) {
+      console.error(`[applyModPack] Failed to download ${modPackMod.id}:`, error);
+      return { kind: "failed", error: error instanceof Error ? error.message : String(error), downloadError: true };
     }
   }
 
@@ -157,6 +231,24 @@ export async function applyModPack(
     console.warn("[applyModPack] Failed to load mod list:", error);
   }
 
+  // Packs built from search results may carry "unknown" instead of a real version
+  const pinnedVersions = pack.mods.map(m => m.version && m.version !== "unknown" ? m.version : undefined);
+
+  // Start every missing mod at once; the backend queue bounds how many download
+  // in parallel, and one failure does not hold up the others
+  const acquisitions = new Map<number, Promise<Acquired>>();
+  pack.mods.forEach((modPackMod, i) => {
+    const pinnedVersion = pinnedVersions[i];
+    // Check if the pinned version is already installed (use cached mod list)
+    const isInstalled = modList.some(m =>
+      m.id === modPackMod.id &&
+      (!pinnedVersion || !m.version || m.version === "unknown" || m.version === pinnedVersion)
+    );
+    if (!isInstalled && !abortSignal?.aborted) {
+      acquisitions.set(i, acquireMod(modPackMod, pinnedVersion));
+    }
+  });
+
   for (let i = 0; i < pack.mods.length; i++) {
     const modPackMod = pack.mods[i];
     
@@ -190,71 +282,25 @@ export async function applyModPack(
         break;
       }
 
-      // Check if mod is already installed (use cached mod list)
-      const isInstalled = modList.some(m => m.id === modPackMod.id);
+      const acquisition = acquisitions.get(i);
 
-      if (!isInstalled) {
-        // Get download URL from mod page
-        let downloadUrl: string | null = null;
-        
-        // Check for cancellation before fetching download URL
+      if (acquisition) {
+        const acquired = await acquisition;
+
+        // Check for cancellation after download
         if (abortSignal?.aborted) {
           console.log("[applyModPack] Application cancelled by user");
           break;
         }
-        
-        // Try to get download URL from mod page
-        downloadUrl = await getDownloadUrl(modPackMod.id, modPackMod.version, modPackMod.url);
-        
-        if (!downloadUrl) {
-          console.warn(`[applyModPack] Could not get download URL for ${modPackMod.id}`);
-        } else {
-          console.log(`[applyModPack] Got download URL for ${modPackMod.id}: ${downloadUrl}`);
-        }
 
-        if (downloadUrl && downloadUrl.trim() !== '') {
-          // Check for cancellation before download
-          if (abortSignal?.aborted) {
-            console.log("[applyModPack] Application cancelled by user");
-            break;
-          }
-          
-          console.log(`[applyModPack] Downloading ${modPackMod.id} from ${downloadUrl}`);
-          try {
-            await apiDownloadMod(modPackMod.id, downloadUrl, modsPath);
-            
-            // Reindex the newly downloaded mod
-            try {
-              await invoke("reindex_mod", { modsPath, modId: modPackMod.id });
-            } catch (reindexError) {
-              console.warn(`[applyModPack] Failed to reindex ${modPackMod.id}:`, reindexError);
-            }
-            
-            // Check for cancellation after download
-            if (abortSignal?.aborted) {
-              console.log("[applyModPack] Application cancelled by user");
-              break;
-            }
-            
-            showToast?.(`Downloaded ${modPackMod.id}`, "success", 3000);
-          } catch (error) {
-            // Check if error is due to cancellation
-            if (abortSignal?.aborted) {
-              console.log("[applyModPack] Application cancelled by user during download");
-              break;
-            }
-            const errorMsg = error instanceof Error ? error.message : String(error);
-            console.error(`[applyModPack] Failed to download ${modPackMod.id}:`, error);
-            showToast?.(`Failed to download ${modPackMod.id}: ${errorMsg}`, "error", 6000);
-            onFailed?.(modPackMod.id, errorMsg);
-            result.failed++;
-            continue; // Skip enabling if download failed
-          }
+        if (acquired.kind === "cache") {
+          console.log(`[applyModPack] Installed ${modPackMod.id} from download cache`);
+          showToast?.(`Installed ${modPackMod.id} from cache`, "success", 3000);
+        } else if (acquired.kind === "downloaded") {
+          showToast?.(`Downloaded ${modPackMod.id}`, "success", 3000);
         } else {
-          // No download URL available and mod is not installed - this is a failure
-          console.warn(`[applyModPack] No download URL available for ${modPackMod.id}, cannot download`);
-          const errorMsg = `No download URL available for ${modPackMod.id}`;
-          showToast?.(errorMsg, "error", 6000);
+          const errorMsg = acquired.error;
+          showToast?.(acquired.downloadError ? `Failed to download ${modPackMod.id}: ${errorMsg}` : errorMsg, "error", 6000);
           onFailed?.(modPackMod.id, errorMsg);
           result.failed++;
           continue; // Skip enabling since we couldn't download
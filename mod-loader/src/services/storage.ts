@@ -7,6 +7,13 @@ export interface Settings {
   api_password?: string;
   theme: string;
   default_mod_pack_location?: string;
+  game_version?: string;
+  offline_mode?: boolean;
+  api_cache_ttl_minutes?: number;
+  parallel_downloads?: number;
+  enable_html_scraping?: boolean; // Fall back to ModDB mod pages when the API fails
+  watchlist_interval_minutes?: number; // 0 turns the background check off
+  game_install_path?: string; // Where the game itself is installed, for its version
 }
 
 export async function getSettings(): Promise<Settings> {
@@ -11,6 +11,30 @@ export interface ModInfo {
   dependencies?: any;
 }
 
+export type ModSide = 'universal' | 'client' | 'server';
+
+export interface ModSideInfo {
+  modId: string;
+  name: string;
+  version: string;
+  side: ModSide;
+  requiredOnClient: boolean;
+  requiredOnServer: boolean;
+}
+
+export interface PackSideReport {
+  side: ModSide;
+  wrongSide: ModSideInfo[]; // Pack mods that do not run on that side
+  unknown: string[]; // Pack mods that are not installed
+}
+
+export interface ClientRequirements {
+  required: ModSideInfo[];
+  optional: ModSideInfo[]; // Universal mods clients may skip, and client-only mods
+  serverOnly: ModSideInfo[];
+  unknown: string[]; // Mods of the set that are not installed
+}
+
 export interface ModDependency {
   modid: string;
   version?: string; // Optional version requirement
@@ -27,8 +51,198 @@ export interface ModStatus {
   latestVersion?: string;
   missingDependencies: ModDependency[];
   outdatedDependencies: OutdatedDependency[];
+  compatibilityNote?: string;
+  fetchOutcome?: FetchOutcome;
+  fetchError?: string;
+  responseAgeSecs?: number;
+  changelogs?: ReleaseChangelog[]; // Releases newer than the installed one, newest first
+  gameVersion?: string;
+  requiredGameVersion?: string; // From the modinfo's game/survival/creative dependency
+  meetsGameRequirement?: boolean; // Unset when either version is unknown
+  installedReleaseTags?: string[]; // ModDB game version tags of the installed release
+  taggedCompatible?: boolean; // Unset when unknown or the release is untagged
+  checkedAt?: number; // Unix seconds
+  held?: HeldMod; // Set while the mod is held at a version
+  knownIssues?: KnownIssue[]; // Incompatibility rules that match the enabled mods
+}
+
+export type RuleSeverity = 'info' | 'warning' | 'error';
+
+export interface ModMatcher {
+  modId: string;
+  minVersion?: string; // Inclusive
+  maxVersion?: string; // Inclusive
+}
+
+export interface IncompatibilityRule {
+  id: string;
+  mod: ModMatcher;
+  conflictsWith?: ModMatcher; // Without it the rule is a known issue of the mod on its own
+  severity: RuleSeverity;
+  note: string;
+  link?: string;
+  source?: string; // Shared file the rule was imported from
+}
+
+export interface RuleMatch {
+  rule: IncompatibilityRule;
+  modVersion: string;
+  conflictingVersion?: string;
+}
+
+export interface KnownIssue {
+  ruleId: string;
+  severity: RuleSeverity;
+  note: string;
+  link?: string;
+  otherMod?: string;
+}
+
+export interface PackEnableReport {
+  applied: boolean; // False while incompatibilities need confirming
+  incompatibilities: RuleMatch[];
+}
+
+export interface HeldMod {
+  modId: string;
+  version: string;
+  reason?: string;
+  heldAt: number; // Unix seconds
+}
+
+export interface ReleaseChangelog {
+  version: string;
+  created?: string;
+  changelog?: string; // HTML
+  gameVersions: string[];
+  compatible?: boolean; // Unset when the game version is unknown
+}
+
+export interface UpdateDigestEntry {
+  modId: string;
+  name: string;
+  installedVersion: string;
+  latestVersion?: string;
+  steps: ReleaseChangelog[]; // Oldest first, ending at the latest version
+  newDependencies: Array<{ modid: string; version?: string; installed: boolean; since: string }>; // since: oldest step known to declare it
+  dependencyCheckError?: string;
+}
+
+export interface UpdateDigest {
+  gameVersion?: string;
+  entries: UpdateDigestEntry[];
+}
+
+export interface DependencyRequirement {
+  modId: string; // The mod that declares the dependency
+  minVersion?: string;
+}
+
+export interface DependencyPlanStep {
+  modId: string;
+  action: 'install' | 'upgrade';
+  fromVersion?: string;
+  toVersion: string;
+  requiredBy: DependencyRequirement[];
+}
+
+export type DependencyPlanProblem =
+  | { kind: 'unsatisfiable'; modId: string; minVersion: string; newestAvailable?: string; requiredBy: DependencyRequirement[] }
+  | { kind: 'unavailable'; modId: string; error: string; requiredBy: DependencyRequirement[] }
+  | { kind: 'cycle'; modIds: string[] };
+
+export interface DependencyPlan {
+  roots: string[];
+  steps: DependencyPlanStep[]; // Dependencies before the mods that need them
+  satisfied: Array<{ modId: string; installedVersion: string; enabled: boolean }>;
+  problems: DependencyPlanProblem[];
+  warnings: string[];
+}
+
+// What disable/delete should do with enabled mods that depend on the targets
+export type DependentsAction = 'ask' | 'proceed' | 'cascade' | 'keep';
+
+export interface RemovalReport {
+  wouldBreak: Array<{ modId: string; name: string; requires: string[] }>;
+  applied: boolean; // false when nothing was done because dependents would break
+  affected: string[];
+  kept: string[]; // Targets left in place because enabled mods need them
+}
+
+export interface PlannedUpdate {
+  modId: string;
+  name: string;
+  fromVersion: string;
+  toVersion: string;
+}
+
+export interface UpdatePlan {
+  gameVersion?: string;
+  updates: PlannedUpdate[];
+  held: PlannedUpdate[]; // Updates left out because the mod is held
+  dependencies: DependencyPlan; // Dependencies the new releases need
+  warnings: string[];
 }
 
+export type AssetOverlapKind = 'sharedVanillaOverride' | 'modConflict';
+
+export interface AssetConflict {
+  modA: string;
+  nameA: string;
+  modB: string;
+  nameB: string;
+  kind: AssetOverlapKind; // modConflict as soon as any shared path is outside the base game domains
+  vanillaOverrides: string[];
+  modConflicts: string[];
+}
+
+export interface AssetConflictReport {
+  modsScanned: number;
+  conflicts: AssetConflict[]; // Mod conflicts first
+  errors: string[];
+}
+
+export interface PatchRef {
+  modId: string;
+  patchFile: string; // Asset path of the file the patch is in
+  index: number; // Position of the patch in that file
+  op: string;
+  path: string;
+  file: string; // Target as written in the patch
+}
+
+export interface PatchReport {
+  modsScanned: number;
+  patchesChecked: number;
+  gameAssetsPath?: string;
+  missingTargets: { patch: PatchRef; reason: string }[];
+  disabledDependencies: { patch: PatchRef; disabledMod: string; reason: string }[];
+  overlaps: { file: string; path: string; patches: PatchRef[] }[]; // Same place patched by different mods
+  errors: string[];
+  warnings: string[];
+}
+
+export interface UpdateBatchEntry {
+  modId: string;
+  action: 'update' | 'install';
+  fromVersion?: string;
+  toVersion: string;
+  originalPath?: string;
+  backupPath?: string;
+  installedPath: string;
+  installedHash?: string; // SHA-256 of the installed file
+}
+
+export interface UpdateBatch {
+  id: string;
+  modsPath: string;
+  createdAt: number; // Unix seconds
+  entries: UpdateBatchEntry[];
+}
+
+// How ModDB data was obtained; "cached" data carries its age
+export type FetchOutcome = 'fresh' | 'retried' | 'failed' | 'cached';
+
 export interface Mod {
   id: string;
   name: string;
@@ -57,6 +271,75 @@ export interface ModSearchResult {
   total: number;
   page: number;
   per_page: number;
+  total_pages?: number;
+  fetch_outcome?: FetchOutcome;
+  age_secs?: number;
+}
+
+export interface ModRelease {
+  mainfile: string;
+  modversion?: string;
+  tags: string[]; // Game versions, e.g. "v1.19.8"
+  fileid?: number;
+  created?: string;
+  releaseid?: number;
+  filename?: string;
+  modidstr?: string;
+  downloads?: number;
+  changelog?: string; // HTML
+}
+
+export interface ModScreenshot {
+  fileid?: number;
+  mainfile?: string;
+  filename?: string;
+  created?: string;
+}
+
+export interface ModDetails {
+  id?: number;
+  modid?: string;
+  name?: string;
+  description?: string; // HTML
+  authors: string[];
+  side?: string;
+  mod_type?: string;
+  tags: string[];
+  downloads?: number;
+  follows?: number;
+  comments?: number;
+  logo_url?: string;
+  screenshots: ModScreenshot[];
+  page_url?: string;
+  homepage_url?: string;
+  source_url?: string;
+  issue_tracker_url?: string;
+  wiki_url?: string;
+  trailer_video_url?: string;
+  created?: string;
+  last_modified?: string;
+  releases: ModRelease[]; // Newest first
+  fetch_outcome: FetchOutcome;
+  age_secs: number;
+}
+
+export type DownloadState = "queued" | "downloading" | "verifying" | "done" | "failed";
+
+export interface DownloadItem {
+  id: string;
+  mod_id: string;
+  version?: string;
+  download_url?: string; // As requested
+  resolved_url?: string; // Where the source said to download from
+  mods_path: string;
+  expected_hash?: string;
+  override_hold: boolean; // Installs even if the mod is held at another version
+  state: DownloadState;
+  attempts: number;
+  error?: string;
+  path?: string; // Installed zip once done
+  added_at: number;
+  updated_at: number;
 }
 
 export interface ModPackMod {
@@ -64,6 +347,42 @@ export interface ModPackMod {
   version: string;
   url?: string; // Optional URL to download the mod directly
   hash?: string; // SHA256 hash of the mod file for verification
+  source?: ModSource; // Where to get the mod; inferred from url when absent
+}
+
+// Where a mod comes from; packs can mix sources
+export type ModSource =
+  | { type: "moddb"; id?: string } // id: numeric ModDB id when it differs from the modid
+  | { type: "url"; url: string }
+  | { type: "local"; path: string } // Folder of zips or a single zip
+  | { type: "scrape"; page_url?: string } // Latest file linked from a ModDB page
+  | { type: "github"; repo: string; base_url?: string; prereleases?: boolean } // repo: "owner/name"
+  | { type: "gitea"; repo: string; base_url: string; prereleases?: boolean }
+  | { type: "repository"; url: string }; // Static repository: folder, file:// or http(s):// URL of its index.json
+
+// Static mod repository index (index.json)
+export interface RepositoryRelease {
+  version: string;
+  file: string; // Relative to the index
+  sha256: string;
+  size: number;
+  dependencies: Record<string, string>; // modid -> minimum version, "" for any
+}
+
+export interface RepositoryMod {
+  modid: string;
+  name: string;
+  description?: string;
+  authors: string[];
+  side?: string;
+  releases: RepositoryRelease[]; // Newest first
+}
+
+export interface RepositoryIndex {
+  format: number;
+  name?: string;
+  generated_at?: number;
+  mods: RepositoryMod[];
 }
 
 export interface ModPackLinks {
@@ -97,3 +416,28 @@ export interface ModPack {
   mods: ModPackMod[];
   metadata: ModPackMetadata;
 }
+
+// Watchlist of ModDB mods that are not installed
+export interface SeenRelease {
+  version?: string;
+  created?: string;
+  game_versions: string[];
+}
+
+export interface WatchedMod {
+  mod_id: string;
+  name?: string;
+  added_at: number;
+  last_checked?: number;
+  last_seen_release?: SeenRelease;
+  compatible_release?: SeenRelease; // Newest release tagged for our game version
+  last_error?: string;
+}
+
+// Payload of the "watchlist-new-release" and "watchlist-compatible-release" events
+export interface WatchEvent {
+  mod_id: string;
+  name?: string;
+  release: SeenRelease;
+  game_version?: string;
+}
@@ -83,7 +83,7 @@ describe('API and ModPack Integration Tests', () => {
             releases: [
               {
                 mainfile: 'https://mods.vintagestory.at/download/searched-mod.zip',
-                version: '1.0.0',
+                modversion: '1.0.0',
               },
             ],
           },
@@ -138,6 +138,7 @@ describe('API and ModPack Integration Tests', () => {
         modId: 'searched-mod',
         downloadUrl: 'https://mods.vintagestory.at/download/searched-mod.zip',
         modsPath,
+        version: '1.0.0',
       });
     });
   });
@@ -184,11 +185,13 @@ describe('API and ModPack Integration Tests', () => {
       expect(invoke).toHaveBeenCalledWith('get_mod_download_url', {
         modId: 'mod1',
         modUrl: 'https://mods.vintagestory.at/api/mod/mod1',
+        version: '1.0.0',
       });
       expect(invoke).toHaveBeenCalledWith('download_mod', {
         modId: 'mod1',
         downloadUrl: 'https://mods.vintagestory.at/download/mod1.zip',
         modsPath,
+        version: '1.0.0',
       });
     });
 
@@ -232,8 +235,54 @@ describe('API and ModPack Integration Tests', () => {
         modId: 'mod1',
         downloadUrl: 'https://mods.vintagestory.at/download/mod1.zip',
         modsPath,
+        version: '1.0.0',
+      });
+    });
+
+    it('should resolve mods through the source recorded in the pack', async () => {
+      const modsPath = '/path/to/mods';
+      const modPack: ModPack = {
+        name: 'Test Pack',
+        version: '1.0.0',
+        description: 'Test',
+        mods: [
+          {
+            id: 'mod1',
+            version: '1.0.0',
+            source: { type: 'local', path: '/srv/mods' },
+          },
+        ],
+        metadata: {},
+      };
+
+      (invoke as ReturnType<typeof vi.fn>).mockImplementation((cmd: string) => {
+        if (cmd === 'get_mod_list') {
+          return Promise.resolve([]);
+        }
+        if (cmd === 'get_mod_download_url') {
+          return Promise.resolve('/srv/mods/mod1.zip');
+        }
+        if (cmd === 'download_mod') {
+          return Promise.resolve('/path/to/mods/mod1.zip');
+        }
+        return Promise.resolve(undefined);
+      });
+
+      const result = await applyModPack(modPack, modsPath);
+
+      expect(result.success).toBe(1);
+      expect(invoke).toHaveBeenCalledWith('get_mod_download_url', {
+        modId: 'mod1',
+        version: '1.0.0',
+        source: { type: 'local', path: '/srv/mods' },
+      });
+      expect(invoke).toHaveBeenCalledWith('download_mod', {
+        modId: 'mod1',
+        downloadUrl: '/srv/mods/mod1.zip',
+        modsPath,
+        version: '1.0.0',
+        source: { type: 'local', path: '/srv/mods' },
       });
     });
   });
 });
-
@@ -27,7 +27,7 @@ describe('API Service', () => {
             releases: [
               {
                 mainfile: 'https://example.com/mod.zip',
-                version: '1.0.0',
+                modversion: '1.0.0',
               },
             ],
           },